@@ -0,0 +1,34 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+#[allow(dead_code)]
+#[path = "src/config/types.rs"]
+mod types;
+
+#[allow(dead_code)]
+#[path = "src/config/codegen.rs"]
+mod codegen;
+
+/// 默认的消息定义文件
+const DEFAULT_MESSAGE_XML: &str = "config/sse-message.xml";
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=src/config/types.rs");
+    println!("cargo:rerun-if-changed=src/config/codegen.rs");
+    println!("cargo:rerun-if-env-changed=SSE_TDGW_MESSAGE_XML");
+
+    let xml_path = env::var("SSE_TDGW_MESSAGE_XML").unwrap_or_else(|_| DEFAULT_MESSAGE_XML.to_string());
+    println!("cargo:rerun-if-changed={}", xml_path);
+
+    let xml = fs::read_to_string(&xml_path)
+        .unwrap_or_else(|e| panic!("failed to read message config {}: {}", xml_path, e));
+    let config: types::MessageConfig = quick_xml::de::from_str(&xml)
+        .unwrap_or_else(|e| panic!("failed to parse message config {}: {}", xml_path, e));
+    let source = codegen::generate(&config)
+        .unwrap_or_else(|e| panic!("failed to generate typed messages from {}: {}", xml_path, e));
+
+    let out_dir = PathBuf::from(env::var("OUT_DIR").expect("OUT_DIR not set"));
+    fs::write(out_dir.join("sse_messages.rs"), source).expect("failed to write generated messages");
+}
@@ -46,7 +46,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("\n=== 编解码测试 ===");
     
     // 示例1: 登录消息 (Logon - 消息类型40)
-    if let Some(_) = config_manager.get_message_def(40) {
+    if config_manager.get_message_def(40).is_some() {
         println!("\n1. 测试登录消息 (Logon - 类型40)");
         
         let mut logon_message = Message::new(40, 1001);
@@ -74,7 +74,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         println!("  解码后消息类型: {}", decoded_message.msg_type);
         println!("  解码后序列号: {}", decoded_message.seq_num);
         println!("  解码后字段数: {}", decoded_message.fields.len());
-        println!("  解码后消息内容: {}", decoded_message.to_string());
+        println!("  解码后消息内容: {}", decoded_message);
         
         // 验证往返一致性
         assert_eq!(logon_message.msg_type, decoded_message.msg_type, "消息类型不匹配");
@@ -85,7 +85,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     }
     
     // 示例2: 新订单消息 (NewOrderSingle - 消息类型58)
-    if let Some(_) = config_manager.get_message_def(58) {
+    if config_manager.get_message_def(58).is_some() {
         println!("\n2. 测试新订单消息 (NewOrderSingle - 类型58)");
         
         let mut order_message = Message::new(58, 1002);
@@ -113,7 +113,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         println!("  解码后消息类型: {}", decoded_message.msg_type);
         println!("  解码后序列号: {}", decoded_message.seq_num);
         println!("  解码后字段数: {}", decoded_message.fields.len());
-        println!("  解码后消息内容: {}", decoded_message.to_string());
+        println!("  解码后消息内容: {}", decoded_message);
         
         // 验证关键字段
         if let Some(FieldValue::U32(biz_id)) = decoded_message.get_field("BizID") {
@@ -134,7 +134,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     }
     
     // 示例3: 心跳消息 (Heartbeat - 消息类型42)
-    if let Some(_) = config_manager.get_message_def(42) {
+    if config_manager.get_message_def(42).is_some() {
         println!("\n3. 测试心跳消息 (Heartbeat - 类型42)");
         
         let heartbeat_message = Message::new(42, 1003);
@@ -154,13 +154,13 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         
         println!("  解码后消息类型: {}", decoded_message.msg_type);
         println!("  解码后序列号: {}", decoded_message.seq_num);
-        println!("  解码后消息内容: {}", decoded_message.to_string());
+        println!("  解码后消息内容: {}", decoded_message);
         
         println!("  ✓ 往返编解码验证成功");
     }
     
     // 示例4: 包含扩展字段的消息测试
-    if let Some(_) = config_manager.get_message_def(58) {
+    if config_manager.get_message_def(58).is_some() {
         println!("\n4. 测试包含扩展字段的订单消息");
         
         let mut extended_order = Message::new(58, 1004);
@@ -194,7 +194,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         for (field_name, field_value) in &decoded_message.fields {
             println!("    {}: {:?}", field_name, field_value);
         }
-        println!("  解码后消息内容: {}", decoded_message.to_string());
+        println!("  解码后消息内容: {}", decoded_message);
         
         println!("  ✓ 扩展字段编解码验证成功");
     }
@@ -1,6 +1,6 @@
 use sse_tdgw_binary::codec::MessageEncoder;
 use sse_tdgw_binary::config::ConfigManager;
-use sse_tdgw_binary::message::{Message, FieldValue};
+use sse_tdgw_binary::message::{Message, FieldValue, Record};
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     // 配置字符串
@@ -85,16 +85,14 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     
     // 创建数组数据
     let sync_requests = vec![
-        vec![
-            FieldValue::Str("PBU00001".to_string()),  // Pbu
-            FieldValue::U32(1),                        // SetID
-            FieldValue::U64(100),                      // BeginReportIndex
-        ],
-        vec![
-            FieldValue::Str("PBU00002".to_string()),  // Pbu
-            FieldValue::U32(2),                        // SetID
-            FieldValue::U64(200),                      // BeginReportIndex
-        ],
+        Record::new()
+            .with("Pbu", "PBU00001")
+            .with("SetID", 1u32)
+            .with("BeginReportIndex", 100u64),
+        Record::new()
+            .with("Pbu", "PBU00002")
+            .with("SetID", 2u32)
+            .with("BeginReportIndex", 200u64),
     ];
     
     sync_message.add_field("SyncRequests".to_string(), FieldValue::Array(sync_requests));
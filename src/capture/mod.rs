@@ -0,0 +1,324 @@
+pub mod pcap;
+pub mod packet;
+pub(crate) mod stream;
+
+pub use pcap::{CaptureReader, Packet};
+pub use packet::{LinkType, TcpSegment};
+
+use std::collections::HashMap;
+use std::fmt;
+use std::net::SocketAddr;
+use std::path::Path;
+use std::time::Duration;
+
+use crate::codec::{ChecksumMode, MessageDecoder};
+use crate::config::manager::ConfigManager;
+use crate::message::Message;
+use crate::util::{CaptureResult, ContextResult};
+use stream::{StreamBuffer, StreamOutput};
+
+/// 消息的传输方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    /// 发起连接的一方（券商端）发往网关
+    ClientToServer,
+    /// 网关发往券商端
+    ServerToClient,
+}
+
+impl Direction {
+    /// 相反的方向
+    pub fn opposite(self) -> Direction {
+        match self {
+            Direction::ClientToServer => Direction::ServerToClient,
+            Direction::ServerToClient => Direction::ClientToServer,
+        }
+    }
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Direction::ClientToServer => write!(f, "->"),
+            Direction::ServerToClient => write!(f, "<-"),
+        }
+    }
+}
+
+/// 从抓包中切出的一帧及其解码结果
+#[derive(Debug)]
+pub struct CapturedMessage {
+    /// 收到这一帧最后一个字节的时间，自 UNIX 纪元起
+    pub timestamp: Duration,
+    pub direction: Direction,
+    /// 完整的帧
+    pub frame: Vec<u8>,
+    /// 解码结果，失败时带有出错的字段和偏移
+    pub result: ContextResult<Message>,
+}
+
+/// 重组 TCP 流时发现的问题
+#[derive(Debug, Clone, PartialEq)]
+pub enum StreamIssue {
+    /// 重复收到已经收到过的字节
+    Retransmission { bytes: usize },
+    /// 缺少从 `expected_seq` 到 `seq` 的 `missing` 个字节，缺口之前 `discarded` 个未成帧的字节被丢弃
+    Gap { expected_seq: u32, seq: u32, missing: usize, discarded: usize },
+    /// 帧头部不合理，丢弃缓冲区中的 `bytes` 个字节
+    Framing { bytes: usize, reason: String },
+    /// 抓包结束时还有 `bytes` 个字节不够一帧
+    Truncated { bytes: usize },
+}
+
+impl fmt::Display for StreamIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StreamIssue::Retransmission { bytes } => write!(f, "retransmission of {} bytes", bytes),
+            StreamIssue::Gap { expected_seq, seq, missing, discarded } => write!(
+                f, "gap of {} bytes (expected seq {}, got {}), discarded {} buffered bytes",
+                missing, expected_seq, seq, discarded,
+            ),
+            StreamIssue::Framing { bytes, reason } => write!(f, "framing error, discarded {} bytes: {}", bytes, reason),
+            StreamIssue::Truncated { bytes } => write!(f, "capture ended inside a frame, {} bytes left", bytes),
+        }
+    }
+}
+
+/// 某个方向上发现的流问题
+#[derive(Debug, Clone, PartialEq)]
+pub struct StreamEvent {
+    pub timestamp: Duration,
+    pub direction: Direction,
+    pub issue: StreamIssue,
+}
+
+/// 一个 TCP 连接上的全部消息，按到达顺序排列
+#[derive(Debug)]
+pub struct Connection {
+    /// 发起连接的一方
+    pub client: SocketAddr,
+    /// 网关
+    pub server: SocketAddr,
+    pub messages: Vec<CapturedMessage>,
+    pub events: Vec<StreamEvent>,
+}
+
+impl Connection {
+    /// 某个方向上的消息
+    pub fn messages_from(&self, direction: Direction) -> impl Iterator<Item = &CapturedMessage> {
+        self.messages.iter().filter(move |message| message.direction == direction)
+    }
+
+    /// 解码失败的消息
+    pub fn failures(&self) -> impl Iterator<Item = &CapturedMessage> {
+        self.messages.iter().filter(|message| message.result.is_err())
+    }
+}
+
+/// 整个抓包文件的解析结果
+#[derive(Debug, Default)]
+pub struct CaptureReport {
+    /// 按首次出现的顺序排列的连接
+    pub connections: Vec<Connection>,
+    /// 读取的数据包数
+    pub packets: usize,
+    /// 不是 TCP 或不属于指定端口而被跳过的数据包数
+    pub skipped: usize,
+}
+
+impl CaptureReport {
+    /// 是否有流问题或解码失败的消息
+    pub fn has_problems(&self) -> bool {
+        self.connections.iter().any(|connection| !connection.events.is_empty() || connection.failures().next().is_some())
+    }
+}
+
+/// 连接的重组状态
+#[derive(Debug)]
+struct ConnectionState {
+    connection: Connection,
+    /// 按 `Direction` 的顺序排列的两个方向
+    streams: [StreamBuffer; 2],
+    /// 是否收到过数据，收到数据之后的 SYN 表示同一地址对上的新连接
+    has_data: bool,
+    last_timestamp: Duration,
+}
+
+/// 从抓包的数据包中重组 TDGW 会话并逐帧解码
+///
+/// 每个 TCP 连接的两个方向分别按序列号重组后按头部中的消息体长度分帧；
+/// 发起连接的一方按 SYN 判断，抓包中没有握手时端口号较小的一方视为网关，也可以用 `with_port` 指定网关端口
+///
+/// ```ignore
+/// let mut decoder = CaptureDecoder::new(&config_manager).with_port(9000);
+/// for packet in CaptureReader::open("gateway.pcap")? {
+///     decoder.feed(&packet?);
+/// }
+/// for connection in decoder.finish().connections {
+///     println!("{} -> {}: {} messages", connection.client, connection.server, connection.messages.len());
+/// }
+/// ```
+#[derive(Debug)]
+pub struct CaptureDecoder<'a> {
+    config_manager: &'a ConfigManager,
+    port: Option<u16>,
+    checksum_mode: ChecksumMode,
+    connections: Vec<ConnectionState>,
+    /// (客户端, 网关) 到当前连接下标的映射
+    active: HashMap<(SocketAddr, SocketAddr), usize>,
+    packets: usize,
+    skipped: usize,
+}
+
+impl<'a> CaptureDecoder<'a> {
+    pub fn new(config_manager: &'a ConfigManager) -> Self {
+        Self {
+            config_manager,
+            port: None,
+            checksum_mode: ChecksumMode::Strict,
+            connections: Vec::new(),
+            active: HashMap::new(),
+            packets: 0,
+            skipped: 0,
+        }
+    }
+
+    /// 只解析与该端口的连接，该端口一侧为网关
+    pub fn with_port(mut self, port: u16) -> Self {
+        self.port = Some(port);
+        self
+    }
+
+    /// 设置校验和的处理方式，默认严格校验
+    pub fn with_checksum_mode(mut self, checksum_mode: ChecksumMode) -> Self {
+        self.checksum_mode = checksum_mode;
+        self
+    }
+
+    /// 处理一个数据包
+    pub fn feed(&mut self, packet: &Packet) {
+        self.packets += 1;
+        let Some(segment) = TcpSegment::parse(packet.link_type, &packet.data) else {
+            self.skipped += 1;
+            return;
+        };
+        let Some((client, server, direction)) = self.endpoints(&segment) else {
+            self.skipped += 1;
+            return;
+        };
+
+        let index = match self.active.get(&(client, server)) {
+            Some(&index) if !(segment.syn && self.connections[index].has_data) => index,
+            previous => {
+                if let Some(&index) = previous {
+                    self.close(index);
+                }
+                self.connections.push(ConnectionState {
+                    connection: Connection { client, server, messages: Vec::new(), events: Vec::new() },
+                    streams: Default::default(),
+                    has_data: false,
+                    last_timestamp: packet.timestamp,
+                });
+                self.active.insert((client, server), self.connections.len() - 1);
+                self.connections.len() - 1
+            },
+        };
+
+        let mut output = Vec::new();
+        let state = &mut self.connections[index];
+        state.has_data |= !segment.payload.is_empty();
+        state.last_timestamp = packet.timestamp;
+        state.streams[direction as usize].push(packet.timestamp, &segment, &mut output);
+        self.collect(index, direction, output);
+    }
+
+    /// 处理完所有数据包，报告未补齐的缺口和不完整的帧
+    pub fn finish(mut self) -> CaptureReport {
+        let indices: Vec<usize> = self.active.values().copied().collect();
+        for index in indices {
+            self.close(index);
+        }
+        CaptureReport {
+            connections: self.connections.into_iter().map(|state| state.connection).collect(),
+            packets: self.packets,
+            skipped: self.skipped,
+        }
+    }
+
+    /// 判断客户端、网关以及报文段的方向
+    fn endpoints(&self, segment: &TcpSegment) -> Option<(SocketAddr, SocketAddr, Direction)> {
+        let from_client = match self.port {
+            Some(port) if segment.dst.port() == port => true,
+            Some(port) if segment.src.port() == port => false,
+            Some(_) => return None,
+            None if self.active.contains_key(&(segment.src, segment.dst)) => true,
+            None if self.active.contains_key(&(segment.dst, segment.src)) => false,
+            None if segment.syn => !segment.ack,
+            None => segment.src.port() >= segment.dst.port(),
+        };
+        Some(match from_client {
+            true => (segment.src, segment.dst, Direction::ClientToServer),
+            false => (segment.dst, segment.src, Direction::ServerToClient),
+        })
+    }
+
+    fn close(&mut self, index: usize) {
+        let mut output = Vec::new();
+        let timestamp = self.connections[index].last_timestamp;
+        for direction in [Direction::ClientToServer, Direction::ServerToClient] {
+            self.connections[index].streams[direction as usize].finish(timestamp, &mut output);
+            self.collect(index, direction, std::mem::take(&mut output));
+        }
+    }
+
+    fn collect(&mut self, index: usize, direction: Direction, output: Vec<StreamOutput>) {
+        let connection = &mut self.connections[index].connection;
+        for item in output {
+            match item {
+                StreamOutput::Frame(timestamp, frame) => {
+                    let result = MessageDecoder::new(self.config_manager, &frame)
+                        .with_checksum_mode(self.checksum_mode)
+                        .decode_with_context();
+                    connection.messages.push(CapturedMessage { timestamp, direction, frame, result });
+                },
+                StreamOutput::Issue(timestamp, issue) => {
+                    connection.events.push(StreamEvent { timestamp, direction, issue });
+                },
+            }
+        }
+    }
+}
+
+/// 读取抓包文件并解码其中的全部 TDGW 会话
+pub fn decode_capture<P: AsRef<Path>>(config_manager: &ConfigManager, path: P) -> CaptureResult<CaptureReport> {
+    let mut decoder = CaptureDecoder::new(config_manager);
+    for packet in CaptureReader::open(path)? {
+        decoder.feed(&packet?);
+    }
+    Ok(decoder.finish())
+}
+
+/// 将抓包时间格式化为 UTC 时间，精确到微秒，如 `2024-01-05T01:30:00.123456Z`
+pub fn format_timestamp(timestamp: Duration) -> String {
+    let seconds = timestamp.as_secs();
+    let (year, month, day) = civil_date(seconds / 86_400);
+    let time = seconds % 86_400;
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:06}Z",
+        year, month, day, time / 3600, time / 60 % 60, time % 60, timestamp.subsec_micros(),
+    )
+}
+
+/// 自 UNIX 纪元起的天数对应的公历日期
+pub(crate) fn civil_date(days: u64) -> (i64, i64, i64) {
+    // 按公历从 0000-03-01 起的 400 年周期换算日期
+    let days = days as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    (year_of_era + era * 400 + (month <= 2) as i64, month, day)
+}
@@ -0,0 +1,142 @@
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+
+use byteorder::{BigEndian, ByteOrder, LittleEndian};
+
+/// 抓包文件中数据包的链路层类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinkType {
+    /// BSD 环回接口，4 字节的地址族
+    Null,
+    Ethernet,
+    /// 没有链路层头部的 IP 包
+    Raw,
+    /// Linux cooked capture v1（`any` 接口）
+    LinuxSll,
+    /// Linux cooked capture v2
+    LinuxSll2,
+    Other(u32),
+}
+
+impl From<u32> for LinkType {
+    fn from(value: u32) -> Self {
+        match value {
+            0 => LinkType::Null,
+            1 => LinkType::Ethernet,
+            12 | 101 | 228 | 229 => LinkType::Raw,
+            113 => LinkType::LinuxSll,
+            276 => LinkType::LinuxSll2,
+            other => LinkType::Other(other),
+        }
+    }
+}
+
+const ETHERTYPE_IPV4: u16 = 0x0800;
+const ETHERTYPE_IPV6: u16 = 0x86DD;
+const ETHERTYPE_VLAN: u16 = 0x8100;
+const ETHERTYPE_QINQ: u16 = 0x88A8;
+const IP_PROTOCOL_TCP: u8 = 6;
+
+/// 一个 TCP 报文段
+#[derive(Debug, Clone)]
+pub struct TcpSegment {
+    pub src: SocketAddr,
+    pub dst: SocketAddr,
+    pub seq: u32,
+    pub syn: bool,
+    pub ack: bool,
+    pub fin: bool,
+    pub rst: bool,
+    pub payload: Vec<u8>,
+}
+
+impl TcpSegment {
+    /// 从链路层开始解析，不是 TCP 的数据包和 IP 分片返回 `None`
+    pub fn parse(link_type: LinkType, data: &[u8]) -> Option<Self> {
+        let (ethertype, ip) = match link_type {
+            LinkType::Ethernet => {
+                let mut offset = 12;
+                let mut ethertype = BigEndian::read_u16(data.get(offset..offset + 2)?);
+                while matches!(ethertype, ETHERTYPE_VLAN | ETHERTYPE_QINQ) {
+                    offset += 4;
+                    ethertype = BigEndian::read_u16(data.get(offset..offset + 2)?);
+                }
+                (ethertype, data.get(offset + 2..)?)
+            },
+            LinkType::Null => {
+                // 地址族按抓包主机的字节序存放，IPv6 在各系统上的取值不同
+                let family = data.get(..4)?;
+                let ipv4 = LittleEndian::read_u32(family) == 2 || BigEndian::read_u32(family) == 2;
+                let ethertype = if ipv4 { ETHERTYPE_IPV4 } else { ETHERTYPE_IPV6 };
+                (ethertype, &data[4..])
+            },
+            LinkType::Raw => match data.first()? >> 4 {
+                4 => (ETHERTYPE_IPV4, data),
+                6 => (ETHERTYPE_IPV6, data),
+                _ => return None,
+            },
+            LinkType::LinuxSll => (BigEndian::read_u16(data.get(14..16)?), data.get(16..)?),
+            LinkType::LinuxSll2 => (BigEndian::read_u16(data.get(..2)?), data.get(20..)?),
+            LinkType::Other(_) => return None,
+        };
+
+        let (src, dst, tcp) = match ethertype {
+            ETHERTYPE_IPV4 => parse_ipv4(ip)?,
+            ETHERTYPE_IPV6 => parse_ipv6(ip)?,
+            _ => return None,
+        };
+        // 固定部分不完整的报文段无法解析
+        if tcp.len() < 20 {
+            return None;
+        }
+        let header_len = ((tcp[12] >> 4) as usize) * 4;
+        if header_len < 20 {
+            return None;
+        }
+        let flags = tcp[13];
+        Some(Self {
+            src: SocketAddr::new(src, BigEndian::read_u16(&tcp[0..2])),
+            dst: SocketAddr::new(dst, BigEndian::read_u16(&tcp[2..4])),
+            seq: BigEndian::read_u32(&tcp[4..8]),
+            fin: flags & 0x01 != 0,
+            syn: flags & 0x02 != 0,
+            rst: flags & 0x04 != 0,
+            ack: flags & 0x10 != 0,
+            // 被 snaplen 截断时只保留抓到的部分
+            payload: tcp.get(header_len..).unwrap_or_default().to_vec(),
+        })
+    }
+
+    /// 报文段占用的序列号个数，SYN 和 FIN 各占一个
+    pub fn seq_len(&self) -> u32 {
+        self.payload.len() as u32 + self.syn as u32 + self.fin as u32
+    }
+}
+
+/// 返回源地址、目的地址和 TCP 部分
+fn parse_ipv4(ip: &[u8]) -> Option<(IpAddr, IpAddr, &[u8])> {
+    let header_len = ((ip.first()? & 0x0F) as usize) * 4;
+    if header_len < 20 || ip.len() < header_len || ip[9] != IP_PROTOCOL_TCP {
+        return None;
+    }
+    // 分片的 IP 包无法单独解析出 TCP 报文段
+    let fragment = BigEndian::read_u16(&ip[6..8]);
+    if fragment & 0x2000 != 0 || fragment & 0x1FFF != 0 {
+        return None;
+    }
+    // 以太网最小帧长会在 IP 包后补零，按总长度截掉
+    let total = (BigEndian::read_u16(&ip[2..4]) as usize).clamp(header_len, ip.len());
+    let src = Ipv4Addr::new(ip[12], ip[13], ip[14], ip[15]);
+    let dst = Ipv4Addr::new(ip[16], ip[17], ip[18], ip[19]);
+    Some((src.into(), dst.into(), &ip[header_len..total]))
+}
+
+/// 只支持紧跟在固定头部后的 TCP，不解析扩展头部
+fn parse_ipv6(ip: &[u8]) -> Option<(IpAddr, IpAddr, &[u8])> {
+    if ip.len() < 40 || ip[6] != IP_PROTOCOL_TCP {
+        return None;
+    }
+    let total = (40 + BigEndian::read_u16(&ip[4..6]) as usize).min(ip.len());
+    let src = Ipv6Addr::from(<[u8; 16]>::try_from(&ip[8..24]).ok()?);
+    let dst = Ipv6Addr::from(<[u8; 16]>::try_from(&ip[24..40]).ok()?);
+    Some((src.into(), dst.into(), &ip[40..total]))
+}
@@ -0,0 +1,344 @@
+use std::fs::File;
+use std::io::{BufReader, ErrorKind, Read};
+use std::path::Path;
+use std::time::Duration;
+
+use byteorder::{BigEndian, ByteOrder, LittleEndian};
+
+use crate::capture::packet::LinkType;
+use crate::util::{CaptureError, CaptureResult};
+
+/// pcap 文件头的魔数，按写入方的字节序存放
+const PCAP_MICROS: u32 = 0xA1B2_C3D4;
+const PCAP_NANOS: u32 = 0xA1B2_3C4D;
+/// pcapng 各类块的类型
+const BLOCK_SECTION_HEADER: u32 = 0x0A0D_0D0A;
+const BLOCK_INTERFACE: u32 = 0x0000_0001;
+const BLOCK_OBSOLETE_PACKET: u32 = 0x0000_0002;
+const BLOCK_SIMPLE_PACKET: u32 = 0x0000_0003;
+const BLOCK_ENHANCED_PACKET: u32 = 0x0000_0006;
+const BYTE_ORDER_MAGIC: u32 = 0x1A2B_3C4D;
+/// 单个记录或块的长度上限，超过时认为文件已损坏
+const MAX_RECORD_LEN: usize = 256 * 1024 * 1024;
+
+/// 抓包文件中的一个数据包
+#[derive(Debug, Clone)]
+pub struct Packet {
+    /// 抓包时间，自 UNIX 纪元起
+    pub timestamp: Duration,
+    /// 链路层类型
+    pub link_type: LinkType,
+    /// 抓到的字节，可能被 snaplen 截断
+    pub data: Vec<u8>,
+}
+
+/// 文件格式及格式相关的状态
+#[derive(Debug)]
+enum Format {
+    Pcap {
+        big_endian: bool,
+        /// 每秒的时间戳单位数，微秒格式为 10^6，纳秒格式为 10^9
+        units_per_second: u64,
+        link_type: LinkType,
+    },
+    PcapNg {
+        big_endian: bool,
+        /// 当前段中按出现顺序排列的接口
+        interfaces: Vec<Interface>,
+    },
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Interface {
+    link_type: LinkType,
+    units_per_second: u64,
+    /// `if_tsoffset`，加到时间戳上的秒数
+    offset_seconds: i64,
+}
+
+/// 逐个读取 pcap 或 pcapng 文件中的数据包，按文件头的魔数自动识别格式和字节序
+///
+/// ```ignore
+/// for packet in CaptureReader::open("gateway.pcapng")? {
+///     let packet = packet?;
+///     println!("{:?} {} bytes", packet.timestamp, packet.data.len());
+/// }
+/// ```
+#[derive(Debug)]
+pub struct CaptureReader<R> {
+    reader: R,
+    format: Format,
+    /// 上一个数据包的时间，用于没有时间戳的 simple packet block
+    last_timestamp: Duration,
+    done: bool,
+}
+
+impl CaptureReader<BufReader<File>> {
+    /// 打开抓包文件
+    pub fn open<P: AsRef<Path>>(path: P) -> CaptureResult<Self> {
+        Self::new(BufReader::new(File::open(path)?))
+    }
+}
+
+impl<R: Read> CaptureReader<R> {
+    /// 读取文件头并识别格式
+    pub fn new(mut reader: R) -> CaptureResult<Self> {
+        let mut magic = [0u8; 4];
+        read_exact(&mut reader, &mut magic, "file header")?;
+
+        let format = if BigEndian::read_u32(&magic) == BLOCK_SECTION_HEADER {
+            let mut format = Format::PcapNg { big_endian: false, interfaces: Vec::new() };
+            read_section_header(&mut reader, &mut format)?;
+            format
+        } else {
+            let (big_endian, units_per_second) = match (BigEndian::read_u32(&magic), LittleEndian::read_u32(&magic)) {
+                (PCAP_MICROS, _) => (true, 1_000_000),
+                (_, PCAP_MICROS) => (false, 1_000_000),
+                (PCAP_NANOS, _) => (true, 1_000_000_000),
+                (_, PCAP_NANOS) => (false, 1_000_000_000),
+                (magic, _) => return Err(CaptureError::UnknownFormat(magic)),
+            };
+            // 版本号、时区、精度和 snaplen 不影响解析
+            let mut header = [0u8; 20];
+            read_exact(&mut reader, &mut header, "file header")?;
+            let link_type = LinkType::from(read_u32(&header[16..], big_endian) & 0x0FFF_FFFF);
+            Format::Pcap { big_endian, units_per_second, link_type }
+        };
+
+        Ok(Self { reader, format, last_timestamp: Duration::ZERO, done: false })
+    }
+
+    fn read_pcap_record(&mut self) -> CaptureResult<Option<Packet>> {
+        let Format::Pcap { big_endian, units_per_second, link_type } = self.format else {
+            unreachable!("read_pcap_record called on a pcapng file");
+        };
+        let mut header = [0u8; 16];
+        if !read_or_eof(&mut self.reader, &mut header, "packet record header")? {
+            return Ok(None);
+        }
+        let seconds = read_u32(&header[0..], big_endian) as u64;
+        let fraction = read_u32(&header[4..], big_endian) as u64;
+        let captured = read_u32(&header[8..], big_endian) as usize;
+        if captured > MAX_RECORD_LEN {
+            return Err(CaptureError::Truncated(format!("packet record of {} bytes", captured)));
+        }
+        let mut data = vec![0u8; captured];
+        read_exact(&mut self.reader, &mut data, "packet data")?;
+        let timestamp = Duration::from_secs(seconds) + fraction_to_duration(fraction, units_per_second);
+        Ok(Some(Packet { timestamp, link_type, data }))
+    }
+
+    fn read_pcapng_block(&mut self) -> CaptureResult<Option<Packet>> {
+        loop {
+            let mut header = [0u8; 8];
+            if !read_or_eof(&mut self.reader, &mut header, "block header")? {
+                return Ok(None);
+            }
+            if BigEndian::read_u32(&header) == BLOCK_SECTION_HEADER {
+                read_section_body(&mut self.reader, &mut self.format, &header[4..])?;
+                continue;
+            }
+
+            let Format::PcapNg { big_endian, interfaces } = &mut self.format else {
+                unreachable!("read_pcapng_block called on a pcap file");
+            };
+            let big_endian = *big_endian;
+            let block_type = read_u32(&header, big_endian);
+            let body = read_block_body(&mut self.reader, read_u32(&header[4..], big_endian) as usize)?;
+
+            let packet = match block_type {
+                BLOCK_INTERFACE => {
+                    interfaces.push(parse_interface(&body, big_endian)?);
+                    continue;
+                },
+                BLOCK_ENHANCED_PACKET | BLOCK_OBSOLETE_PACKET => {
+                    if body.len() < 20 {
+                        return Err(CaptureError::InvalidBlock(format!("packet block of {} bytes", body.len())));
+                    }
+                    // 旧版 packet block 的接口号只有 16 位，后面是 16 位的丢包计数
+                    let interface_id = match block_type {
+                        BLOCK_ENHANCED_PACKET => read_u32(&body, big_endian) as usize,
+                        _ => read_u16(&body, big_endian) as usize,
+                    };
+                    let interface = *interfaces.get(interface_id).ok_or_else(|| {
+                        CaptureError::InvalidBlock(format!("packet refers to undefined interface {}", interface_id))
+                    })?;
+                    let ticks = ((read_u32(&body[4..], big_endian) as u64) << 32) | read_u32(&body[8..], big_endian) as u64;
+                    let captured = read_u32(&body[12..], big_endian) as usize;
+                    let data = body.get(20..20 + captured).ok_or_else(|| {
+                        CaptureError::InvalidBlock(format!("captured length {} exceeds block", captured))
+                    })?;
+                    let since_epoch = Duration::from_secs(ticks / interface.units_per_second)
+                        + fraction_to_duration(ticks % interface.units_per_second, interface.units_per_second);
+                    let timestamp = match interface.offset_seconds {
+                        offset if offset >= 0 => since_epoch.checked_add(Duration::from_secs(offset as u64)).ok_or_else(|| {
+                            CaptureError::InvalidBlock(format!("timestamp overflows with interface offset {}", offset))
+                        })?,
+                        offset => since_epoch.saturating_sub(Duration::from_secs(offset.unsigned_abs())),
+                    };
+                    Packet { timestamp, link_type: interface.link_type, data: data.to_vec() }
+                },
+                BLOCK_SIMPLE_PACKET => {
+                    let interface = *interfaces.first().ok_or_else(|| {
+                        CaptureError::InvalidBlock("simple packet block before any interface".to_string())
+                    })?;
+                    if body.len() < 4 {
+                        return Err(CaptureError::InvalidBlock(format!("simple packet block of {} bytes", body.len())));
+                    }
+                    let original = read_u32(&body, big_endian) as usize;
+                    let data = &body[4..];
+                    let data = &data[..original.min(data.len())];
+                    Packet { timestamp: self.last_timestamp, link_type: interface.link_type, data: data.to_vec() }
+                },
+                // 名称解析、统计等其他块与数据包无关
+                _ => continue,
+            };
+            return Ok(Some(packet));
+        }
+    }
+}
+
+impl<R: Read> Iterator for CaptureReader<R> {
+    type Item = CaptureResult<Packet>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let result = match self.format {
+            Format::Pcap { .. } => self.read_pcap_record(),
+            Format::PcapNg { .. } => self.read_pcapng_block(),
+        };
+        match result {
+            Ok(Some(packet)) => {
+                self.last_timestamp = packet.timestamp;
+                Some(Ok(packet))
+            },
+            Ok(None) => {
+                self.done = true;
+                None
+            },
+            // 文件损坏时不再继续读取
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            },
+        }
+    }
+}
+
+/// 读取 section header block 中块类型之后的部分
+fn read_section_header<R: Read>(reader: &mut R, format: &mut Format) -> CaptureResult<()> {
+    let mut length = [0u8; 4];
+    read_exact(reader, &mut length, "section header")?;
+    read_section_body(reader, format, &length)
+}
+
+/// 按字节序标记确定本段的字节序，读取段头的剩余部分并清空接口列表
+fn read_section_body<R: Read>(reader: &mut R, format: &mut Format, length: &[u8]) -> CaptureResult<()> {
+    let mut magic = [0u8; 4];
+    read_exact(reader, &mut magic, "section header")?;
+    let big_endian = match (BigEndian::read_u32(&magic), LittleEndian::read_u32(&magic)) {
+        (BYTE_ORDER_MAGIC, _) => true,
+        (_, BYTE_ORDER_MAGIC) => false,
+        (magic, _) => return Err(CaptureError::InvalidBlock(format!("byte-order magic {:#010x}", magic))),
+    };
+    let total = read_u32(length, big_endian) as usize;
+    // 已经读取了块类型、长度和字节序标记
+    let rest = total.checked_sub(12)
+        .filter(|rest| rest % 4 == 0 && *rest <= MAX_RECORD_LEN)
+        .ok_or_else(|| CaptureError::InvalidBlock(format!("section header of {} bytes", total)))?;
+    let mut body = vec![0u8; rest];
+    read_exact(reader, &mut body, "section header")?;
+    *format = Format::PcapNg { big_endian, interfaces: Vec::new() };
+    Ok(())
+}
+
+/// 读取块头之后的内容，去掉末尾重复的块长度
+fn read_block_body<R: Read>(reader: &mut R, total: usize) -> CaptureResult<Vec<u8>> {
+    let rest = total.checked_sub(8)
+        .filter(|rest| *rest >= 4 && rest % 4 == 0 && *rest <= MAX_RECORD_LEN)
+        .ok_or_else(|| CaptureError::InvalidBlock(format!("block of {} bytes", total)))?;
+    let mut body = vec![0u8; rest];
+    read_exact(reader, &mut body, "block body")?;
+    body.truncate(rest - 4);
+    Ok(body)
+}
+
+fn parse_interface(body: &[u8], big_endian: bool) -> CaptureResult<Interface> {
+    if body.len() < 8 {
+        return Err(CaptureError::InvalidBlock(format!("interface block of {} bytes", body.len())));
+    }
+    let mut interface = Interface {
+        link_type: LinkType::from(read_u16(body, big_endian) as u32),
+        units_per_second: 1_000_000,
+        offset_seconds: 0,
+    };
+
+    let mut options = &body[8..];
+    while options.len() >= 4 {
+        let code = read_u16(options, big_endian);
+        let len = read_u16(&options[2..], big_endian) as usize;
+        let Some(value) = options.get(4..4 + len) else { break };
+        match (code, value) {
+            (0, _) => break,
+            // if_tsresol：最高位为 0 时是 10 的负幂，为 1 时是 2 的负幂
+            (9, [resolution]) => {
+                let exponent = (resolution & 0x7F) as u32;
+                interface.units_per_second = match resolution & 0x80 {
+                    0 => 10u64.checked_pow(exponent),
+                    _ => 2u64.checked_pow(exponent),
+                }.filter(|units| *units > 0).unwrap_or(1_000_000);
+            },
+            (14, value) if value.len() == 8 => {
+                interface.offset_seconds = match big_endian {
+                    true => BigEndian::read_i64(value),
+                    false => LittleEndian::read_i64(value),
+                };
+            },
+            _ => {},
+        }
+        options = options.get(4 + len.div_ceil(4) * 4..).unwrap_or_default();
+    }
+    Ok(interface)
+}
+
+fn fraction_to_duration(fraction: u64, units_per_second: u64) -> Duration {
+    Duration::from_nanos((fraction as u128 * 1_000_000_000 / units_per_second as u128) as u64)
+}
+
+fn read_u16(bytes: &[u8], big_endian: bool) -> u16 {
+    match big_endian {
+        true => BigEndian::read_u16(bytes),
+        false => LittleEndian::read_u16(bytes),
+    }
+}
+
+fn read_u32(bytes: &[u8], big_endian: bool) -> u32 {
+    match big_endian {
+        true => BigEndian::read_u32(bytes),
+        false => LittleEndian::read_u32(bytes),
+    }
+}
+
+fn read_exact<R: Read>(reader: &mut R, buffer: &mut [u8], what: &str) -> CaptureResult<()> {
+    reader.read_exact(buffer).map_err(|e| match e.kind() {
+        ErrorKind::UnexpectedEof => CaptureError::Truncated(what.to_string()),
+        _ => CaptureError::IoError(e),
+    })
+}
+
+/// 读取定长的记录头，文件恰好在记录之间结束时返回 `false`
+fn read_or_eof<R: Read>(reader: &mut R, buffer: &mut [u8], what: &str) -> CaptureResult<bool> {
+    let mut filled = 0;
+    while filled < buffer.len() {
+        match reader.read(&mut buffer[filled..]) {
+            Ok(0) if filled == 0 => return Ok(false),
+            Ok(0) => return Err(CaptureError::Truncated(what.to_string())),
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == ErrorKind::Interrupted => {},
+            Err(e) => return Err(CaptureError::IoError(e)),
+        }
+    }
+    Ok(true)
+}
@@ -0,0 +1,137 @@
+use std::time::Duration;
+
+use byteorder::{BigEndian, ByteOrder};
+
+use crate::capture::packet::TcpSegment;
+use crate::capture::StreamIssue;
+use crate::codec::MessageHeader;
+
+/// 最多缓存的乱序报文段个数，超过时认为中间的数据没有被抓到
+const MAX_PENDING: usize = 64;
+/// 消息体长度上限，超过时认为流已经错位
+pub(crate) const MAX_BODY_LENGTH: usize = 1024 * 1024;
+
+/// 重组结果
+#[derive(Debug)]
+pub(crate) enum StreamOutput {
+    /// 一个完整的帧，时间为收到其最后一个字节的时间
+    Frame(Duration, Vec<u8>),
+    Issue(Duration, StreamIssue),
+}
+
+/// 一个方向上的 TCP 流重组和分帧
+///
+/// 按序列号拼接报文段，重复的字节按重传丢弃，提前到达的报文段暂存到缺口补齐；
+/// 缺口一直补不上时丢弃未成帧的数据，从缺口之后第一个报文段的开头重新分帧
+#[derive(Debug, Default)]
+pub(crate) struct StreamBuffer {
+    /// 下一个期望的序列号，抓包从连接中途开始时取第一个报文段的序列号
+    next_seq: Option<u32>,
+    /// 已按序到达但还不够一帧的字节
+    buffer: Vec<u8>,
+    /// 提前到达的报文段
+    pending: Vec<(Duration, u32, Vec<u8>)>,
+}
+
+impl StreamBuffer {
+    pub(crate) fn push(&mut self, timestamp: Duration, segment: &TcpSegment, out: &mut Vec<StreamOutput>) {
+        let seq = segment.seq.wrapping_add(segment.syn as u32);
+        if segment.syn {
+            self.next_seq = Some(seq);
+        }
+        let next = *self.next_seq.get_or_insert(seq);
+        if segment.payload.is_empty() {
+            return;
+        }
+
+        if offset(seq, next) > 0 {
+            self.pending.push((timestamp, seq, segment.payload.clone()));
+            if self.pending.len() > MAX_PENDING {
+                self.skip_gap(timestamp, out);
+            }
+        } else {
+            self.append(timestamp, seq, &segment.payload, out);
+        }
+        self.drain_pending(out);
+    }
+
+    /// 抓包结束时补不上的缺口和不完整的帧
+    pub(crate) fn finish(&mut self, timestamp: Duration, out: &mut Vec<StreamOutput>) {
+        while !self.pending.is_empty() {
+            self.skip_gap(timestamp, out);
+            self.drain_pending(out);
+        }
+        if !self.buffer.is_empty() {
+            out.push(StreamOutput::Issue(timestamp, StreamIssue::Truncated { bytes: self.buffer.len() }));
+            self.buffer.clear();
+        }
+    }
+
+    /// 追加从 `seq` 开始的字节，与已收到的部分重叠时按重传处理
+    fn append(&mut self, timestamp: Duration, seq: u32, payload: &[u8], out: &mut Vec<StreamOutput>) {
+        let next = self.next_seq.unwrap_or(seq);
+        let overlap = (-offset(seq, next)) as usize;
+        if overlap > 0 {
+            out.push(StreamOutput::Issue(timestamp, StreamIssue::Retransmission { bytes: overlap.min(payload.len()) }));
+        }
+        let Some(payload) = payload.get(overlap..).filter(|rest| !rest.is_empty()) else {
+            return;
+        };
+        self.next_seq = Some(next.wrapping_add(payload.len() as u32));
+        self.buffer.extend_from_slice(payload);
+        self.split_frames(timestamp, out);
+    }
+
+    /// 处理已经可以接上的暂存报文段
+    fn drain_pending(&mut self, out: &mut Vec<StreamOutput>) {
+        while let Some(next) = self.next_seq
+            && let Some(index) = self.pending.iter().position(|(_, seq, _)| offset(*seq, next) <= 0)
+        {
+            let (timestamp, seq, payload) = self.pending.remove(index);
+            self.append(timestamp, seq, &payload, out);
+        }
+    }
+
+    /// 跳过缺口，从最早的暂存报文段重新开始
+    fn skip_gap(&mut self, timestamp: Duration, out: &mut Vec<StreamOutput>) {
+        let Some(next) = self.next_seq else { return };
+        let Some(first) = self.pending.iter().map(|(_, seq, _)| *seq).min_by_key(|seq| offset(*seq, next)) else {
+            return;
+        };
+        out.push(StreamOutput::Issue(timestamp, StreamIssue::Gap {
+            expected_seq: next,
+            seq: first,
+            missing: first.wrapping_sub(next) as usize,
+            discarded: self.buffer.len(),
+        }));
+        self.buffer.clear();
+        self.next_seq = Some(first);
+    }
+
+    /// 按头部中的消息体长度切出完整的帧
+    fn split_frames(&mut self, timestamp: Duration, out: &mut Vec<StreamOutput>) {
+        let mut start = 0;
+        while self.buffer.len() - start >= MessageHeader::SIZE {
+            let body_length = BigEndian::read_u32(&self.buffer[start + 8..start + 12]) as usize;
+            if body_length > MAX_BODY_LENGTH {
+                let bytes = self.buffer.len() - start;
+                let reason = format!("body length {} exceeds {}", body_length, MAX_BODY_LENGTH);
+                out.push(StreamOutput::Issue(timestamp, StreamIssue::Framing { bytes, reason }));
+                start = self.buffer.len();
+                break;
+            }
+            let end = start + MessageHeader::SIZE + body_length + 4;
+            if self.buffer.len() < end {
+                break;
+            }
+            out.push(StreamOutput::Frame(timestamp, self.buffer[start..end].to_vec()));
+            start = end;
+        }
+        self.buffer.drain(..start);
+    }
+}
+
+/// `seq` 相对 `next` 的位置，负数表示已经收到过
+fn offset(seq: u32, next: u32) -> i64 {
+    seq.wrapping_sub(next) as i32 as i64
+}
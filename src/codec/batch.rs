@@ -0,0 +1,232 @@
+use std::ops::Range;
+use std::thread;
+
+use byteorder::{BigEndian, ByteOrder};
+
+use crate::codec::checksum::ChecksumMode;
+use crate::codec::decoder::MessageDecoder;
+use crate::codec::encoder::MessageEncoder;
+use crate::codec::types::MessageHeader;
+use crate::config::manager::ConfigManager;
+use crate::message::Message;
+use crate::util::{MessageError, MessageResult};
+
+/// 批量编码结果：所有帧连续存放在一个缓冲区中，并记录每条消息对应的帧位置
+///
+/// 单条消息编码失败不影响其他消息，失败消息的位置记录错误，缓冲区中不留下任何数据
+#[derive(Debug, Default)]
+pub struct EncodedBatch {
+    /// 连续存放的帧
+    buffer: Vec<u8>,
+    /// 每条消息的帧范围或编码错误，与输入消息一一对应
+    frames: Vec<Result<Range<usize>, MessageError>>,
+}
+
+impl EncodedBatch {
+    /// 全部帧的连续字节
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buffer
+    }
+
+    /// 取出连续字节
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.buffer
+    }
+
+    /// 输入的消息数，包括编码失败的消息
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    /// 是否没有输入消息
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// 第 `index` 条消息的帧，编码失败或超出范围时返回 `None`
+    pub fn frame(&self, index: usize) -> Option<&[u8]> {
+        match self.frames.get(index)? {
+            Ok(range) => Some(&self.buffer[range.clone()]),
+            Err(_) => None,
+        }
+    }
+
+    /// 第 `index` 条消息的帧在缓冲区中的范围
+    pub fn frame_range(&self, index: usize) -> Option<Range<usize>> {
+        self.frames.get(index)?.as_ref().ok().cloned()
+    }
+
+    /// 按输入顺序遍历编码成功的帧及其消息下标
+    pub fn frames(&self) -> impl Iterator<Item = (usize, &[u8])> {
+        self.frames.iter().enumerate().filter_map(|(index, frame)| {
+            frame.as_ref().ok().map(|range| (index, &self.buffer[range.clone()]))
+        })
+    }
+
+    /// 第 `index` 条消息的编码错误
+    pub fn error(&self, index: usize) -> Option<&MessageError> {
+        self.frames.get(index)?.as_ref().err()
+    }
+
+    /// 遍历编码失败的消息下标及错误
+    pub fn errors(&self) -> impl Iterator<Item = (usize, &MessageError)> {
+        self.frames.iter().enumerate().filter_map(|(index, frame)| frame.as_ref().err().map(|e| (index, e)))
+    }
+}
+
+impl MessageEncoder<'_> {
+    /// 将多条消息依次编码到一个连续缓冲区中
+    pub fn encode_batch(&mut self, messages: &[Message]) -> EncodedBatch {
+        let mut batch = EncodedBatch { buffer: Vec::new(), frames: Vec::with_capacity(messages.len()) };
+        for message in messages {
+            let start = batch.buffer.len();
+            let frame = match self.encode_append(message, &mut batch.buffer) {
+                Ok(len) => Ok(start..start + len),
+                Err(e) => {
+                    batch.buffer.truncate(start);
+                    Err(e.kind)
+                },
+            };
+            batch.frames.push(frame);
+        }
+        batch
+    }
+}
+
+/// 按头部中的消息体长度切分连续存放的帧
+///
+/// 每次产生帧在缓冲区中的偏移和完整的帧（包括校验和）；末尾数据不足一帧时产生一次
+/// `HeaderTooShort` 或 `BodyTooShort` 错误后结束
+#[derive(Debug, Clone)]
+pub struct Frames<'a> {
+    buffer: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Frames<'a> {
+    /// 切分缓冲区中的帧
+    pub fn new(buffer: &'a [u8]) -> Self {
+        Self { buffer, position: 0 }
+    }
+}
+
+impl<'a> Iterator for Frames<'a> {
+    type Item = MessageResult<(usize, &'a [u8])>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let rest = &self.buffer[self.position..];
+        if rest.is_empty() {
+            return None;
+        }
+
+        let offset = self.position;
+        let frame_len = match rest.get(8..12) {
+            Some(body_length) => MessageHeader::SIZE + BigEndian::read_u32(body_length) as usize + 4,
+            None => {
+                self.position = self.buffer.len();
+                return Some(Err(MessageError::HeaderTooShort));
+            },
+        };
+        if rest.len() < frame_len {
+            self.position = self.buffer.len();
+            return Some(Err(MessageError::BodyTooShort));
+        }
+        self.position += frame_len;
+        Some(Ok((offset, &rest[..frame_len])))
+    }
+}
+
+/// 批量解码中单个帧的结果
+#[derive(Debug)]
+pub struct DecodedFrame {
+    /// 帧的序号
+    pub index: usize,
+    /// 帧在缓冲区中的偏移
+    pub offset: usize,
+    /// 解码结果，失败时不影响其他帧
+    pub result: MessageResult<Message>,
+}
+
+/// 逐帧解码连续存放的帧，单个帧解码失败时继续解码下一帧
+#[derive(Debug, Clone)]
+pub struct DecodeFrames<'a> {
+    config_manager: &'a ConfigManager,
+    frames: Frames<'a>,
+    index: usize,
+    checksum_mode: ChecksumMode,
+}
+
+impl<'a> DecodeFrames<'a> {
+    /// 创建批量解码迭代器
+    pub fn new(config_manager: &'a ConfigManager, buffer: &'a [u8]) -> Self {
+        Self { config_manager, frames: Frames::new(buffer), index: 0, checksum_mode: ChecksumMode::Strict }
+    }
+
+    /// 设置校验和的处理方式，默认严格校验
+    pub fn with_checksum_mode(mut self, checksum_mode: ChecksumMode) -> Self {
+        self.checksum_mode = checksum_mode;
+        self
+    }
+
+    /// 使用多个线程解码剩余的帧，结果按帧的顺序返回
+    ///
+    /// 先在当前线程切分帧，再把帧平均分给 `threads` 个线程解码；`threads` 不大于 1 时
+    /// 与逐帧迭代的结果相同
+    pub fn parallel(self, threads: usize) -> Vec<DecodedFrame> {
+        if threads <= 1 {
+            return self.collect();
+        }
+
+        let Self { config_manager, frames: mut splitter, index: start, checksum_mode } = self;
+        let mut frames = Vec::new();
+        let mut tail = None;
+        loop {
+            let offset = splitter.position;
+            match splitter.next() {
+                Some(Ok(frame)) => frames.push(frame),
+                Some(Err(e)) => tail = Some(DecodedFrame { index: start + frames.len(), offset, result: Err(e) }),
+                None => break,
+            }
+        }
+
+        let chunk_size = frames.len().div_ceil(threads).max(1);
+        let mut decoded: Vec<DecodedFrame> = thread::scope(|scope| {
+            let handles: Vec<_> = frames.chunks(chunk_size).enumerate().map(|(chunk, frames)| {
+                scope.spawn(move || {
+                    frames.iter().enumerate().map(|(i, &(offset, frame))| DecodedFrame {
+                        index: start + chunk * chunk_size + i,
+                        offset,
+                        result: MessageDecoder::new(config_manager, frame).with_checksum_mode(checksum_mode).decode(),
+                    }).collect::<Vec<_>>()
+                })
+            }).collect();
+            handles.into_iter().flat_map(|handle| handle.join().expect("decode thread panicked")).collect()
+        });
+        decoded.extend(tail);
+        decoded
+    }
+}
+
+impl Iterator for DecodeFrames<'_> {
+    type Item = DecodedFrame;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let offset = self.frames.position;
+        let result = self.frames.next()?;
+        let index = self.index;
+        self.index += 1;
+        Some(match result {
+            Ok((offset, frame)) => DecodedFrame {
+                index,
+                offset,
+                result: MessageDecoder::new(self.config_manager, frame).with_checksum_mode(self.checksum_mode).decode(),
+            },
+            Err(e) => DecodedFrame { index, offset, result: Err(e) },
+        })
+    }
+}
+
+/// 使用多个线程解码连续存放的帧，结果按帧的顺序返回，参见 [`DecodeFrames::parallel`]
+pub fn decode_frames_parallel(config_manager: &ConfigManager, buffer: &[u8], threads: usize) -> Vec<DecodedFrame> {
+    DecodeFrames::new(config_manager, buffer).parallel(threads)
+}
@@ -0,0 +1,158 @@
+use std::fmt;
+
+/// 校验和算法，编码时写入帧尾，解码时用于校验
+///
+/// 算法在 [`crate::config::manager::ConfigManager`] 中配置，默认是协议规定的 [`Sum8`]；
+/// 测试环境可以换成其他算法或使用 [`NoChecksum`] 关闭校验
+pub trait ChecksumAlgorithm: Send + Sync + fmt::Debug {
+    /// 初始状态
+    fn init(&self) -> u32 {
+        0
+    }
+
+    /// 在当前状态上累加一段数据，返回新的状态
+    fn update(&self, state: u32, data: &[u8]) -> u32;
+
+    /// 由累加状态得到写入帧尾的校验和
+    fn finish(&self, state: u32) -> u32 {
+        state
+    }
+
+    /// 解码时是否校验帧尾的校验和
+    fn verifies(&self) -> bool {
+        true
+    }
+
+    /// 计算一段数据的校验和
+    fn compute(&self, data: &[u8]) -> u32 {
+        self.finish(self.update(self.init(), data))
+    }
+
+    /// 校验数据与帧尾的校验和是否一致，不校验的算法总是返回 `true`
+    fn verify(&self, data: &[u8], expected: u32) -> bool {
+        !self.verifies() || self.compute(data) == expected
+    }
+}
+
+/// 协议规定的校验和：所有字节按 u8 累加后转换为 u32
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Sum8;
+
+/// 分块累加时的并行通道数，每个通道独立按 u8 累加，便于编译器生成 SIMD 指令
+const SUM8_LANES: usize = 32;
+
+impl Sum8 {
+    /// 按 u8 累加全部字节
+    pub fn sum(data: &[u8]) -> u8 {
+        let mut lanes = [0u8; SUM8_LANES];
+        let mut chunks = data.chunks_exact(SUM8_LANES);
+        for chunk in &mut chunks {
+            for (lane, byte) in lanes.iter_mut().zip(chunk) {
+                *lane = lane.wrapping_add(*byte);
+            }
+        }
+        // u8 加法对 256 取模，各通道的和可以按任意顺序合并
+        let tail = chunks.remainder().iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte));
+        lanes.iter().fold(tail, |sum, lane| sum.wrapping_add(*lane))
+    }
+}
+
+impl ChecksumAlgorithm for Sum8 {
+    fn update(&self, state: u32, data: &[u8]) -> u32 {
+        (state as u8).wrapping_add(Self::sum(data)) as u32
+    }
+}
+
+/// 关闭校验和：编码时写入 0，解码时不校验
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoChecksum;
+
+impl ChecksumAlgorithm for NoChecksum {
+    fn update(&self, state: u32, _data: &[u8]) -> u32 {
+        state
+    }
+
+    fn finish(&self, _state: u32) -> u32 {
+        0
+    }
+
+    fn verifies(&self) -> bool {
+        false
+    }
+}
+
+/// 解码时对校验和的处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ChecksumMode {
+    /// 校验失败时返回 [`crate::util::MessageError::ChecksumError`]
+    #[default]
+    Strict,
+    /// 校验失败时继续解码，并设置 [`crate::message::Message::checksum_mismatch`]
+    Warn,
+    /// 不校验，用于已经校验过完整性的进程内路径
+    Skip,
+}
+
+/// 增量计算校验和，用于边写边算的场景，例如按片段组帧
+///
+/// ```ignore
+/// let mut state = ChecksumState::new(config_manager.checksum());
+/// state.update(&header);
+/// state.update(&body);
+/// let checksum = state.finish();
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct ChecksumState<'c> {
+    algorithm: &'c dyn ChecksumAlgorithm,
+    state: u32,
+}
+
+impl<'c> ChecksumState<'c> {
+    /// 以算法的初始状态开始计算
+    pub fn new(algorithm: &'c dyn ChecksumAlgorithm) -> Self {
+        Self { algorithm, state: algorithm.init() }
+    }
+
+    /// 累加一段数据
+    pub fn update(&mut self, data: &[u8]) {
+        self.state = self.algorithm.update(self.state, data);
+    }
+
+    /// 当前已累加数据的校验和，不影响继续累加
+    pub fn finish(&self) -> u32 {
+        self.algorithm.finish(self.state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 逐字节累加，作为分块实现的对照
+    fn byte_loop(data: &[u8]) -> u32 {
+        data.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte)) as u32
+    }
+
+    #[test]
+    fn test_sum8_matches_byte_loop() {
+        let data: Vec<u8> = (0..1000u32).map(|i| (i * 7 + 13) as u8).collect();
+        for len in [0, 1, 31, 32, 33, 64, 999, 1000] {
+            assert_eq!(Sum8.compute(&data[..len]), byte_loop(&data[..len]), "length {}", len);
+        }
+    }
+
+    #[test]
+    fn test_incremental_update() {
+        let data: Vec<u8> = (0..=255u8).cycle().take(777).collect();
+        let mut state = ChecksumState::new(&Sum8);
+        for chunk in data.chunks(50) {
+            state.update(chunk);
+        }
+        assert_eq!(state.finish(), Sum8.compute(&data));
+
+        let mut state = ChecksumState::new(&NoChecksum);
+        state.update(&data);
+        assert_eq!(state.finish(), 0);
+        assert!(NoChecksum.verify(&data, 12345));
+    }
+}
@@ -0,0 +1,238 @@
+use std::borrow::Cow;
+
+use crate::codec::types::{integer_range, integer_value_type, is_integer_widening};
+use crate::config::types::{BaseFieldDef, FieldType};
+use crate::message::FieldValue;
+use crate::util::{MessageError, MessageResult};
+
+/// f64 能精确表示的最大整数 2^53
+const MAX_EXACT_FLOAT_INTEGER: i128 = 1 << 53;
+
+/// 验证FieldValue与FieldType是否匹配
+///
+/// 整数字段额外接受可无损拓宽的值，例如 U8 值写入 U32 字段
+pub(super) fn validate_field_type_match(field_type: &FieldType, value: &FieldValue) -> bool {
+    if is_integer_widening(field_type, value) {
+        return true;
+    }
+    matches!(
+        (field_type, value),
+        (FieldType::U8, FieldValue::U8(_))
+            | (FieldType::U16, FieldValue::U16(_))
+            | (FieldType::U32, FieldValue::U32(_))
+            | (FieldType::U64, FieldValue::U64(_))
+            | (FieldType::I8, FieldValue::I8(_))
+            | (FieldType::I16, FieldValue::I16(_))
+            | (FieldType::I32, FieldValue::I32(_))
+            | (FieldType::I64, FieldValue::I64(_))
+            | (FieldType::Char, FieldValue::Str(_))
+            | (FieldType::Price, FieldValue::Float(_))
+            | (FieldType::Quantity, FieldValue::Float(_))
+            | (FieldType::Amount, FieldValue::Float(_))
+            | (FieldType::Date, FieldValue::U32(_))
+            | (FieldType::NTime, FieldValue::U64(_))
+            | (FieldType::Bytes, FieldValue::Bytes(_))
+            | (FieldType::Array, FieldValue::Array(_))
+    )
+}
+
+/// 将字段值无损转换为字段定义要求的类型
+///
+/// - 整数字段：接受任意宽度的整数、整数值的浮点数和数字字符串，超出范围时报错
+/// - Date/NTime：分别按 U32/U64 处理，格式校验仍由编码器完成
+/// - Price/Quantity/Amount：接受浮点数、整数和数字字符串
+///
+/// 转换结果总是字段定义对应的 FieldValue 变体，类型完全一致时直接借用原值
+pub fn coerce_field_value<'a>(base_field_def: &BaseFieldDef, value: &'a FieldValue) -> MessageResult<Cow<'a, FieldValue>> {
+    let field_type = &base_field_def.r#type;
+    if validate_field_type_match(field_type, value) {
+        // 拓宽的整数值统一转换为目标类型
+        if integer_range(field_type).is_some()
+            && integer_value_type(value).as_ref() != Some(field_type)
+            && let Some(widened) = value.as_integer().and_then(|integer| integer_value(field_type, integer))
+        {
+            return Ok(Cow::Owned(widened));
+        }
+        return Ok(Cow::Borrowed(value));
+    }
+
+    let coerced = match field_type {
+        FieldType::U8 | FieldType::U16 | FieldType::U32 | FieldType::U64
+        | FieldType::I8 | FieldType::I16 | FieldType::I32 | FieldType::I64 => {
+            let integer = integer_of(base_field_def, value)?;
+            integer_value(field_type, integer).ok_or_else(|| out_of_range(base_field_def, integer))?
+        },
+        FieldType::Date => {
+            let integer = integer_of(base_field_def, value)?;
+            let date = u32::try_from(integer).map_err(|_| out_of_range(base_field_def, integer))?;
+            FieldValue::U32(date)
+        },
+        FieldType::NTime => {
+            let integer = integer_of(base_field_def, value)?;
+            let ntime = u64::try_from(integer).map_err(|_| out_of_range(base_field_def, integer))?;
+            FieldValue::U64(ntime)
+        },
+        FieldType::Price | FieldType::Quantity | FieldType::Amount => {
+            FieldValue::Float(float_of(base_field_def, value)?)
+        },
+        FieldType::Char | FieldType::Bytes | FieldType::Array => return Err(type_mismatch(base_field_def, value)),
+    };
+
+    Ok(Cow::Owned(coerced))
+}
+
+/// 从整数、整数值浮点数或数字字符串中取出整数
+fn integer_of(base_field_def: &BaseFieldDef, value: &FieldValue) -> MessageResult<i128> {
+    if let Some(integer) = value.as_integer() {
+        return Ok(integer);
+    }
+    match value {
+        FieldValue::Float(v) => {
+            if !v.is_finite() || v.fract() != 0.0 {
+                return Err(MessageError::InvalidFieldValue(format!(
+                    "Field '{}' value {} is not an integer for {:?}",
+                    base_field_def.name, v, base_field_def.r#type
+                )));
+            }
+            // 超出 i128 的浮点数会饱和到边界，随后的范围检查会将其拒绝
+            Ok(*v as i128)
+        },
+        FieldValue::Str(s) => s.trim().parse::<i128>().map_err(|_| {
+            MessageError::InvalidFieldValue(format!(
+                "Field '{}' cannot parse {:?} as {:?}",
+                base_field_def.name, s, base_field_def.r#type
+            ))
+        }),
+        _ => Err(type_mismatch(base_field_def, value)),
+    }
+}
+
+/// 从浮点数、整数或数字字符串中取出浮点数
+fn float_of(base_field_def: &BaseFieldDef, value: &FieldValue) -> MessageResult<f64> {
+    if let Some(integer) = value.as_integer() {
+        if integer.abs() > MAX_EXACT_FLOAT_INTEGER {
+            return Err(MessageError::ValueExceedsRange(format!(
+                "Field '{}' value {} cannot be represented exactly as {:?}",
+                base_field_def.name, integer, base_field_def.r#type
+            )));
+        }
+        return Ok(integer as f64);
+    }
+    match value {
+        FieldValue::Str(s) => s.trim().parse::<f64>()
+            .ok()
+            .filter(|v| v.is_finite())
+            .ok_or_else(|| {
+                MessageError::InvalidFieldValue(format!(
+                    "Field '{}' cannot parse {:?} as {:?}",
+                    base_field_def.name, s, base_field_def.r#type
+                ))
+            }),
+        _ => Err(type_mismatch(base_field_def, value)),
+    }
+}
+
+/// 按目标整数类型构造字段值，超出范围时返回 None
+fn integer_value(field_type: &FieldType, integer: i128) -> Option<FieldValue> {
+    match field_type {
+        FieldType::U8 => u8::try_from(integer).ok().map(FieldValue::U8),
+        FieldType::U16 => u16::try_from(integer).ok().map(FieldValue::U16),
+        FieldType::U32 => u32::try_from(integer).ok().map(FieldValue::U32),
+        FieldType::U64 => u64::try_from(integer).ok().map(FieldValue::U64),
+        FieldType::I8 => i8::try_from(integer).ok().map(FieldValue::I8),
+        FieldType::I16 => i16::try_from(integer).ok().map(FieldValue::I16),
+        FieldType::I32 => i32::try_from(integer).ok().map(FieldValue::I32),
+        FieldType::I64 => i64::try_from(integer).ok().map(FieldValue::I64),
+        _ => None,
+    }
+}
+
+fn out_of_range(base_field_def: &BaseFieldDef, integer: i128) -> MessageError {
+    let range = match base_field_def.r#type {
+        FieldType::Date => Some((u32::MIN as i128, u32::MAX as i128)),
+        FieldType::NTime => Some((u64::MIN as i128, u64::MAX as i128)),
+        ref field_type => integer_range(field_type),
+    };
+    match range {
+        Some((min, max)) => MessageError::ValueExceedsRange(format!(
+            "Field '{}' value {} out of range for {:?} ({}..={})",
+            base_field_def.name, integer, base_field_def.r#type, min, max
+        )),
+        None => MessageError::ValueExceedsRange(format!(
+            "Field '{}' value {} out of range for {:?}",
+            base_field_def.name, integer, base_field_def.r#type
+        )),
+    }
+}
+
+fn type_mismatch(base_field_def: &BaseFieldDef, value: &FieldValue) -> MessageError {
+    MessageError::InvalidFieldValue(format!(
+        "Field '{}' type mismatch: expected {:?}, got {:?}",
+        base_field_def.name, base_field_def.r#type, value
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(name: &str, field_type: FieldType) -> BaseFieldDef {
+        BaseFieldDef {
+            name: name.to_string(),
+            r#type: field_type,
+            length: None,
+            desc: None,
+        }
+    }
+
+    #[test]
+    fn test_coerce_integer_widths() {
+        let def = field("HeartBtInt", FieldType::U16);
+        assert_eq!(coerce_field_value(&def, &FieldValue::U32(30)).unwrap().into_owned(), FieldValue::U16(30));
+        assert_eq!(coerce_field_value(&def, &FieldValue::I64(65535)).unwrap().into_owned(), FieldValue::U16(65535));
+
+        // 已匹配的类型直接借用，拓宽的整数也转换为目标类型
+        assert!(matches!(coerce_field_value(&def, &FieldValue::U16(1)).unwrap(), Cow::Borrowed(_)));
+        assert_eq!(coerce_field_value(&def, &FieldValue::U8(1)).unwrap().into_owned(), FieldValue::U16(1));
+
+        // 超出范围的错误需要包含字段名、值和范围
+        match coerce_field_value(&def, &FieldValue::U32(70000)) {
+            Err(MessageError::ValueExceedsRange(msg)) => {
+                assert!(msg.contains("HeartBtInt") && msg.contains("70000") && msg.contains("0..=65535"), "{}", msg);
+            },
+            other => panic!("Expected ValueExceedsRange, got {:?}", other),
+        }
+        assert!(matches!(
+            coerce_field_value(&field("Signed", FieldType::I8), &FieldValue::I64(-129)),
+            Err(MessageError::ValueExceedsRange(_))
+        ));
+        assert!(matches!(
+            coerce_field_value(&field("Unsigned", FieldType::U32), &FieldValue::I32(-1)),
+            Err(MessageError::ValueExceedsRange(_))
+        ));
+    }
+
+    #[test]
+    fn test_coerce_strings_and_floats() {
+        let qsize = field("QSize", FieldType::U32);
+        assert_eq!(coerce_field_value(&qsize, &FieldValue::from(" 1000 ")).unwrap().into_owned(), FieldValue::U32(1000));
+        assert_eq!(coerce_field_value(&qsize, &FieldValue::Float(12.0)).unwrap().into_owned(), FieldValue::U32(12));
+        assert!(matches!(coerce_field_value(&qsize, &FieldValue::Float(12.5)), Err(MessageError::InvalidFieldValue(_))));
+        assert!(matches!(coerce_field_value(&qsize, &FieldValue::from("abc")), Err(MessageError::InvalidFieldValue(_))));
+
+        let date = field("TradeDate", FieldType::Date);
+        assert_eq!(coerce_field_value(&date, &FieldValue::from("20231201")).unwrap().into_owned(), FieldValue::U32(20231201));
+
+        let ntime = field("TransactTime", FieldType::NTime);
+        assert_eq!(coerce_field_value(&ntime, &FieldValue::I64(930000000000)).unwrap().into_owned(), FieldValue::U64(930000000000));
+
+        let price = field("Price", FieldType::Price);
+        assert_eq!(coerce_field_value(&price, &FieldValue::from("10.5")).unwrap().into_owned(), FieldValue::Float(10.5));
+        assert_eq!(coerce_field_value(&price, &FieldValue::U32(10)).unwrap().into_owned(), FieldValue::Float(10.0));
+        assert!(matches!(coerce_field_value(&price, &FieldValue::U64(u64::MAX)), Err(MessageError::ValueExceedsRange(_))));
+
+        // 字符串、字节和数组字段不做转换
+        let text = field("Text", FieldType::Char);
+        assert!(matches!(coerce_field_value(&text, &FieldValue::U32(1)), Err(MessageError::InvalidFieldValue(_))));
+    }
+}
@@ -1,14 +1,22 @@
 use byteorder::{BigEndian, ByteOrder};
 
-use crate::codec::types::MessageHeader;
+use crate::codec::layout::{base_field_size, FieldsLayout};
+use crate::codec::checksum::ChecksumMode;
+use crate::codec::types::{split_frame, verify_checksum, MessageHeader};
 use crate::config::manager::ConfigManager;
 use crate::config::types::{FieldDef, FieldType, BaseFieldDef};
-use crate::message::{Message, FieldValue};
-use crate::util::{MessageError, MessageResult};
+use crate::message::{Message, FieldValue, Record};
+use crate::util::{ContextError, ContextResult, MessageError, MessageResult};
 use crate::codec::types::{
     TYPE_PRICE_SCALE,
     TYPE_QUANTITY_SCALE,
     TYPE_AMOUNT_SCALE,
+    TYPE_PRICE_MAX,
+    TYPE_PRICE_MIN,
+    TYPE_QUANTITY_MAX,
+    TYPE_QUANTITY_MIN,
+    TYPE_AMOUNT_MAX,
+    TYPE_AMOUNT_MIN,
     validate_price, 
     validate_quantity, 
     validate_amount,
@@ -20,10 +28,39 @@ use crate::codec::types::{
 pub struct MessageDecoder<'a> {
     /// 配置管理器，用于获取消息定义
     config_manager: &'a ConfigManager,
+    /// 字段读取器，持有二进制数据和当前解析位置
+    reader: FieldReader<'a>,
+    /// 校验和的处理方式
+    checksum_mode: ChecksumMode,
+}
+
+/// 宽松解码的结果
+///
+/// 取值无效的字段以原始值保存在消息中：日期、时间保留整数值，价格、数量、金额保留未缩放的 `I64`，
+/// 非 UTF-8 的字符串保留为 `Bytes`；每个问题在 `diagnostics` 中记录一条带上下文的错误
+#[derive(Debug)]
+pub struct LenientMessage {
+    /// 解码得到的消息，消息体被截断时只包含截断之前的字段
+    pub message: Message,
+    /// 解码过程中发现的问题
+    pub diagnostics: Vec<ContextError>,
+}
+
+impl LenientMessage {
+    /// 是否没有发现任何问题
+    pub fn is_clean(&self) -> bool {
+        self.diagnostics.is_empty()
+    }
+}
+
+/// 按字段定义从二进制数据中逐个读取字段值，解码器和消息视图共用
+pub(crate) struct FieldReader<'a> {
     /// 二进制数据
     buffer: &'a [u8],
     /// 当前解析位置
     position: usize,
+    /// 宽松模式下记录的取值问题，为 `None` 时遇到无效取值直接报错
+    diagnostics: Option<Vec<ContextError>>,
 }
 
 impl<'a> MessageDecoder<'a> {
@@ -31,83 +68,184 @@ impl<'a> MessageDecoder<'a> {
     pub fn new(config_manager: &'a ConfigManager, buffer: &'a [u8]) -> Self {
         Self {
             config_manager,
-            buffer,
-            position: 0,
+            reader: FieldReader::new(buffer, 0),
+            checksum_mode: ChecksumMode::Strict,
         }
     }
 
+    /// 设置校验和的处理方式，默认严格校验
+    pub fn with_checksum_mode(mut self, checksum_mode: ChecksumMode) -> Self {
+        self.checksum_mode = checksum_mode;
+        self
+    }
+
     /// 解码消息
     pub fn decode(&mut self) -> MessageResult<Message> {
-        // 解析消息头部
-        if self.buffer.len() < MessageHeader::SIZE {
-            return Err(MessageError::HeaderTooShort);
-        }
+        Ok(self.decode_with_context()?)
+    }
 
-        // 解码消息类型
-        let msg_type = BigEndian::read_u32(&self.buffer[0..4]);
-        
-        // 解码序列号
-        let seq_num = BigEndian::read_u32(&self.buffer[4..8]);
-        
-        // 解码消息体长度
-        let body_length = BigEndian::read_u32(&self.buffer[8..12]);
-        
-        // 更新位置到消息体开始处
-        self.position = MessageHeader::SIZE;
+    /// 解码消息，出错时返回带上下文的错误，记录消息类型、序列号、字段路径和字节偏移
+    pub fn decode_with_context(&mut self) -> ContextResult<Message> {
+        self.decode_frame(false)
+            .map(|(message, _)| message)
+            .map_err(|e| self.message_error(e))
+    }
+
+    /// 宽松解码消息，字段取值无效或消息体被截断时仍然返回消息，问题记录在诊断列表中
+    ///
+    /// 头部不完整、消息类型未知以及严格校验方式下的校验和错误仍然直接返回错误
+    pub fn decode_lenient(&mut self) -> ContextResult<LenientMessage> {
+        let (message, diagnostics) = self.decode_frame(true).map_err(|e| self.message_error(e))?;
+        let diagnostics = diagnostics.into_iter().map(|e| self.message_error(e)).collect();
+        Ok(LenientMessage { message, diagnostics })
+    }
+
+    /// 在错误上下文中记录消息类型、名称和序列号
+    fn message_error(&self, error: ContextError) -> ContextError {
+        match MessageHeader::parse(self.reader.buffer) {
+            Ok(header) => {
+                let name = self.config_manager.get_layout(header.msg_type).map(|layout| layout.name.as_str());
+                error.in_message(header.msg_type, name, Some(header.seq_num))
+            },
+            Err(_) => error,
+        }
+    }
 
-        // 验证校验和
-        let body_end = MessageHeader::SIZE + body_length as usize;
-        self.verify_checksum(body_end)?;
+    fn decode_frame(&mut self, lenient: bool) -> ContextResult<(Message, Vec<ContextError>)> {
+        // 解析消息头部，验证消息体长度和校验和
+        let (header, body_end) = split_frame(self.reader.buffer)?;
+        let checksum_mismatch = verify_checksum(self.reader.buffer, body_end, self.config_manager.checksum(), self.checksum_mode)?;
+        let MessageHeader { msg_type, seq_num, body_length } = header;
+
+        // 字段只能在消息体内读取，位置移到消息体开始处
+        let body = &self.reader.buffer[..body_end];
+        self.reader = match lenient {
+            true => FieldReader::lenient(body, MessageHeader::SIZE),
+            false => FieldReader::new(body, MessageHeader::SIZE),
+        };
 
-        // 获取消息定义
-        let message_def = self.config_manager.get_message_def(msg_type)
-            .ok_or_else(|| MessageError::UnknownMessageType(msg_type))?;
+        // 获取预先计算的消息布局
+        let layout = self.config_manager.get_layout(msg_type)
+            .ok_or(MessageError::UnknownMessageType(msg_type))?;
 
         // 创建消息对象
         let mut message = Message::new(msg_type, seq_num);
+        message.checksum_mismatch = checksum_mismatch;
+
+        // 定长消息只需检查一次消息体长度，随后按布局偏移直接读取；宽松模式下逐个字段解码到截断处
+        if !lenient && let Some(fixed_len) = layout.fixed_body_len() {
+            if (body_length as usize) < fixed_len {
+                return Err(MessageError::BodyTooShort
+                    .at(MessageHeader::SIZE)
+                    .expected(format!("body of {} bytes", fixed_len), format!("{} bytes", body_length)));
+            }
+            self.reader.decode_fixed(layout.body(), &mut message)?;
+            return Ok((message, Vec::new()));
+        }
 
-        // 解析消息字段
-        for field_def in &message_def.fields {
-            let field_value = self.decode_field(&field_def.base, Some(field_def))?;
-            message.add_field(field_def.base.name.clone(), field_value);
+        // 解析消息字段和扩展字段
+        let result = self.reader.decode_fields(layout.body(), &mut message).and_then(|_| {
+            match message.get_field("BizID").and_then(FieldValue::as_u32) {
+                Some(biz_id) if layout.has_extensions() => match layout.extension(biz_id) {
+                    Some(extension) => self.reader.decode_fields(extension, &mut message),
+                    None => Ok(()),
+                },
+                _ => Ok(()),
+            }
+        });
+
+        let mut diagnostics = self.reader.diagnostics.take().unwrap_or_default();
+        match result {
+            Ok(()) => Ok((message, diagnostics)),
+            // 宽松模式下截断之后的字段无法定位，保留已解码的字段
+            Err(e) if lenient => {
+                diagnostics.push(e);
+                Ok((message, diagnostics))
+            },
+            Err(e) => Err(e),
         }
+    }
+}
 
-        // 解析扩展字段
-        if message.has_field("BizID") && message_def.extensions.len() > 0 {
-            let biz_id = message.get_field("BizID").unwrap().as_u32().unwrap();
-            let biz_extension = self.config_manager.get_extension(msg_type, biz_id);
+impl<'a> FieldReader<'a> {
+    pub(crate) fn new(buffer: &'a [u8], position: usize) -> Self {
+        Self { buffer, position, diagnostics: None }
+    }
 
-            if let Some(biz_extension) = biz_extension {
-                for field_def in &biz_extension.fields {
-                    let field_value = self.decode_field(&field_def, None)?;
-                    message.add_field(field_def.name.clone(), field_value);
-                }
-            }
+    /// 创建宽松模式的读取器，无效取值记录为诊断信息并返回原始值
+    pub(crate) fn lenient(buffer: &'a [u8], position: usize) -> Self {
+        Self { buffer, position, diagnostics: Some(Vec::new()) }
+    }
+
+    /// 当前解析位置
+    pub(crate) fn position(&self) -> usize {
+        self.position
+    }
+
+    /// 取出已记录的诊断信息
+    pub(crate) fn take_diagnostics(&mut self) -> Vec<ContextError> {
+        self.diagnostics.as_mut().map(std::mem::take).unwrap_or_default()
+    }
+
+    /// 取值无效时，宽松模式下记录问题并返回原始值，否则报错
+    fn invalid(&mut self, error: ContextError, raw: FieldValue) -> ContextResult<FieldValue> {
+        match &mut self.diagnostics {
+            Some(diagnostics) => {
+                diagnostics.push(error);
+                Ok(raw)
+            },
+            None => Err(error),
         }
+    }
 
-        Ok(message)
+    /// 解码一个字段，出错或记录了问题时用 `wrap` 在错误上下文中加上字段路径和偏移
+    fn decode_wrapped(
+        &mut self,
+        base_field_def: &BaseFieldDef,
+        field_def: Option<&FieldDef>,
+        wrap: impl Fn(ContextError) -> ContextError,
+    ) -> ContextResult<FieldValue> {
+        let mark = self.diagnostics.as_ref().map_or(0, Vec::len);
+        let result = self.decode_field(base_field_def, field_def);
+        if let Some(diagnostics) = &mut self.diagnostics
+            && diagnostics.len() > mark
+        {
+            let recorded: Vec<_> = diagnostics.drain(mark..).map(&wrap).collect();
+            diagnostics.extend(recorded);
+        }
+        result.map_err(wrap)
     }
 
-    /// 验证校验和
-    fn verify_checksum(&self, body_end: usize) -> MessageResult<()> {
-        if self.buffer.len() < body_end + 4 {
-            return Err(MessageError::BodyTooShort);
+    /// 检查当前位置之后是否还有 `len` 字节
+    fn ensure(&self, len: usize) -> ContextResult<()> {
+        if self.position + len > self.buffer.len() {
+            return Err(MessageError::BodyTooShort
+                .at(self.position)
+                .expected(format!("{} bytes", len), format!("{} bytes", self.buffer.len().saturating_sub(self.position))));
         }
-        
-        // 计算校验和 - 使用 uint8 累加然后转换为 uint32
-        let mut checksum: u8 = 0;
-        for byte in &self.buffer[0..body_end] {
-            checksum = checksum.wrapping_add(*byte);
+        Ok(())
+    }
+
+    /// 按布局依次解码一组字段并添加到消息中，出错时在错误上下文中记录字段名和起始偏移
+    pub(crate) fn decode_fields(&mut self, fields: &FieldsLayout, message: &mut Message) -> ContextResult<()> {
+        for field in fields.fields() {
+            let start = self.position;
+            let value = self.decode_wrapped(&field.def.base, Some(&field.def), |e| e.in_field(field.name()).at(start))?;
+            message.add_field(field.name().to_string(), value);
         }
-        
-        // 读取消息中的校验和
-        let message_checksum = BigEndian::read_u32(&self.buffer[body_end..body_end+4]);
-        
-        // 比较校验和 - 将 u8 转换为 u32 后比较
-        if (checksum as u32) != message_checksum {
-            return Err(MessageError::ChecksumError);
+        Ok(())
+    }
+
+    /// 按定长布局在各字段的固定偏移处直接读取，调用方已检查过消息体长度
+    pub(crate) fn decode_fixed(&mut self, fields: &FieldsLayout, message: &mut Message) -> ContextResult<()> {
+        let base = self.position;
+        message.fields.reserve(fields.fields().len());
+        for field in fields.fields() {
+            let start = base + field.offset.unwrap_or_default();
+            let value = self.read_at(&field.def.base, start).map_err(|e| e.in_field(field.name()).at(start))?;
+            message.add_field(field.name().to_string(), value);
         }
-        
+        self.position = base + fields.fixed_len().unwrap_or_default();
         Ok(())
     }
 
@@ -115,149 +253,148 @@ impl<'a> MessageDecoder<'a> {
     /// 
     /// * `base_field_def` - 基本字段定义
     /// * `field_def` - 完整字段定义，用于数组类型
-    fn decode_field(&mut self, base_field_def: &BaseFieldDef, field_def: Option<&FieldDef>) -> MessageResult<FieldValue> {
+    pub(crate) fn decode_field(&mut self, base_field_def: &BaseFieldDef, field_def: Option<&FieldDef>) -> ContextResult<FieldValue> {
         match base_field_def.r#type {
-            FieldType::U8 => {
-                if self.position + 1 > self.buffer.len() {
-                    return Err(MessageError::BodyTooShort);
-                }
-                let value = self.buffer[self.position];
-                self.position += 1;
-                Ok(FieldValue::U8(value))
-            },
-            FieldType::U16 => {
-                if self.position + 2 > self.buffer.len() {
-                    return Err(MessageError::BodyTooShort);
-                }
-                let value = BigEndian::read_u16(&self.buffer[self.position..]);
-                self.position += 2;
-                Ok(FieldValue::U16(value))
-            },
-            FieldType::U32 => {
-                if self.position + 4 > self.buffer.len() {
-                    return Err(MessageError::BodyTooShort);
-                }
-                let value = BigEndian::read_u32(&self.buffer[self.position..]);
-                self.position += 4;
-                Ok(FieldValue::U32(value))
-            },
-            FieldType::U64 => {
-                if self.position + 8 > self.buffer.len() {
-                    return Err(MessageError::BodyTooShort);
-                }
-                let value = BigEndian::read_u64(&self.buffer[self.position..]);
-                self.position += 8;
-                Ok(FieldValue::U64(value))
-            },
-            FieldType::I64 => {
-                if self.position + 8 > self.buffer.len() {
-                    return Err(MessageError::BodyTooShort);
-                }
-                let value = BigEndian::read_i64(&self.buffer[self.position..]);
-                self.position += 8;
-                Ok(FieldValue::I64(value))
+            FieldType::Array => {
+                // 如果是数组类型，需要完整的字段定义
+                let field_def = field_def.ok_or_else(|| {
+                    MessageError::ArrayElementDecodeError(format!("Array field {} missing field definition", base_field_def.name))
+                })?;
+                self.decode_array(field_def)
             },
-            FieldType::Char => {
-                let length = base_field_def.length.ok_or_else(|| {
+            FieldType::Bytes if base_field_def.length.is_none() => self.decode_bytes(base_field_def, field_def),
+            _ => {
+                let size = base_field_size(base_field_def).ok_or_else(|| {
                     MessageError::FieldDecodeError(format!("Char field {} missing length", base_field_def.name))
                 })?;
-                
-                if self.position + length > self.buffer.len() {
-                    return Err(MessageError::BodyTooShort);
+                self.ensure(size)?;
+                let start = self.position;
+                self.position += size;
+                self.read_at(base_field_def, start)
+            },
+        }
+    }
+
+    /// 读取 `start` 处的定长字段，调用方保证字段完整地位于缓冲区内
+    fn read_at(&mut self, base_field_def: &BaseFieldDef, start: usize) -> ContextResult<FieldValue> {
+        let data = &self.buffer[start..];
+        match base_field_def.r#type {
+            FieldType::U8 => Ok(FieldValue::U8(data[0])),
+            FieldType::U16 => Ok(FieldValue::U16(BigEndian::read_u16(data))),
+            FieldType::U32 => Ok(FieldValue::U32(BigEndian::read_u32(data))),
+            FieldType::U64 => Ok(FieldValue::U64(BigEndian::read_u64(data))),
+            FieldType::I8 => Ok(FieldValue::I8(data[0] as i8)),
+            FieldType::I16 => Ok(FieldValue::I16(BigEndian::read_i16(data))),
+            FieldType::I32 => Ok(FieldValue::I32(BigEndian::read_i32(data))),
+            FieldType::I64 => Ok(FieldValue::I64(BigEndian::read_i64(data))),
+            FieldType::Char => {
+                let length = base_field_def.length.unwrap_or_default();
+                let raw = &data[..length];
+                match std::str::from_utf8(raw) {
+                    Ok(s) => Ok(FieldValue::Str(s.trim().to_string())),
+                    Err(e) => {
+                        let offset = start + e.valid_up_to();
+                        let error = MessageError::FieldDecodeError(format!("UTF-8 error: {}", e))
+                            .at(offset)
+                            .expected("UTF-8 text", format!("byte {:#04x}", self.buffer[offset]));
+                        self.invalid(error, FieldValue::Bytes(raw.to_vec()))
+                    },
                 }
-                
-                let s = std::str::from_utf8(&self.buffer[self.position..self.position + length])
-                    .map_err(|e| MessageError::FieldDecodeError(format!("UTF-8 error: {}", e)))?
-                    .trim()
-                    .to_string();
-                    
-                self.position += length;
-                Ok(FieldValue::Str(s))
             },
             FieldType::Price => {
-                if self.position + 8 > self.buffer.len() {
-                    return Err(MessageError::BodyTooShort);
-                }
-                let value = BigEndian::read_i64(&self.buffer[self.position..]);
-                self.position += 8;
+                let value = BigEndian::read_i64(data);
                 if !validate_price(value) {
-                    return Err(MessageError::ValueExceedsRange(format!("Price value {} exceeds maximum limit", value)));
+                    let error = MessageError::ValueExceedsRange(format!("Price value {} exceeds maximum limit", value))
+                        .expected(format!("{}..={}", TYPE_PRICE_MIN, TYPE_PRICE_MAX), value);
+                    return self.invalid(error, FieldValue::I64(value));
                 }
                 // Price类型：先解析为i64，然后除以100000转成float
                 let float_value = value as f64 / TYPE_PRICE_SCALE;
                 Ok(FieldValue::Float(float_value))
             },
             FieldType::Quantity => {
-                if self.position + 8 > self.buffer.len() {
-                    return Err(MessageError::BodyTooShort);
-                }
-                let value = BigEndian::read_i64(&self.buffer[self.position..]);
-                self.position += 8;
+                let value = BigEndian::read_i64(data);
                 if !validate_quantity(value) {
-                    return Err(MessageError::ValueExceedsRange(format!("Quantity value {} exceeds maximum limit", value)));
+                    let error = MessageError::ValueExceedsRange(format!("Quantity value {} exceeds maximum limit", value))
+                        .expected(format!("{}..={}", TYPE_QUANTITY_MIN, TYPE_QUANTITY_MAX), value);
+                    return self.invalid(error, FieldValue::I64(value));
                 }
                 let float_value = value as f64 / TYPE_QUANTITY_SCALE;
                 Ok(FieldValue::Float(float_value))
             },
             FieldType::Amount => {
-                if self.position + 8 > self.buffer.len() {
-                    return Err(MessageError::BodyTooShort);
-                }
-                let value = BigEndian::read_i64(&self.buffer[self.position..]);
-                self.position += 8;
+                let value = BigEndian::read_i64(data);
                 // Amount类型：先解析为i64，验证小于999999999999999，然后除以TYPE_AMOUNT_SCALE转成float
                 if !validate_amount(value) {
-                    return Err(MessageError::ValueExceedsRange(format!("Amount value {} exceeds maximum limit", value)));
+                    let error = MessageError::ValueExceedsRange(format!("Amount value {} exceeds maximum limit", value))
+                        .expected(format!("{}..={}", TYPE_AMOUNT_MIN, TYPE_AMOUNT_MAX), value);
+                    return self.invalid(error, FieldValue::I64(value));
                 }
                 let float_value = value as f64 / TYPE_AMOUNT_SCALE;
                 Ok(FieldValue::Float(float_value))
             },
             FieldType::Date => {
-                if self.position + 4 > self.buffer.len() {
-                    return Err(MessageError::BodyTooShort);
-                }
-                let value = BigEndian::read_u32(&self.buffer[self.position..]);
-                self.position += 4;
+                let value = BigEndian::read_u32(data);
                 
                 // 验证Date格式 YYYYMMDD
                 if !validate_date_format(value) {
-                    return Err(MessageError::InvalidFieldValue(format!(
+                    return self.invalid(MessageError::InvalidFieldValue(format!(
                         "Invalid date format: {}. Expected YYYYMMDD format with valid year (0000-9999), month (01-12), and day (01-31)", 
                         value
-                    )));
+                    )).expected("YYYYMMDD", value), FieldValue::U32(value));
                 }
                 
                 Ok(FieldValue::U32(value))
             },
             FieldType::NTime => {
-                if self.position + 8 > self.buffer.len() {
-                    return Err(MessageError::BodyTooShort);
-                }
-                let value = BigEndian::read_u64(&self.buffer[self.position..]);
-                self.position += 8;
+                let value = BigEndian::read_u64(data);
                 
                 // 验证NTime格式 HHMMSSsssnnnn
                 if !validate_ntime_format(value) {
-                    return Err(MessageError::InvalidFieldValue(format!(
+                    return self.invalid(MessageError::InvalidFieldValue(format!(
                         "Invalid ntime format: {}. Expected HHMMSSsssnnnn format with valid hour (00-23), minute (00-59), second (00-59), millisecond (000-999), and hundred nanosecond (0000-9999)", 
                         value
-                    )));
+                    )).expected("HHMMSSsssnnnn", value), FieldValue::U64(value));
                 }
                 
                 Ok(FieldValue::U64(value))
             },
-            FieldType::Array => {
-                // 如果是数组类型，需要完整的字段定义
-                let field_def = field_def.ok_or_else(|| {
-                    MessageError::ArrayElementDecodeError(format!("Array field {} missing field definition", base_field_def.name))
+            FieldType::Bytes => Ok(FieldValue::Bytes(data[..base_field_def.length.unwrap_or_default()].to_vec())),
+            FieldType::Array => Err(MessageError::FieldDecodeError(format!("Array field {} has no fixed size", base_field_def.name)).into()),
+        }
+    }
+
+    /// 解码原始字节字段
+    ///
+    /// 配置了 `length` 时按定长读取，否则先解码 `length_field` 得到字节数
+    fn decode_bytes(&mut self, base_field_def: &BaseFieldDef, field_def: Option<&FieldDef>) -> ContextResult<FieldValue> {
+        let length = match base_field_def.length {
+            Some(length) => length,
+            None => {
+                let length_field_def = field_def.and_then(|def| def.length_field.as_ref()).ok_or_else(|| {
+                    MessageError::FieldDecodeError(format!("Bytes field {} missing length or length field", base_field_def.name))
                 })?;
-                self.decode_array(field_def)
+                match self.decode_field(length_field_def, None)? {
+                    FieldValue::U8(v) => v as usize,
+                    FieldValue::U16(v) => v as usize,
+                    FieldValue::U32(v) => v as usize,
+                    _ => return Err(MessageError::FieldDecodeError(format!(
+                        "Bytes field {} length field {} must be u8, u16 or u32",
+                        base_field_def.name, length_field_def.name
+                    )).into()),
+                }
             },
-        }
+        };
+
+        self.ensure(length)?;
+
+        let bytes = self.buffer[self.position..self.position + length].to_vec();
+        self.position += length;
+        Ok(FieldValue::Bytes(bytes))
     }
 
     /// 解码数组字段
-    fn decode_array(&mut self, field_def: &FieldDef) -> MessageResult<FieldValue> {
+    fn decode_array(&mut self, field_def: &FieldDef) -> ContextResult<FieldValue> {
         // 获取数组长度字段定义
         let length_field_def = field_def.length_field.as_ref().ok_or_else(|| {
             MessageError::ArrayCountDecodeError(format!("Array field {} missing length field", field_def.base.name))
@@ -271,7 +408,7 @@ impl<'a> MessageDecoder<'a> {
             FieldValue::U8(v) => v as usize,
             FieldValue::U16(v) => v as usize,
             FieldValue::U32(v) => v as usize,
-            _ => return Err(MessageError::InvalidArrayCountType),
+            other => return Err(MessageError::InvalidArrayCountType.expected("u8, u16 or u32", other.variant_name())),
         };
         
         // 获取数组元素结构定义
@@ -281,13 +418,16 @@ impl<'a> MessageDecoder<'a> {
         
         // 解码数组元素
         let mut array_elements = Vec::with_capacity(length);
-        for _ in 0..length {
-            let mut element = Vec::with_capacity(struct_def.fields.len());
+        for index in 0..length {
+            let mut element = Record::with_capacity(struct_def.fields.len());
             
             // 解码每个元素的字段
             for field in &struct_def.fields {
-                let field_value = self.decode_field(field, None)?;
-                element.push(field_value);
+                let start = self.position;
+                let field_value = self.decode_wrapped(field, None, |e| {
+                    e.in_field(&field.name).in_field(&format!("[{}]", index)).at(start)
+                })?;
+                element.insert(field.name.clone(), field_value);
             }
             
             array_elements.push(element);
@@ -494,19 +634,17 @@ const CONFIG_SUBSET_STR: &str = r#"<messages>
         let mut array_elements = Vec::new();
         
         // 第一个元素
-        let element1 = vec![
-            FieldValue::Str("PBU001".to_string()),
-            FieldValue::U32(1),
-            FieldValue::U64(100),
-        ];
+        let element1 = Record::new()
+            .with("Pbu", "PBU001")
+            .with("SetID", 1u32)
+            .with("BeginReportIndex", 100u64);
         array_elements.push(element1);
         
         // 第二个元素
-        let element2 = vec![
-            FieldValue::Str("PBU002".to_string()),
-            FieldValue::U32(2),
-            FieldValue::U64(200),
-        ];
+        let element2 = Record::new()
+            .with("Pbu", "PBU002")
+            .with("SetID", 2u32)
+            .with("BeginReportIndex", 200u64);
         array_elements.push(element2);
         
         message.add_field("SyncRequests".to_string(), FieldValue::Array(array_elements));
@@ -626,15 +764,15 @@ const CONFIG_SUBSET_STR: &str = r#"<messages>
             
             // 验证第一个元素
             let element1 = &elements[0];
-            assert_eq!(element1[0].to_string().trim(), "PBU001");
-            assert_eq!(element1[1].as_u32().unwrap(), 1);
-            assert_eq!(element1[2].as_u64().unwrap(), 100);
+            assert_eq!(element1["Pbu"].to_string().trim(), "PBU001");
+            assert_eq!(element1["SetID"].as_u32().unwrap(), 1);
+            assert_eq!(element1["BeginReportIndex"].as_u64().unwrap(), 100);
             
             // 验证第二个元素
             let element2 = &elements[1];
-            assert_eq!(element2[0].to_string().trim(), "PBU002");
-            assert_eq!(element2[1].as_u32().unwrap(), 2);
-            assert_eq!(element2[2].as_u64().unwrap(), 200);
+            assert_eq!(element2["Pbu"].to_string().trim(), "PBU002");
+            assert_eq!(element2["SetID"].as_u32().unwrap(), 2);
+            assert_eq!(element2["BeginReportIndex"].as_u64().unwrap(), 200);
         } else {
             panic!("Expected array field");
         }
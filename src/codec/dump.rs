@@ -0,0 +1,281 @@
+use std::fmt;
+use std::ops::Range;
+
+use crate::codec::decoder::FieldReader;
+use crate::codec::layout::FieldsLayout;
+use crate::codec::types::{split_frame, verify_checksum, MessageHeader};
+use crate::codec::ChecksumMode;
+use crate::config::manager::ConfigManager;
+use crate::config::types::{BaseFieldDef, FieldDef, FieldType};
+use crate::message::pretty::{format_value, symbol_of};
+use crate::message::{FieldValue, Message};
+use crate::util::{ContextError, MessageError};
+
+/// 每行显示的字节数，较长的字段折行显示
+const BYTES_PER_ROW: usize = 16;
+
+/// 带字段标注的十六进制转储
+///
+/// 按消息定义把帧中的每段字节对应到头部字段、消息字段、数组个数、数组元素、扩展字段和校验和，
+/// 每行显示偏移范围、十六进制字节、字段名和解码后的值；取值无效、长度不足和校验和不一致的字节
+/// 在下一行用 `^^` 标出并附上错误：
+///
+/// ```ignore
+/// print!("{}", HexDump::new(&config_manager, &frame));
+/// // 0000..0004  00 00 00 3a      MsgType        = 58 (NewOrderSingle)
+/// // 0004..0008  00 00 00 09      SeqNum         = 9
+/// // ...
+/// // 0037..0042                     Parties[1]
+/// // 0037..0041  50 ff 30 32          PartyID      = 50ff3032
+/// //                ^^              offset 38, expected UTF-8 text, got byte 0xff: ...
+/// // 0042..0046  01 34 db f7      TradeDate      = 20241399
+/// //             ^^ ^^ ^^ ^^      expected YYYYMMDD, got 20241399: ...
+/// ```
+///
+/// 出错的字段之后无法定位的字节合并为一行，消息体被截断时没有校验和行
+#[derive(Debug)]
+pub struct HexDump<'a> {
+    frame: &'a [u8],
+    /// 消息体结束位置，消息体被截断时为帧的末尾
+    body_end: usize,
+    lines: Vec<DumpLine>,
+}
+
+/// 转储中的一行
+#[derive(Debug)]
+pub struct DumpLine {
+    /// 在帧中的字节范围
+    pub range: Range<usize>,
+    /// 嵌套层级，数组元素和扩展字段缩进显示
+    pub depth: usize,
+    /// 字段名，数组元素为 `Name[i]`
+    pub name: String,
+    /// 格式化后的值，分组行和无法解码的字节为 `None`
+    pub value: Option<String>,
+    /// 是否为数组元素或扩展部分这类分组行，分组行不显示字节，组内的字段在其后缩进显示
+    pub group: bool,
+    /// 这段字节未通过校验时的错误
+    pub error: Option<ContextError>,
+    /// 需要标出的字节，通常是整行，UTF-8 错误只标出无效的字节
+    pub highlight: Range<usize>,
+}
+
+impl DumpLine {
+    fn new(range: Range<usize>, depth: usize, name: impl Into<String>, value: Option<String>) -> Self {
+        Self { highlight: range.clone(), range, depth, name: name.into(), value, group: false, error: None }
+    }
+
+    fn group(start: usize, depth: usize, name: impl Into<String>) -> Self {
+        Self { group: true, ..Self::new(start..start, depth, name, None) }
+    }
+
+    fn with_error(mut self, error: Option<ContextError>) -> Self {
+        self.error = error;
+        self
+    }
+}
+
+impl<'a> HexDump<'a> {
+    /// 按 `config_manager` 中的消息定义和校验和算法转储一帧
+    pub fn new(config_manager: &ConfigManager, frame: &'a [u8]) -> Self {
+        let mut dump = Self { frame, body_end: frame.len(), lines: Vec::new() };
+        dump.walk(config_manager);
+        dump
+    }
+
+    /// 全部行
+    pub fn lines(&self) -> &[DumpLine] {
+        &self.lines
+    }
+
+    /// 是否有未通过校验的字节
+    pub fn has_errors(&self) -> bool {
+        self.lines.iter().any(|line| line.error.is_some())
+    }
+
+    fn push(&mut self, line: DumpLine) {
+        self.lines.push(line);
+    }
+
+    /// 分组行的范围延伸到组内最后一行的末尾，组内字段被截断时也包含截断处的字节
+    fn close_group(&mut self, index: usize) {
+        let end = self.lines.last().map_or(0, |line| line.range.end);
+        let group = &mut self.lines[index];
+        group.range.end = group.range.end.max(end);
+    }
+
+    fn walk(&mut self, config_manager: &ConfigManager) {
+        let frame = self.frame;
+        let header = match MessageHeader::parse(frame) {
+            Ok(header) => header,
+            Err(_) => {
+                self.push(DumpLine::new(0..frame.len(), 0, "Header", None).with_error(split_frame(frame).err()));
+                return;
+            },
+        };
+
+        let layout = config_manager.get_layout(header.msg_type);
+        let msg_type = match layout {
+            Some(layout) => DumpLine::new(0..4, 0, "MsgType", Some(format!("{} ({})", header.msg_type, layout.name))),
+            None => DumpLine::new(0..4, 0, "MsgType", Some(header.msg_type.to_string()))
+                .with_error(Some(MessageError::UnknownMessageType(header.msg_type).into())),
+        };
+        self.push(msg_type);
+        self.push(DumpLine::new(4..8, 0, "SeqNum", Some(header.seq_num.to_string())));
+        let frame_error = split_frame(frame).err();
+        let truncated = frame_error.is_some();
+        self.push(DumpLine::new(8..12, 0, "BodyLength", Some(header.body_length.to_string())).with_error(frame_error));
+
+        let body_end = (MessageHeader::SIZE + header.body_length as usize).min(frame.len());
+        self.body_end = body_end;
+        let mut reader = FieldReader::lenient(&frame[..body_end], MessageHeader::SIZE);
+        if let Some(layout) = layout {
+            let mut message = Message::new(header.msg_type, header.seq_num);
+            let walked = self.walk_fields(&mut reader, layout.body(), 0, &mut message).and_then(|_| {
+                let extension = message.get_field("BizID")
+                    .and_then(FieldValue::as_u32)
+                    .and_then(|biz_id| layout.extension(biz_id).map(|extension| (biz_id, extension)));
+                match extension {
+                    Some((biz_id, extension)) => {
+                        let start = reader.position();
+                        let index = self.lines.len();
+                        self.push(DumpLine::group(start, 0, format!("Extension (BizID {})", biz_id)));
+                        let result = self.walk_fields(&mut reader, extension, 1, &mut message);
+                        self.close_group(index);
+                        result
+                    },
+                    None => Some(()),
+                }
+            });
+            if walked.is_none() {
+                return;
+            }
+        }
+
+        // 未知消息类型的消息体以及消息定义之外的字节
+        if reader.position() < body_end {
+            let name = if layout.is_some() { "<unparsed>" } else { "<body>" };
+            self.push(DumpLine::new(reader.position()..body_end, 0, name, None));
+        }
+        if truncated {
+            return;
+        }
+
+        let trailer = body_end..body_end + 4;
+        let checksum = u32::from_be_bytes([frame[body_end], frame[body_end + 1], frame[body_end + 2], frame[body_end + 3]]);
+        let error = verify_checksum(frame, body_end, config_manager.checksum(), ChecksumMode::Strict).err();
+        self.push(DumpLine::new(trailer, 0, "Checksum", Some(format!("{:#010x}", checksum))).with_error(error));
+        if frame.len() > body_end + 4 {
+            self.push(DumpLine::new(body_end + 4..frame.len(), 0, "<trailing>", None));
+        }
+    }
+
+    /// 逐个字段添加行，遇到无法继续定位的错误时把剩余字节合并为一行并返回 `None`
+    fn walk_fields(&mut self, reader: &mut FieldReader, fields: &FieldsLayout, depth: usize, message: &mut Message) -> Option<()> {
+        for field in fields.fields() {
+            match (&field.def.base.r#type, &field.def.length_field, &field.def.r#struct) {
+                (FieldType::Array, Some(length_field), Some(struct_def)) => {
+                    let count = self.walk_field(reader, length_field, None, depth)?;
+                    let count = match count {
+                        FieldValue::U8(v) => v as usize,
+                        FieldValue::U16(v) => v as usize,
+                        FieldValue::U32(v) => v as usize,
+                        other => {
+                            let error = MessageError::InvalidArrayCountType.expected("u8, u16 or u32", other.variant_name());
+                            if let Some(line) = self.lines.last_mut() {
+                                line.error = Some(error);
+                            }
+                            return None;
+                        },
+                    };
+                    for index in 0..count {
+                        let start = reader.position();
+                        let line = self.lines.len();
+                        self.push(DumpLine::group(start, depth + 1, format!("{}[{}]", field.name(), index)));
+                        let walked = struct_def.fields.iter()
+                            .try_for_each(|element| self.walk_field(reader, element, None, depth + 2).map(|_| ()));
+                        self.close_group(line);
+                        walked?;
+                    }
+                },
+                _ => {
+                    let value = self.walk_field(reader, &field.def.base, Some(&field.def), depth)?;
+                    message.add_field(field.name().to_string(), value);
+                },
+            }
+        }
+        Some(())
+    }
+
+    /// 解码一个字段并添加一行，数组只在定义不完整时走到这里
+    fn walk_field(&mut self, reader: &mut FieldReader, def: &BaseFieldDef, field_def: Option<&FieldDef>, depth: usize) -> Option<FieldValue> {
+        let start = reader.position();
+        match reader.decode_field(def, field_def) {
+            Ok(value) => {
+                let range = start..reader.position();
+                let mut text = format_value(def, &value);
+                if let Some(symbol) = symbol_of(def, &value) {
+                    text = format!("{} ({})", text, symbol);
+                }
+                let error = reader.take_diagnostics().into_iter().next();
+                let highlight = error.as_ref()
+                    .and_then(|error| error.context.offset)
+                    .filter(|offset| range.contains(offset))
+                    .map_or(range.clone(), |offset| offset..offset + 1);
+                let mut line = DumpLine::new(range, depth, def.name.clone(), Some(text)).with_error(error);
+                line.highlight = highlight;
+                self.push(line);
+                Some(value)
+            },
+            Err(e) => {
+                let range = start..self.body_end.max(start);
+                self.push(DumpLine::new(range, depth, def.name.clone(), None).with_error(Some(e)));
+                None
+            },
+        }
+    }
+}
+
+impl fmt::Display for HexDump<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self.lines.iter().filter(|line| !line.group).map(|line| line.depth * 2 + line.name.chars().count()).max().unwrap_or_default();
+        for line in &self.lines {
+            let label = format!("{}{}", "  ".repeat(line.depth), line.name);
+            if line.group {
+                writeln!(f, "{:04}..{:04}  {:hex$}  {}", line.range.start, line.range.end, "", label, hex = BYTES_PER_ROW * 3)?;
+                continue;
+            }
+
+            let rows: Vec<Range<usize>> = (line.range.start..line.range.end.max(line.range.start + 1))
+                .step_by(BYTES_PER_ROW)
+                .map(|start| start..(start + BYTES_PER_ROW).min(line.range.end))
+                .collect();
+            for (index, row) in rows.iter().enumerate() {
+                let cells: Vec<String> = self.frame[row.clone()].iter().map(|byte| hex::encode([*byte])).collect();
+                write!(f, "{:04}..{:04}  {:hex$}", row.start, row.end, cells.join(" "), hex = BYTES_PER_ROW * 3)?;
+                if index == 0 {
+                    match &line.value {
+                        Some(value) => write!(f, "  {:width$} = {}", label, value, width = width)?,
+                        None => write!(f, "  {}", label)?,
+                    }
+                }
+                writeln!(f)?;
+
+                // 标出这一行中未通过校验的字节
+                if let Some(error) = &line.error
+                    && (row.start < line.highlight.end && line.highlight.start < row.end || row.is_empty())
+                {
+                    let marks: Vec<&str> = row.clone()
+                        .map(|offset| if line.highlight.contains(&offset) { "^^" } else { "  " })
+                        .collect();
+                    write!(f, "{:10}  {:hex$}", "", marks.join(" "), hex = BYTES_PER_ROW * 3)?;
+                    if index == rows.len() - 1 || !line.highlight.contains(&row.end) {
+                        write!(f, "  {}", error)?;
+                    }
+                    writeln!(f)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -1,10 +1,13 @@
 use byteorder::{BigEndian, ByteOrder};
+use bytes::BufMut;
 
-use crate::codec::types::MessageHeader;
+use crate::codec::checksum::ChecksumState;
+use crate::codec::layout::{FieldSize, FieldsLayout, MessageLayout};
+use crate::codec::types::{truncate_str, MessageHeader};
 use crate::config::manager::ConfigManager;
 use crate::config::types::{FieldDef, FieldType, BaseFieldDef};
 use crate::message::{Message, FieldValue};
-use crate::util::{MessageError, MessageResult};
+use crate::util::{ContextError, ContextResult, MessageError, MessageResult};
 use crate::codec::types::{
     TYPE_PRICE_SCALE,
     TYPE_QUANTITY_SCALE,
@@ -15,189 +18,320 @@ use crate::codec::types::{
     validate_date_format, 
     validate_ntime_format,
 };
+use crate::codec::coerce::coerce_field_value;
 
 /// 消息编码器，用于将 Message 对象编码为二进制数据
 pub struct MessageEncoder<'a> {
     /// 配置管理器，用于获取消息定义
     config_manager: &'a ConfigManager,
-    /// 编码缓冲区
-    buffer: Vec<u8>,
+}
+
+/// 按字段定义向输出缓冲区写入字段值，同时累加校验和
+struct FieldWriter<'b, B: BufMut> {
+    /// 输出缓冲区，为 `None` 时只校验字段值不写入
+    out: Option<&'b mut B>,
+    /// 已写入数据的校验和
+    checksum: ChecksumState<'b>,
+    /// 已写入的字节数
+    written: usize,
 }
 
 impl<'a> MessageEncoder<'a> {
     /// 创建一个新的消息编码器
     pub fn new(config_manager: &'a ConfigManager) -> Self {
-        Self {
-            config_manager,
-            buffer: Vec::new(),
-        }
+        Self { config_manager }
     }
 
-    /// 编码消息
+    /// 编码消息，返回按编码长度一次分配的帧
     pub fn encode(&mut self, message: &Message) -> MessageResult<Vec<u8>> {
-        // 清空缓冲区
-        self.buffer.clear();
-        
-        // 获取消息定义
-        let message_def = self.config_manager.get_message_def(message.msg_type)
-            .ok_or_else(|| MessageError::UnknownMessageType(message.msg_type))?;
+        Ok(self.encode_with_context(message)?)
+    }
 
-        // 预留消息头部空间
-        self.buffer.resize(MessageHeader::SIZE, 0);
-        
-        // 编码消息字段
-        for field_def in &message_def.fields {
-            let field_value = message.get_field(&field_def.base.name)
-                .cloned()
-                .unwrap_or_else(|| self.get_default_value(&field_def.base, Some(field_def)));
-            
-            self.encode_field(&field_def.base, Some(field_def), &field_value)?;
+    /// 编码消息，出错时返回带上下文的错误，记录消息类型、序列号、字段路径和字节偏移
+    pub fn encode_with_context(&mut self, message: &Message) -> ContextResult<Vec<u8>> {
+        let mut frame = Vec::with_capacity(self.encoded_len_with_context(message)?);
+        self.encode_append(message, &mut frame)?;
+        Ok(frame)
+    }
+
+    /// 将消息直接编码到调用方提供的缓冲区，返回写入的字节数
+    ///
+    /// 写入前检查缓冲区剩余空间，并先完整校验一遍所有字段值；任何错误都不会在缓冲区中留下部分数据
+    pub fn encode_into<B: BufMut>(&mut self, message: &Message, buf: &mut B) -> MessageResult<usize> {
+        Ok(self.encode_frame(message, buf, true).map_err(|e| self.message_error(e, message))?)
+    }
+
+    /// 追加到调用方持有的 `Vec` 末尾，不预先校验；出错时由调用方截断到追加前的长度
+    pub(super) fn encode_append(&mut self, message: &Message, buf: &mut Vec<u8>) -> ContextResult<usize> {
+        self.encode_frame(message, buf, false).map_err(|e| self.message_error(e, message))
+    }
+
+    fn encode_frame<B: BufMut>(&mut self, message: &Message, buf: &mut B, validate: bool) -> ContextResult<usize> {
+        let layout = self.layout(message)?;
+        let extension = Self::extension(layout, message)?;
+        let len = Self::frame_len(layout, extension, message)?;
+        if buf.remaining_mut() < len {
+            return Err(MessageError::BufferTooSmall(len, buf.remaining_mut())
+                .expected(format!("{} bytes", len), format!("{} bytes", buf.remaining_mut())));
         }
 
-        // 编码扩展字段
-        if message.has_field("BizID") && message_def.extensions.len() > 0 {
-            let msg_type = message.msg_type;
-            let biz_id = message.get_field("BizID").unwrap().as_u32().unwrap();
-            let biz_extension = self.config_manager.get_extension(msg_type, biz_id);
-            
-            if let Some(biz_extension) = biz_extension {
-                for field_def in &biz_extension.fields {
-                    let field_value = message.get_field(&field_def.name)
-                        .cloned()
-                        .unwrap_or_else(|| self.get_default_value(&field_def, None));
-                    self.encode_field(&field_def, None, &field_value)?;
-                }
-            }
+        // 只校验不写入，走与写入相同的编码路径
+        if validate {
+            let mut writer = FieldWriter::<B> { out: None, checksum: ChecksumState::new(self.config_manager.checksum()), written: MessageHeader::SIZE };
+            writer.encode_body(layout, extension, message)?;
         }
-        
-        // 计算消息体长度
-        let body_length = (self.buffer.len() - MessageHeader::SIZE) as u32;
-        
+
+        let checksum = ChecksumState::new(self.config_manager.checksum());
+        let mut writer = FieldWriter { out: Some(buf), checksum, written: 0 };
+
         // 编码消息头部
-        BigEndian::write_u32(&mut self.buffer[0..4], message.msg_type);
-        BigEndian::write_u32(&mut self.buffer[4..8], message.seq_num);
-        BigEndian::write_u32(&mut self.buffer[8..12], body_length);
-        
-        // 计算并添加校验和
-        self.append_checksum();
-        
-        Ok(self.buffer.clone())
+        let body_length = (len - MessageHeader::SIZE - 4) as u32;
+        let mut header = [0u8; MessageHeader::SIZE];
+        BigEndian::write_u32(&mut header[0..4], message.msg_type);
+        BigEndian::write_u32(&mut header[4..8], message.seq_num);
+        BigEndian::write_u32(&mut header[8..12], body_length);
+        writer.put(&header);
+
+        // 编码消息字段和扩展字段
+        writer.encode_body(layout, extension, message)?;
+        debug_assert_eq!(writer.written, len - 4, "encoded length mismatch");
+
+        // 添加校验和
+        let checksum = writer.checksum.finish();
+        if let Some(out) = writer.out {
+            out.put_u32(checksum);
+        }
+        Ok(len)
     }
-    
-    /// 获取字段的默认值
-    fn get_default_value(&self, base_field_def: &BaseFieldDef, _field_def: Option<&FieldDef>) -> FieldValue {
-        match base_field_def.r#type {
-            FieldType::U8 => FieldValue::U8(0),
-            FieldType::U16 => FieldValue::U16(0),
-            FieldType::U32 => FieldValue::U32(0),
-            FieldType::U64 => FieldValue::U64(0),
-            FieldType::I64 => FieldValue::I64(0),
-            FieldType::Price => FieldValue::Float(0.0),
-            FieldType::Quantity => FieldValue::Float(0.0),
-            FieldType::Amount => FieldValue::Float(0.0),
-            FieldType::Date => FieldValue::U32(0),
-            FieldType::NTime => FieldValue::U64(0),
-            FieldType::Char => {
-                let length = base_field_def.length.unwrap_or(1);
-                FieldValue::Str(" ".repeat(length))
+
+    /// 将消息编码到定长切片中，返回写入的字节数，切片长度不足时报错
+    pub fn encode_to_slice(&mut self, message: &Message, buf: &mut [u8]) -> MessageResult<usize> {
+        let mut out = buf;
+        self.encode_into(message, &mut out)
+    }
+
+    /// 计算消息编码后的完整帧长度，包括头部和校验和
+    pub fn encoded_len(&self, message: &Message) -> MessageResult<usize> {
+        Ok(self.encoded_len_with_context(message)?)
+    }
+
+    fn encoded_len_with_context(&self, message: &Message) -> ContextResult<usize> {
+        let layout = self.layout(message).map_err(|e| self.message_error(e, message))?;
+        Self::extension(layout, message)
+            .and_then(|extension| Self::frame_len(layout, extension, message))
+            .map_err(|e| self.message_error(e, message))
+    }
+
+    /// 在错误上下文中记录消息类型、名称和序列号
+    fn message_error(&self, error: ContextError, message: &Message) -> ContextError {
+        let name = self.config_manager.get_layout(message.msg_type).map(|layout| layout.name.as_str());
+        error.in_message(message.msg_type, name, Some(message.seq_num))
+    }
+
+    fn layout(&self, message: &Message) -> ContextResult<&'a MessageLayout> {
+        self.config_manager.get_layout(message.msg_type)
+            .ok_or_else(|| MessageError::UnknownMessageType(message.msg_type).into())
+    }
+
+    /// 按消息中的 BizID 查找扩展字段布局
+    fn extension<'l>(layout: &'l MessageLayout, message: &Message) -> ContextResult<Option<&'l FieldsLayout>> {
+        let Some(biz_id) = message.get_field("BizID").filter(|_| layout.has_extensions()) else {
+            return Ok(None);
+        };
+        // BizID 可能以其他数值类型设置，按其字段定义转换后再查找扩展
+        let biz_id = match layout.body().index_of("BizID") {
+            Some(index) => coerce_field_value(&layout.body().fields()[index].def.base, biz_id)
+                .map_err(|e| e.in_field("BizID"))?
+                .as_u32()
+                .unwrap_or_default(),
+            None => biz_id.as_u32().unwrap_or_default(),
+        };
+        Ok(layout.extension(biz_id))
+    }
+
+    fn frame_len(layout: &MessageLayout, extension: Option<&FieldsLayout>, message: &Message) -> ContextResult<usize> {
+        let body_len = match layout.fixed_body_len() {
+            Some(fixed_len) => fixed_len,
+            None => {
+                let extension_len = match extension {
+                    Some(extension) => Self::fields_len(extension, message)?,
+                    None => 0,
+                };
+                Self::fields_len(layout.body(), message)? + extension_len
             },
-            FieldType::Array => {
-                // 对于数组类型，返回空数组
-                FieldValue::Array(Vec::new())
+        };
+        Ok(MessageHeader::SIZE + body_len + 4)
+    }
+
+    /// 一组字段编码后的字节数，变长字段按消息中的值计算
+    fn fields_len(fields: &FieldsLayout, message: &Message) -> ContextResult<usize> {
+        if let Some(fixed_len) = fields.fixed_len() {
+            return Ok(fixed_len);
+        }
+        fields.fields().iter().map(|field| match field.size {
+            FieldSize::Fixed(size) => Ok(size),
+            FieldSize::Prefixed { prefix, unit } => {
+                let count = match message.get_field(field.name()) {
+                    Some(FieldValue::Bytes(bytes)) => bytes.len(),
+                    Some(FieldValue::Array(elements)) => elements.len(),
+                    _ => 0,
+                };
+                // 计数字段放不下时编码会报错，这里同样报错，不返回按截断后的计数算出的长度
+                if prefix < 8 && count >> (prefix * 8) != 0 {
+                    return Err(MessageError::ValueExceedsRange(format!(
+                        "Field {} count {} exceeds {}-byte count field", field.name(), count, prefix
+                    )).in_field(field.name()));
+                }
+                Ok(prefix + count * unit)
             },
+            FieldSize::Unknown => Err(MessageError::FieldEncodeError(format!(
+                "Field {} has incomplete definition, size unknown", field.name()
+            )).in_field(field.name())),
+        }).sum()
+    }
+}
+
+/// 获取字段的默认值
+fn default_value(base_field_def: &BaseFieldDef) -> FieldValue {
+    match base_field_def.r#type {
+        FieldType::U8 => FieldValue::U8(0),
+        FieldType::U16 => FieldValue::U16(0),
+        FieldType::U32 => FieldValue::U32(0),
+        FieldType::U64 => FieldValue::U64(0),
+        FieldType::I8 => FieldValue::I8(0),
+        FieldType::I16 => FieldValue::I16(0),
+        FieldType::I32 => FieldValue::I32(0),
+        FieldType::I64 => FieldValue::I64(0),
+        FieldType::Price => FieldValue::Float(0.0),
+        FieldType::Quantity => FieldValue::Float(0.0),
+        FieldType::Amount => FieldValue::Float(0.0),
+        FieldType::Date => FieldValue::U32(0),
+        FieldType::NTime => FieldValue::U64(0),
+        FieldType::Char => {
+            let length = base_field_def.length.unwrap_or(1);
+            FieldValue::Str(" ".repeat(length))
+        },
+        FieldType::Bytes => {
+            // 定长字节填充0，变长字节默认为空
+            let length = base_field_def.length.unwrap_or(0);
+            FieldValue::Bytes(vec![0; length])
+        },
+        FieldType::Array => {
+            // 对于数组类型，返回空数组
+            FieldValue::Array(Vec::new())
+        },
+    }
+}
+
+impl<B: BufMut> FieldWriter<'_, B> {
+    /// 写入数据并累加校验和
+    fn put(&mut self, bytes: &[u8]) {
+        self.written += bytes.len();
+        if let Some(out) = &mut self.out {
+            self.checksum.update(bytes);
+            out.put_slice(bytes);
         }
     }
-    
-    /// 验证FieldValue与FieldType是否匹配
-    fn validate_field_type_match(field_type: &FieldType, value: &FieldValue) -> bool {
-        match (field_type, value) {
-            (FieldType::U8, FieldValue::U8(_)) => true,
-            (FieldType::U16, FieldValue::U16(_)) => true,
-            (FieldType::U32, FieldValue::U32(_)) => true,
-            (FieldType::U64, FieldValue::U64(_)) => true,
-            (FieldType::I64, FieldValue::I64(_)) => true,
-            (FieldType::Char, FieldValue::Str(_)) => true,
-            (FieldType::Price, FieldValue::Float(_)) => true,
-            (FieldType::Quantity, FieldValue::Float(_)) => true,
-            (FieldType::Amount, FieldValue::Float(_)) => true,
-            (FieldType::Date, FieldValue::U32(_)) => true,
-            (FieldType::NTime, FieldValue::U64(_)) => true,
-            (FieldType::Array, FieldValue::Array(_)) => true,
-            _ => false,
+
+    /// 编码消息字段和扩展字段
+    fn encode_body(&mut self, layout: &MessageLayout, extension: Option<&FieldsLayout>, message: &Message) -> ContextResult<()> {
+        self.encode_fields(layout.body(), message)?;
+        if let Some(extension) = extension {
+            self.encode_fields(extension, message)?;
+        }
+        Ok(())
+    }
+
+    /// 写入 `count` 个填充字节
+    fn put_fill(&mut self, byte: u8, count: usize) {
+        let fill = [byte; 64];
+        let mut remaining = count;
+        while remaining > 0 {
+            let len = remaining.min(fill.len());
+            self.put(&fill[..len]);
+            remaining -= len;
         }
     }
 
-    /// 编码字段
-    fn encode_field(&mut self, base_field_def: &BaseFieldDef, field_def: Option<&FieldDef>, value: &FieldValue) -> MessageResult<()> {
-        // 检查FieldValue类型与FieldDef类型是否一致
-        if !Self::validate_field_type_match(&base_field_def.r#type, value) {
-            return Err(MessageError::InvalidFieldValue(format!(
-                "Field '{}' type mismatch: expected {:?}, got {:?}",
-                base_field_def.name,
-                base_field_def.r#type,
-                std::mem::discriminant(value)
-            )));
+    /// 按布局依次编码一组字段，消息中缺少的字段使用默认值；出错时在错误上下文中记录字段名和起始偏移
+    fn encode_fields(&mut self, fields: &FieldsLayout, message: &Message) -> ContextResult<()> {
+        for field in fields.fields() {
+            let def = &field.def;
+            let start = self.written;
+            let result = match message.get_field(field.name()) {
+                Some(value) => self.encode_field(&def.base, Some(def), value),
+                None => self.encode_field(&def.base, Some(def), &default_value(&def.base)),
+            };
+            result.map_err(|e| e.in_field(field.name()).at(start))?;
         }
+        Ok(())
+    }
+
+    /// 编码字段
+    fn encode_field(&mut self, base_field_def: &BaseFieldDef, field_def: Option<&FieldDef>, value: &FieldValue) -> ContextResult<()> {
+        // 将FieldValue转换为FieldDef要求的类型，无法无损转换时报错
+        let value = coerce_field_value(base_field_def, value)
+            .map_err(|e| e.expected(format!("{:?}", base_field_def.r#type), format!("{:?}", value)))?;
+        let value = value.as_ref();
         match base_field_def.r#type {
             FieldType::U8 => {
-                let val = match value {
-                    FieldValue::U8(v) => *v,
-                    _ => 0,
-                };
-                self.buffer.push(val);
+                let val: u8 = Self::integer_value(base_field_def, value)?;
+                self.put(&[val]);
             },
             FieldType::U16 => {
-                let val = match value {
-                    FieldValue::U16(v) => *v,
-                    _ => 0,
-                };
+                let val: u16 = Self::integer_value(base_field_def, value)?;
                 let mut bytes = [0u8; 2];
                 BigEndian::write_u16(&mut bytes, val);
-                self.buffer.extend_from_slice(&bytes);
+                self.put(&bytes);
             },
             FieldType::U32 => {
-                let val = match value {
-                    FieldValue::U32(v) => *v,
-                    _ => 0,
-                };
+                let val: u32 = Self::integer_value(base_field_def, value)?;
                 let mut bytes = [0u8; 4];
                 BigEndian::write_u32(&mut bytes, val);
-                self.buffer.extend_from_slice(&bytes);
+                self.put(&bytes);
             },
             FieldType::U64 => {
-                let val = match value {
-                    FieldValue::U64(v) => *v,
-                    _ => 0,
-                };
+                let val: u64 = Self::integer_value(base_field_def, value)?;
                 let mut bytes = [0u8; 8];
                 BigEndian::write_u64(&mut bytes, val);
-                self.buffer.extend_from_slice(&bytes);
+                self.put(&bytes);
+            },
+            FieldType::I8 => {
+                let val: i8 = Self::integer_value(base_field_def, value)?;
+                self.put(&[val as u8]);
+            },
+            FieldType::I16 => {
+                let val: i16 = Self::integer_value(base_field_def, value)?;
+                let mut bytes = [0u8; 2];
+                BigEndian::write_i16(&mut bytes, val);
+                self.put(&bytes);
+            },
+            FieldType::I32 => {
+                let val: i32 = Self::integer_value(base_field_def, value)?;
+                let mut bytes = [0u8; 4];
+                BigEndian::write_i32(&mut bytes, val);
+                self.put(&bytes);
             },
             FieldType::I64 => {
-                let val = match value {
-                    FieldValue::I64(v) => *v,
-                    _ => 0,
-                };
+                let val: i64 = Self::integer_value(base_field_def, value)?;
                 let mut bytes = [0u8; 8];
                 BigEndian::write_i64(&mut bytes, val);
-                self.buffer.extend_from_slice(&bytes);
+                self.put(&bytes);
             },
             FieldType::Char => {
                 let length = base_field_def.length.ok_or_else(|| {
                     MessageError::FieldEncodeError(format!("Char field {} missing length", base_field_def.name))
                 })?;
                 
-                let string_val = match value {
-                    FieldValue::Str(s) => s.clone(),
-                    _ => " ".repeat(length),
+                let value = match value {
+                    FieldValue::Str(s) => s.as_str(),
+                    _ => "",
                 };
                 
-                // 截断或填充字符串到指定长度
-                let mut bytes = string_val.into_bytes();
-                bytes.resize(length, b' '); // 用空格填充
-                bytes.truncate(length); // 截断到指定长度
-                
-                self.buffer.extend_from_slice(&bytes);
+                // 在字符边界处截断到指定长度，不足部分用空格填充
+                let bytes = truncate_str(value, length).as_bytes();
+                self.put(bytes);
+                self.put_fill(b' ', length - bytes.len());
             },
             FieldType::Price => {
                 let val = match value {
@@ -207,7 +341,7 @@ impl<'a> MessageEncoder<'a> {
                         if !validate_price(encoded_val) {
                             return Err(MessageError::ValueExceedsRange(
                                 format!("Price value {} exceeds maximum allowed value (-99999999.99999, 99999999.99999)", v)
-                            ));
+                            ).expected("-99999999.99999..=99999999.99999", v));
                         }
                         encoded_val
                     },
@@ -215,7 +349,7 @@ impl<'a> MessageEncoder<'a> {
                 };
                 let mut bytes = [0u8; 8];
                 BigEndian::write_i64(&mut bytes, val);
-                self.buffer.extend_from_slice(&bytes);
+                self.put(&bytes);
             },
             FieldType::Quantity => {
                 let val = match value {
@@ -225,7 +359,7 @@ impl<'a> MessageEncoder<'a> {
                         if !validate_quantity(encoded_val) {
                             return Err(MessageError::ValueExceedsRange(
                                 format!("Quantity value {} exceeds maximum allowed value (-999999999999.999, 999999999999.999)", v)
-                            ));
+                            ).expected("-999999999999.999..=999999999999.999", v));
                         }
                         encoded_val
                     },
@@ -233,7 +367,7 @@ impl<'a> MessageEncoder<'a> {
                 };
                 let mut bytes = [0u8; 8];
                 BigEndian::write_i64(&mut bytes, val);
-                self.buffer.extend_from_slice(&bytes);
+                self.put(&bytes);
             },
             FieldType::Amount => {
                 let val = match value {
@@ -243,7 +377,7 @@ impl<'a> MessageEncoder<'a> {
                         if !validate_amount(encoded_val) {
                             return Err(MessageError::ValueExceedsRange(
                                 format!("Amount value {} exceeds maximum allowed value (-9999999999999.99999, 9999999999999.99999)", v)
-                            ));
+                            ).expected("-9999999999999.99999..=9999999999999.99999", v));
                         }
                         encoded_val
                     },
@@ -251,7 +385,7 @@ impl<'a> MessageEncoder<'a> {
                 };
                 let mut bytes = [0u8; 8];
                 BigEndian::write_i64(&mut bytes, val);
-                self.buffer.extend_from_slice(&bytes);
+                self.put(&bytes);
             },
             FieldType::Date => {
                 let val = match value {
@@ -261,7 +395,7 @@ impl<'a> MessageEncoder<'a> {
                             return Err(MessageError::InvalidFieldValue(format!(
                                 "Invalid date format: {}. Expected YYYYMMDD format with valid year (0000-9999), month (01-12), and day (01-31)", 
                                 v
-                            )));
+                            )).expected("YYYYMMDD", v));
                         }
                         *v
                     },
@@ -269,7 +403,7 @@ impl<'a> MessageEncoder<'a> {
                 };
                 let mut bytes = [0u8; 4];
                 BigEndian::write_u32(&mut bytes, val);
-                self.buffer.extend_from_slice(&bytes);
+                self.put(&bytes);
             },
             FieldType::NTime => {
                 let val = match value {
@@ -279,7 +413,7 @@ impl<'a> MessageEncoder<'a> {
                             return Err(MessageError::InvalidFieldValue(format!(
                                 "Invalid ntime format: {}. Expected HHMMSSsssnnnn format with valid hour (00-23), minute (00-59), second (00-59), millisecond (000-999), and hundred nanosecond (0000-9999)", 
                                 v
-                            )));
+                            )).expected("HHMMSSsssnnnn", v));
                         }
                         *v
                     },
@@ -287,7 +421,14 @@ impl<'a> MessageEncoder<'a> {
                 };
                 let mut bytes = [0u8; 8];
                 BigEndian::write_u64(&mut bytes, val);
-                self.buffer.extend_from_slice(&bytes);
+                self.put(&bytes);
+            },
+            FieldType::Bytes => {
+                let bytes = match value {
+                    FieldValue::Bytes(v) => v.as_slice(),
+                    _ => &[],
+                };
+                self.encode_bytes(base_field_def, field_def, bytes)?;
             },
             FieldType::Array => {
                 // 如果是数组类型，需要完整的字段定义
@@ -300,8 +441,63 @@ impl<'a> MessageEncoder<'a> {
         Ok(())
     }
     
+    /// 取出整数值并检查是否在目标类型的取值范围内
+    fn integer_value<T: TryFrom<i128>>(base_field_def: &BaseFieldDef, value: &FieldValue) -> ContextResult<T> {
+        let integer = value.as_integer().ok_or_else(|| {
+            MessageError::InvalidFieldValue(format!("Field '{}' expects an integer value, got {:?}", base_field_def.name, value))
+        })?;
+        T::try_from(integer).map_err(|_| {
+            MessageError::ValueExceedsRange(format!(
+                "Field '{}' value {} out of range for {:?}",
+                base_field_def.name, integer, base_field_def.r#type
+            )).expected(format!("{:?}", base_field_def.r#type), integer)
+        })
+    }
+
+    /// 编码原始字节字段
+    ///
+    /// 配置了 `length` 时按定长编码，不足部分填充0；否则使用 `length_field` 先写入字节数
+    fn encode_bytes(&mut self, base_field_def: &BaseFieldDef, field_def: Option<&FieldDef>, bytes: &[u8]) -> ContextResult<()> {
+        if let Some(length) = base_field_def.length {
+            if bytes.len() > length {
+                return Err(MessageError::ValueExceedsRange(format!(
+                    "Bytes field {} value length {} exceeds fixed length {}",
+                    base_field_def.name, bytes.len(), length
+                )).expected(format!("at most {} bytes", length), format!("{} bytes", bytes.len())));
+            }
+            self.put(bytes);
+            self.put_fill(0, length - bytes.len());
+            return Ok(());
+        }
+
+        let length_field_def = field_def.and_then(|def| def.length_field.as_ref()).ok_or_else(|| {
+            MessageError::FieldEncodeError(format!("Bytes field {} missing length or length field", base_field_def.name))
+        })?;
+
+        let length = bytes.len();
+        let length_value = match length_field_def.r#type {
+            FieldType::U8 if length <= u8::MAX as usize => FieldValue::U8(length as u8),
+            FieldType::U16 if length <= u16::MAX as usize => FieldValue::U16(length as u16),
+            FieldType::U32 if length <= u32::MAX as usize => FieldValue::U32(length as u32),
+            FieldType::U8 | FieldType::U16 | FieldType::U32 => {
+                return Err(MessageError::ValueExceedsRange(format!(
+                    "Bytes field {} value length {} exceeds length field {} capacity",
+                    base_field_def.name, length, length_field_def.name
+                )).into());
+            },
+            _ => return Err(MessageError::FieldEncodeError(format!(
+                "Bytes field {} length field {} must be u8, u16 or u32",
+                base_field_def.name, length_field_def.name
+            )).into()),
+        };
+
+        self.encode_field(length_field_def, None, &length_value)?;
+        self.put(bytes);
+        Ok(())
+    }
+
     /// 编码数组字段
-    fn encode_array(&mut self, field_def: &FieldDef, value: &FieldValue) -> MessageResult<()> {
+    fn encode_array(&mut self, field_def: &FieldDef, value: &FieldValue) -> ContextResult<()> {
         // 获取数组长度字段定义
         let length_field_def = field_def.length_field.as_ref().ok_or_else(|| {
             MessageError::ArrayCountEncodeError(format!("Array field {} missing length field", field_def.base.name))
@@ -323,57 +519,34 @@ impl<'a> MessageEncoder<'a> {
         // 编码数组长度
         let length = array_elements.len();
         let length_value = match length_field_def.r#type {
-            FieldType::U8 => FieldValue::U8(length as u8),
-            FieldType::U16 => FieldValue::U16(length as u16),
-            FieldType::U32 => FieldValue::U32(length as u32),
-            _ => return Err(MessageError::InvalidArrayCountType),
+            FieldType::U8 if length <= u8::MAX as usize => FieldValue::U8(length as u8),
+            FieldType::U16 if length <= u16::MAX as usize => FieldValue::U16(length as u16),
+            FieldType::U32 if length <= u32::MAX as usize => FieldValue::U32(length as u32),
+            FieldType::U8 | FieldType::U16 | FieldType::U32 => {
+                return Err(MessageError::ValueExceedsRange(format!(
+                    "Array field {} element count {} exceeds length field {} capacity",
+                    field_def.base.name, length, length_field_def.name
+                )).into());
+            },
+            _ => return Err(MessageError::InvalidArrayCountType.into()),
         };
         
         self.encode_field(length_field_def, None, &length_value)?;
         
-        // 编码数组元素
-        for element in array_elements {
-            // 为每个结构字段编码
-            for (i, field) in struct_def.fields.iter().enumerate() {
-                let field_value = element.get(i)
-                    .cloned()
-                    .unwrap_or_else(|| self.get_default_value(field, None));
-                
-                self.encode_field(field, None, &field_value)?;
+        // 编码数组元素，元素字段名必须与结构定义一致，按结构定义的顺序编码
+        for (index, element) in array_elements.iter().enumerate() {
+            let element_start = self.written;
+            element.check_names(&format!("{}[{}]", field_def.base.name, index), struct_def)
+                .map_err(|e| e.in_field(&format!("[{}]", index)).at(element_start))?;
+            for field in &struct_def.fields {
+                let start = self.written;
+                self.encode_field(field, None, &element[field.name.as_str()])
+                    .map_err(|e| e.in_field(&field.name).in_field(&format!("[{}]", index)).at(start))?;
             }
         }
         
         Ok(())
     }
-    
-    /// 计算并添加校验和
-    fn append_checksum(&mut self) {
-        // 计算校验和 - 使用 uint8 累加然后转换为 uint32
-        let mut checksum: u8 = 0;
-        for byte in &self.buffer {
-            checksum = checksum.wrapping_add(*byte);
-        }
-        
-        // 添加校验和到缓冲区
-        let mut checksum_bytes = [0u8; 4];
-        BigEndian::write_u32(&mut checksum_bytes, checksum as u32);
-        self.buffer.extend_from_slice(&checksum_bytes);
-    }
-    
-    /// 获取编码后的数据长度
-    pub fn len(&self) -> usize {
-        self.buffer.len()
-    }
-    
-    /// 检查缓冲区是否为空
-    pub fn is_empty(&self) -> bool {
-        self.buffer.is_empty()
-    }
-    
-    /// 清空缓冲区
-    pub fn clear(&mut self) {
-        self.buffer.clear();
-    }
 }
 
 #[cfg(test)]
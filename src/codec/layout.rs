@@ -0,0 +1,198 @@
+use std::collections::HashMap;
+
+use crate::config::types::{BaseFieldDef, BizExtension, FieldDef, FieldType, MessageDef};
+
+/// 字段在消息体中占用的字节数
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldSize {
+    /// 固定字节数
+    Fixed(usize),
+    /// 由前置计数字段决定：计数字段占 `prefix` 字节，每个计数单位占 `unit` 字节
+    Prefixed { prefix: usize, unit: usize },
+    /// 字段定义不完整（如 char 缺少 length），无法确定大小，读取时报错
+    Unknown,
+}
+
+/// 单个字段的布局
+#[derive(Debug, Clone)]
+pub struct FieldLayout {
+    /// 字段定义
+    pub def: FieldDef,
+    /// 相对消息体起始位置的固定偏移，位于变长字段之后时为 `None`
+    pub offset: Option<usize>,
+    /// 字段大小
+    pub size: FieldSize,
+}
+
+impl FieldLayout {
+    fn new(def: FieldDef, offset: Option<usize>) -> Self {
+        let size = field_size(&def);
+        Self { def, offset, size }
+    }
+
+    /// 字段名
+    pub fn name(&self) -> &str {
+        &self.def.base.name
+    }
+
+    /// 字段类型
+    pub fn field_type(&self) -> &FieldType {
+        &self.def.base.r#type
+    }
+}
+
+/// 一组按顺序排列的字段及其名称索引
+#[derive(Debug, Clone, Default)]
+pub struct FieldsLayout {
+    fields: Vec<FieldLayout>,
+    names: HashMap<String, usize>,
+    /// 第一个非定长字段的下标，该字段本身有固定偏移，此后的字段没有
+    first_variable: Option<usize>,
+}
+
+impl FieldsLayout {
+    fn new(defs: impl IntoIterator<Item = FieldDef>) -> Self {
+        let mut layout = Self::default();
+        let mut offset = Some(0);
+        for def in defs {
+            let index = layout.fields.len();
+            let field = FieldLayout::new(def, offset);
+            offset = match (offset, field.size) {
+                (Some(offset), FieldSize::Fixed(size)) => Some(offset + size),
+                _ => {
+                    layout.first_variable.get_or_insert(index);
+                    None
+                },
+            };
+            layout.names.insert(field.name().to_string(), index);
+            layout.fields.push(field);
+        }
+        layout
+    }
+
+    /// 全部字段
+    pub fn fields(&self) -> &[FieldLayout] {
+        &self.fields
+    }
+
+    /// 按字段名查找下标
+    pub fn index_of(&self, name: &str) -> Option<usize> {
+        self.names.get(name).copied()
+    }
+
+    /// 所有字段都是定长时的总字节数
+    pub fn fixed_len(&self) -> Option<usize> {
+        match self.first_variable {
+            Some(_) => None,
+            None => Some(self.fields.iter().map(|field| match field.size {
+                FieldSize::Fixed(size) => size,
+                _ => 0,
+            }).sum()),
+        }
+    }
+
+    /// 第一个非定长字段的下标，定位其后的字段时从这里开始逐个跳过
+    pub(crate) fn first_variable(&self) -> Option<usize> {
+        self.first_variable
+    }
+}
+
+/// 按消息定义预先计算的消息布局：字段偏移、大小和名称索引
+///
+/// 固定长度部分的字段可以按偏移直接读取，变长字段（数组、带长度字段的 bytes）之后的
+/// 字段需要先跳过前面的变长字段才能定位
+#[derive(Debug, Clone)]
+pub struct MessageLayout {
+    /// 消息类型
+    pub msg_type: u32,
+    /// 消息名称
+    pub name: String,
+    /// 消息字段
+    body: FieldsLayout,
+    /// 各 BizID 的扩展字段，偏移相对于扩展部分的起始位置
+    extensions: HashMap<u32, FieldsLayout>,
+}
+
+impl MessageLayout {
+    /// 根据消息定义计算布局
+    pub fn new(message_def: &MessageDef) -> Self {
+        Self::with_extensions(message_def, &message_def.extensions)
+    }
+
+    /// 根据消息定义和指定的扩展定义计算布局
+    pub(crate) fn with_extensions<'e>(message_def: &MessageDef, extensions: impl IntoIterator<Item = &'e BizExtension>) -> Self {
+        let extensions = extensions.into_iter()
+            .map(|extension| {
+                let defs = extension.fields.iter().cloned().map(|base| FieldDef { base, length_field: None, r#struct: None });
+                (extension.biz_id, FieldsLayout::new(defs))
+            })
+            .collect();
+
+        Self {
+            msg_type: message_def.msg_type,
+            name: message_def.name.clone(),
+            body: FieldsLayout::new(message_def.fields.iter().cloned()),
+            extensions,
+        }
+    }
+
+    /// 消息字段布局
+    pub fn body(&self) -> &FieldsLayout {
+        &self.body
+    }
+
+    /// 指定 BizID 的扩展字段布局
+    pub fn extension(&self, biz_id: u32) -> Option<&FieldsLayout> {
+        self.extensions.get(&biz_id)
+    }
+
+    /// 是否定义了扩展字段
+    pub fn has_extensions(&self) -> bool {
+        !self.extensions.is_empty()
+    }
+
+    /// 消息体长度固定时的字节数，包含变长字段或扩展字段的消息返回 `None`
+    pub fn fixed_body_len(&self) -> Option<usize> {
+        if self.has_extensions() {
+            return None;
+        }
+        self.body.fixed_len()
+    }
+}
+
+/// 基本字段的定长大小，char 和定长 bytes 取 `length`
+pub(crate) fn base_field_size(base: &BaseFieldDef) -> Option<usize> {
+    match base.r#type {
+        FieldType::U8 | FieldType::I8 => Some(1),
+        FieldType::U16 | FieldType::I16 => Some(2),
+        FieldType::U32 | FieldType::I32 | FieldType::Date => Some(4),
+        FieldType::U64 | FieldType::I64 | FieldType::Price | FieldType::Quantity
+            | FieldType::Amount | FieldType::NTime => Some(8),
+        FieldType::Char | FieldType::Bytes => base.length,
+        FieldType::Array => None,
+    }
+}
+
+fn field_size(def: &FieldDef) -> FieldSize {
+    if let Some(size) = base_field_size(&def.base) {
+        return FieldSize::Fixed(size);
+    }
+
+    let prefix = match def.length_field.as_ref().and_then(base_field_size) {
+        Some(prefix) => prefix,
+        None => return FieldSize::Unknown,
+    };
+    match def.base.r#type {
+        FieldType::Bytes => FieldSize::Prefixed { prefix, unit: 1 },
+        FieldType::Array => {
+            let unit = def.r#struct.as_ref().and_then(|struct_def| {
+                struct_def.fields.iter().map(base_field_size).sum::<Option<usize>>()
+            });
+            match unit {
+                Some(unit) => FieldSize::Prefixed { prefix, unit },
+                None => FieldSize::Unknown,
+            }
+        },
+        _ => FieldSize::Unknown,
+    }
+}
@@ -1,9 +1,23 @@
 pub mod types;
+pub mod coerce;
 pub mod decoder;
 pub mod encoder;
+pub mod layout;
+pub mod view;
+pub mod shared;
+pub mod batch;
+pub mod checksum;
+pub mod dump;
 
 pub use types::{MessageHeader, Result};
-pub use decoder::MessageDecoder;
+pub use coerce::coerce_field_value;
+pub use decoder::{LenientMessage, MessageDecoder};
 pub use encoder::MessageEncoder;
+pub use layout::{FieldLayout, FieldSize, FieldsLayout, MessageLayout};
+pub use view::MessageView;
+pub use shared::{SharedDecoder, SharedEncoder};
+pub use checksum::{ChecksumAlgorithm, ChecksumMode, ChecksumState, NoChecksum, Sum8};
+pub use dump::{DumpLine, HexDump};
+pub use batch::{decode_frames_parallel, DecodeFrames, DecodedFrame, EncodedBatch, Frames};
 
 
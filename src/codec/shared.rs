@@ -0,0 +1,128 @@
+use std::sync::Arc;
+
+use bytes::BufMut;
+
+use crate::codec::batch::{DecodeFrames, DecodedFrame, EncodedBatch};
+use crate::codec::checksum::{ChecksumMode, NoChecksum};
+use crate::codec::decoder::{LenientMessage, MessageDecoder};
+use crate::codec::encoder::MessageEncoder;
+use crate::codec::types::MessageHeader;
+use crate::codec::view::MessageView;
+use crate::config::manager::ConfigManager;
+use crate::message::Message;
+use crate::util::{ContextResult, MessageError, MessageResult};
+
+/// 持有共享配置的编码器，可在多个任务之间移动并跨调用复用编码缓冲区
+///
+/// ```ignore
+/// let config_manager = Arc::new(config_manager);
+/// let mut encoder = SharedEncoder::new(config_manager.clone());
+/// tokio::spawn(async move {
+///     let frame = encoder.encode(&message)?;
+///     stream.write_all(frame).await?;
+/// });
+/// ```
+#[derive(Debug, Clone)]
+pub struct SharedEncoder {
+    /// 共享的配置管理器
+    config_manager: Arc<ConfigManager>,
+    /// 编码缓冲区，每次编码前清空
+    buffer: Vec<u8>,
+}
+
+impl SharedEncoder {
+    /// 创建一个新的编码器
+    pub fn new(config_manager: Arc<ConfigManager>) -> Self {
+        Self { config_manager, buffer: Vec::new() }
+    }
+
+    /// 共享的配置管理器
+    pub fn config_manager(&self) -> &Arc<ConfigManager> {
+        &self.config_manager
+    }
+
+    /// 将消息编码到内部缓冲区，返回的帧在下次编码前有效
+    pub fn encode(&mut self, message: &Message) -> MessageResult<&[u8]> {
+        self.buffer.clear();
+        MessageEncoder::new(&self.config_manager).encode_into(message, &mut self.buffer)?;
+        Ok(&self.buffer)
+    }
+
+    /// 将消息直接编码到调用方提供的缓冲区，返回写入的字节数
+    pub fn encode_into<B: BufMut>(&self, message: &Message, buf: &mut B) -> MessageResult<usize> {
+        MessageEncoder::new(&self.config_manager).encode_into(message, buf)
+    }
+
+    /// 将消息编码到定长切片中，返回写入的字节数
+    pub fn encode_to_slice(&self, message: &Message, buf: &mut [u8]) -> MessageResult<usize> {
+        MessageEncoder::new(&self.config_manager).encode_to_slice(message, buf)
+    }
+
+    /// 计算消息编码后的完整帧长度
+    pub fn encoded_len(&self, message: &Message) -> MessageResult<usize> {
+        MessageEncoder::new(&self.config_manager).encoded_len(message)
+    }
+
+    /// 将多条消息依次编码到一个连续缓冲区中
+    pub fn encode_batch(&self, messages: &[Message]) -> EncodedBatch {
+        MessageEncoder::new(&self.config_manager).encode_batch(messages)
+    }
+}
+
+/// 持有共享配置的解码器，同一个实例可以依次或并发地解码多个帧
+#[derive(Debug, Clone)]
+pub struct SharedDecoder {
+    /// 共享的配置管理器
+    config_manager: Arc<ConfigManager>,
+    /// 校验和的处理方式
+    checksum_mode: ChecksumMode,
+}
+
+impl SharedDecoder {
+    /// 创建一个新的解码器
+    pub fn new(config_manager: Arc<ConfigManager>) -> Self {
+        Self { config_manager, checksum_mode: ChecksumMode::Strict }
+    }
+
+    /// 设置校验和的处理方式，默认严格校验
+    pub fn with_checksum_mode(mut self, checksum_mode: ChecksumMode) -> Self {
+        self.checksum_mode = checksum_mode;
+        self
+    }
+
+    /// 共享的配置管理器
+    pub fn config_manager(&self) -> &Arc<ConfigManager> {
+        &self.config_manager
+    }
+
+    /// 解码一个完整的帧
+    pub fn decode(&self, frame: &[u8]) -> MessageResult<Message> {
+        MessageDecoder::new(&self.config_manager, frame).with_checksum_mode(self.checksum_mode).decode()
+    }
+
+    /// 宽松解码一个完整的帧，字段取值无效时保留原始值并记录问题
+    pub fn decode_lenient(&self, frame: &[u8]) -> ContextResult<LenientMessage> {
+        MessageDecoder::new(&self.config_manager, frame).with_checksum_mode(self.checksum_mode).decode_lenient()
+    }
+
+    /// 逐帧解码连续存放的帧
+    pub fn decode_batch<'a>(&'a self, buffer: &'a [u8]) -> DecodeFrames<'a> {
+        DecodeFrames::new(&self.config_manager, buffer).with_checksum_mode(self.checksum_mode)
+    }
+
+    /// 使用多个线程解码连续存放的帧
+    pub fn decode_batch_parallel(&self, buffer: &[u8], threads: usize) -> Vec<DecodedFrame> {
+        self.decode_batch(buffer).parallel(threads)
+    }
+
+    /// 按帧中的消息类型创建消息视图，视图不携带告警标记，`Warn` 与 `Skip` 方式都不校验校验和
+    pub fn view<'a>(&'a self, frame: &'a [u8]) -> MessageResult<MessageView<'a>> {
+        let header = MessageHeader::parse(frame)?;
+        let layout = self.config_manager.get_layout(header.msg_type)
+            .ok_or(MessageError::UnknownMessageType(header.msg_type))?;
+        match self.checksum_mode {
+            ChecksumMode::Strict => MessageView::with_checksum(layout, frame, self.config_manager.checksum()),
+            ChecksumMode::Warn | ChecksumMode::Skip => MessageView::with_checksum(layout, frame, &NoChecksum),
+        }
+    }
+}
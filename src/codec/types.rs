@@ -1,28 +1,72 @@
+use byteorder::{BigEndian, ByteOrder};
+
+use crate::codec::checksum::{ChecksumAlgorithm, ChecksumMode};
+use crate::config::types::FieldType;
+use crate::message::FieldValue;
 use crate::util::error::CodecResult;
+use crate::util::{ContextResult, MessageError, MessageResult};
 
 pub type Result<T> = CodecResult<T>;
 
-pub(super) const TYPE_PRICE_MAX: i64 = 9_999_999_999_999;
-pub(super) const TYPE_PRICE_MIN: i64 = -9_999_999_999_999;
-pub(super) const TYPE_QUANTITY_MAX: i64 = 999_999_999_999_999;
-pub(super) const TYPE_QUANTITY_MIN: i64 = -999_999_999_999_999;
-pub(super) const TYPE_AMOUNT_MAX: i64 = 999_999_999_999_999_999;
-pub(super) const TYPE_AMOUNT_MIN: i64 = -999_999_999_999_999_999;
+pub(crate) const TYPE_PRICE_MAX: i64 = 9_999_999_999_999;
+pub(crate) const TYPE_PRICE_MIN: i64 = -9_999_999_999_999;
+pub(crate) const TYPE_QUANTITY_MAX: i64 = 999_999_999_999_999;
+pub(crate) const TYPE_QUANTITY_MIN: i64 = -999_999_999_999_999;
+pub(crate) const TYPE_AMOUNT_MAX: i64 = 999_999_999_999_999_999;
+pub(crate) const TYPE_AMOUNT_MIN: i64 = -999_999_999_999_999_999;
+
+pub(crate) const TYPE_PRICE_SCALE: f64 = 1e5;
+pub(crate) const TYPE_QUANTITY_SCALE: f64 = 1e3;
+pub(crate) const TYPE_AMOUNT_SCALE: f64 = 1e5;
+
+/// 获取整数字段类型的取值范围，非整数类型返回 None
+pub(crate) fn integer_range(field_type: &FieldType) -> Option<(i128, i128)> {
+    match field_type {
+        FieldType::U8 => Some((u8::MIN as i128, u8::MAX as i128)),
+        FieldType::U16 => Some((u16::MIN as i128, u16::MAX as i128)),
+        FieldType::U32 => Some((u32::MIN as i128, u32::MAX as i128)),
+        FieldType::U64 => Some((u64::MIN as i128, u64::MAX as i128)),
+        FieldType::I8 => Some((i8::MIN as i128, i8::MAX as i128)),
+        FieldType::I16 => Some((i16::MIN as i128, i16::MAX as i128)),
+        FieldType::I32 => Some((i32::MIN as i128, i32::MAX as i128)),
+        FieldType::I64 => Some((i64::MIN as i128, i64::MAX as i128)),
+        _ => None,
+    }
+}
+
+/// 获取整数字段值对应的字段类型，非整数值返回 None
+pub(crate) fn integer_value_type(value: &FieldValue) -> Option<FieldType> {
+    match value {
+        FieldValue::U8(_) => Some(FieldType::U8),
+        FieldValue::U16(_) => Some(FieldType::U16),
+        FieldValue::U32(_) => Some(FieldType::U32),
+        FieldValue::U64(_) => Some(FieldType::U64),
+        FieldValue::I8(_) => Some(FieldType::I8),
+        FieldValue::I16(_) => Some(FieldType::I16),
+        FieldValue::I32(_) => Some(FieldType::I32),
+        FieldValue::I64(_) => Some(FieldType::I64),
+        _ => None,
+    }
+}
 
-pub(super) const TYPE_PRICE_SCALE: f64 = 1e5;
-pub(super) const TYPE_QUANTITY_SCALE: f64 = 1e3;
-pub(super) const TYPE_AMOUNT_SCALE: f64 = 1e5;
+/// 判断整数值能否无损拓宽到目标字段类型，例如 U8 值写入 U32 字段
+pub(crate) fn is_integer_widening(field_type: &FieldType, value: &FieldValue) -> bool {
+    match (integer_range(field_type), integer_value_type(value).as_ref().and_then(integer_range)) {
+        (Some((min, max)), Some((value_min, value_max))) => min <= value_min && value_max <= max,
+        _ => false,
+    }
+}
 
-pub(super) fn validate_price(value: i64) -> bool {
-    value >= TYPE_PRICE_MIN && value <= TYPE_PRICE_MAX
+pub(crate) fn validate_price(value: i64) -> bool {
+    (TYPE_PRICE_MIN..=TYPE_PRICE_MAX).contains(&value)
 }
 
-pub(super) fn validate_quantity(value: i64) -> bool {
-    value >= TYPE_QUANTITY_MIN && value <= TYPE_QUANTITY_MAX
+pub(crate) fn validate_quantity(value: i64) -> bool {
+    (TYPE_QUANTITY_MIN..=TYPE_QUANTITY_MAX).contains(&value)
 }
 
-pub(super) fn validate_amount(value: i64) -> bool {
-    value >= TYPE_AMOUNT_MIN && value <= TYPE_AMOUNT_MAX
+pub(crate) fn validate_amount(value: i64) -> bool {
+    (TYPE_AMOUNT_MIN..=TYPE_AMOUNT_MAX).contains(&value)
 }
 
 /// 验证NTime格式是否为有效的HHMMSSsssnnnn格式
@@ -31,7 +75,7 @@ pub(super) fn validate_amount(value: i64) -> bool {
 /// SS: 秒范围 00-59
 /// sss: 毫秒范围 000-999
 /// nnnn: 百纳秒范围 0000-9999
-pub(super) fn validate_ntime_format(ntime_value: u64) -> bool {
+pub(crate) fn validate_ntime_format(ntime_value: u64) -> bool {
     // 提取各个时间组件
     let hour = (ntime_value / 100000000000) % 100;
     let minute = (ntime_value / 1000000000) % 100;
@@ -71,7 +115,7 @@ pub(super) fn validate_ntime_format(ntime_value: u64) -> bool {
 /// YYYY: 年份范围 0000-9999
 /// MM: 月份范围 01-12
 /// DD: 日期范围 01-31
-pub(super) fn validate_date_format(date_value: u32) -> bool {
+pub(crate) fn validate_date_format(date_value: u32) -> bool {
     // 提取年、月、日
     let year = date_value / 10000;
     let month = (date_value % 10000) / 100;
@@ -83,12 +127,12 @@ pub(super) fn validate_date_format(date_value: u32) -> bool {
     }
     
     // 验证月份范围 01-12
-    if month < 1 || month > 12 {
+    if !(1..=12).contains(&month) {
         return false;
     }
     
     // 验证日期范围 01-31
-    if day < 1 || day > 31 {
+    if !(1..=31).contains(&day) {
         return false;
     }
     
@@ -96,7 +140,7 @@ pub(super) fn validate_date_format(date_value: u32) -> bool {
     match month {
         2 => {
             // 2月份，考虑闰年
-            let is_leap_year = (year % 4 == 0 && year % 100 != 0) || (year % 400 == 0);
+            let is_leap_year = (year.is_multiple_of(4) && !year.is_multiple_of(100)) || year.is_multiple_of(400);
             if is_leap_year {
                 day <= 29
             } else {
@@ -114,6 +158,17 @@ pub(super) fn validate_date_format(date_value: u32) -> bool {
     }
 }
 
+/// 截断字符串到不超过 `max_len` 字节，截断位置落在字符边界上
+///
+/// 动态编码器和强类型的 `FixedStr` 共用，保证超长的多字节字符串编码结果一致，且解码时仍是合法的 UTF-8
+pub(crate) fn truncate_str(value: &str, max_len: usize) -> &str {
+    let mut end = value.len().min(max_len);
+    while !value.is_char_boundary(end) {
+        end -= 1;
+    }
+    &value[..end]
+}
+
 // 消息头部结构
 #[derive(Debug, Clone)]
 pub struct MessageHeader {
@@ -133,6 +188,55 @@ impl MessageHeader {
     
     // 头部固定长度为12字节
     pub const SIZE: usize = 12;
+
+    /// 从消息帧开头解析头部
+    pub fn parse(frame: &[u8]) -> MessageResult<Self> {
+        if frame.len() < Self::SIZE {
+            return Err(MessageError::HeaderTooShort);
+        }
+        Ok(Self::new(
+            BigEndian::read_u32(&frame[0..4]),
+            BigEndian::read_u32(&frame[4..8]),
+            BigEndian::read_u32(&frame[8..12]),
+        ))
+    }
+}
+
+/// 解析头部并校验消息体长度，返回头部和消息体结束位置
+pub(crate) fn split_frame(frame: &[u8]) -> ContextResult<(MessageHeader, usize)> {
+    let header = MessageHeader::parse(frame).map_err(|e| {
+        e.at(0).expected(format!("{} bytes", MessageHeader::SIZE), format!("{} bytes", frame.len()))
+    })?;
+    let body_end = MessageHeader::SIZE + header.body_length as usize;
+    if frame.len() < body_end + 4 {
+        return Err(MessageError::BodyTooShort
+            .in_message(header.msg_type, None, Some(header.seq_num))
+            .at(MessageHeader::SIZE)
+            .expected(format!("{} bytes", body_end + 4), format!("{} bytes", frame.len())));
+    }
+    Ok((header, body_end))
+}
+
+/// 按校验方式检查帧尾的校验和，返回校验和是否不一致；严格模式下不一致时报错
+pub(crate) fn verify_checksum(frame: &[u8], body_end: usize, checksum: &dyn ChecksumAlgorithm, mode: ChecksumMode) -> ContextResult<bool> {
+    if mode == ChecksumMode::Skip {
+        return Ok(false);
+    }
+    let trailer = BigEndian::read_u32(&frame[body_end..body_end + 4]);
+    let mismatch = !checksum.verify(&frame[..body_end], trailer);
+    if mismatch && mode == ChecksumMode::Strict {
+        return Err(MessageError::ChecksumError
+            .at(body_end)
+            .expected(format!("{:#010x}", checksum.compute(&frame[..body_end])), format!("{:#010x}", trailer)));
+    }
+    Ok(mismatch)
+}
+
+/// 解析头部并严格校验消息体长度和校验和，返回头部和消息体结束位置
+pub(crate) fn check_frame(frame: &[u8], checksum: &dyn ChecksumAlgorithm) -> ContextResult<(MessageHeader, usize)> {
+    let (header, body_end) = split_frame(frame)?;
+    verify_checksum(frame, body_end, checksum, ChecksumMode::Strict)?;
+    Ok((header, body_end))
 }
 
 #[cfg(test)]
@@ -268,4 +372,25 @@ mod tests {
         assert!(validate_ntime_format(10000000000)); // 01:00:00.000.0000
         assert!(validate_ntime_format(100000000000)); // 10:00:00.000.0000
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_is_integer_widening() {
+        // 同宽度或更宽的同符号类型
+        assert!(is_integer_widening(&FieldType::U32, &FieldValue::U8(1)));
+        assert!(is_integer_widening(&FieldType::U32, &FieldValue::U32(1)));
+        assert!(is_integer_widening(&FieldType::I32, &FieldValue::I16(-1)));
+
+        // 无符号值可以拓宽到更宽的有符号类型
+        assert!(is_integer_widening(&FieldType::I16, &FieldValue::U8(255)));
+        assert!(is_integer_widening(&FieldType::I64, &FieldValue::U32(1)));
+
+        // 收窄、同宽度跨符号、有符号到无符号均不是拓宽
+        assert!(!is_integer_widening(&FieldType::U8, &FieldValue::U16(1)));
+        assert!(!is_integer_widening(&FieldType::I64, &FieldValue::U64(1)));
+        assert!(!is_integer_widening(&FieldType::U64, &FieldValue::I8(1)));
+
+        // 非整数类型不参与拓宽
+        assert!(!is_integer_widening(&FieldType::Date, &FieldValue::U8(1)));
+        assert!(!is_integer_widening(&FieldType::U32, &FieldValue::Float(1.0)));
+    }
+}
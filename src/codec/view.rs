@@ -0,0 +1,257 @@
+use byteorder::{BigEndian, ByteOrder};
+
+use crate::codec::decoder::FieldReader;
+use crate::codec::checksum::{ChecksumAlgorithm, Sum8};
+use crate::codec::layout::{FieldLayout, FieldSize, FieldsLayout, MessageLayout};
+use crate::codec::types::{check_frame, MessageHeader};
+use crate::config::types::FieldType;
+use crate::message::{FieldValue, Message};
+use crate::util::{ContextResult, ConversionError, MessageError, MessageResult};
+
+/// 借用消息帧的只读消息视图，按需解码单个字段
+///
+/// 创建时只校验头部、长度和校验和，不解码任何字段；定长部分的字段按布局中的偏移直接读取，
+/// 字符串和字节字段可以零拷贝地借用帧中的数据：
+///
+/// ```ignore
+/// let layout = config_manager.get_layout(103).unwrap();
+/// let view = MessageView::new(&layout, &frame)?;
+/// let cl_ord_id: &str = view.get_str("ClOrdID")?;
+/// let last_px = view.get_f64("LastPx")?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct MessageView<'a> {
+    layout: &'a MessageLayout,
+    header: MessageHeader,
+    /// 头部和消息体，不含校验和
+    data: &'a [u8],
+}
+
+/// 字段在帧中的位置
+#[derive(Clone, Copy)]
+struct Located<'a> {
+    field: &'a FieldLayout,
+    position: usize,
+}
+
+macro_rules! typed_view_getter {
+    ($($method:ident => $ty:ty),+ $(,)?) => {
+        $(
+            #[doc = concat!("读取 `", stringify!($ty), "` 类型的字段")]
+            pub fn $method(&self, name: &str) -> MessageResult<$ty> {
+                self.get_as(name)
+            }
+        )+
+    };
+}
+
+impl<'a> MessageView<'a> {
+    /// 创建消息视图，校验消息类型、消息体长度和协议规定的 [`Sum8`] 校验和
+    pub fn new(layout: &'a MessageLayout, frame: &'a [u8]) -> MessageResult<Self> {
+        Self::with_checksum(layout, frame, &Sum8)
+    }
+
+    /// 创建消息视图，使用指定的校验和算法
+    pub fn with_checksum(layout: &'a MessageLayout, frame: &'a [u8], checksum: &dyn ChecksumAlgorithm) -> MessageResult<Self> {
+        let header = MessageHeader::parse(frame)?;
+        if header.msg_type != layout.msg_type {
+            return Err(MessageError::UnexpectedMessageType(layout.msg_type, header.msg_type));
+        }
+        let (header, body_end) = check_frame(frame, checksum)?;
+        Ok(Self { layout, header, data: &frame[..body_end] })
+    }
+
+    /// 消息头部
+    pub fn header(&self) -> &MessageHeader {
+        &self.header
+    }
+
+    /// 消息类型
+    pub fn msg_type(&self) -> u32 {
+        self.header.msg_type
+    }
+
+    /// 序列号
+    pub fn seq_num(&self) -> u32 {
+        self.header.seq_num
+    }
+
+    /// 消息布局
+    pub fn layout(&self) -> &'a MessageLayout {
+        self.layout
+    }
+
+    /// 按字段名解码字段值，扩展字段按消息中的 BizID 查找
+    pub fn get(&self, name: &str) -> MessageResult<FieldValue> {
+        let located = self.locate(name)?;
+        self.decode(located)
+    }
+
+    /// 按字段名解码字段值，解码失败时错误中带有消息、字段名和字节偏移
+    pub fn get_with_context(&self, name: &str) -> ContextResult<FieldValue> {
+        let located = self.locate(name)?;
+        let mut reader = FieldReader::new(self.data, located.position);
+        reader.decode_field(&located.field.def.base, Some(&located.field.def)).map_err(|e| {
+            e.in_field(located.field.name())
+                .at(located.position)
+                .in_message(self.layout.msg_type, Some(&self.layout.name), Some(self.header.seq_num))
+        })
+    }
+
+    /// 按消息定义中的下标解码字段值，不包括扩展字段
+    pub fn get_at(&self, index: usize) -> MessageResult<FieldValue> {
+        let body = self.layout.body();
+        let field = body.fields().get(index).ok_or_else(|| {
+            MessageError::FieldNotFound(format!("#{} (message {})", index, self.layout.name))
+        })?;
+        let position = self.position_in(body, index, MessageHeader::SIZE)?;
+        self.decode(Located { field, position })
+    }
+
+    /// 读取字段并转换为指定类型
+    pub fn get_as<T>(&self, name: &str) -> MessageResult<T>
+    where
+        T: for<'v> TryFrom<&'v FieldValue, Error = ConversionError>,
+    {
+        let value = self.get(name)?;
+        T::try_from(&value).map_err(|e| MessageError::FieldConversionError(name.to_string(), e))
+    }
+
+    typed_view_getter! {
+        get_u8 => u8,
+        get_u16 => u16,
+        get_u32 => u32,
+        get_u64 => u64,
+        get_i8 => i8,
+        get_i16 => i16,
+        get_i32 => i32,
+        get_i64 => i64,
+        get_f64 => f64,
+    }
+
+    /// 读取 char 字段，借用帧中的数据并去除首尾空白
+    pub fn get_str(&self, name: &str) -> MessageResult<&'a str> {
+        let located = self.locate(name)?;
+        if located.field.field_type() != &FieldType::Char {
+            return Err(self.type_mismatch(name, located, "Str"));
+        }
+        let raw = self.raw_at(located)?;
+        std::str::from_utf8(raw)
+            .map(str::trim)
+            .map_err(|e| MessageError::FieldDecodeError(format!("UTF-8 error: {}", e)))
+    }
+
+    /// 读取 bytes 字段，借用帧中的数据
+    pub fn get_bytes(&self, name: &str) -> MessageResult<&'a [u8]> {
+        let located = self.locate(name)?;
+        if located.field.field_type() != &FieldType::Bytes {
+            return Err(self.type_mismatch(name, located, "Bytes"));
+        }
+        let raw = self.raw_at(located)?;
+        match located.field.size {
+            FieldSize::Prefixed { prefix, .. } => Ok(&raw[prefix..]),
+            _ => Ok(raw),
+        }
+    }
+
+    /// 字段在帧中的原始字节，变长字段包含前置的计数字段
+    pub fn raw(&self, name: &str) -> MessageResult<&'a [u8]> {
+        let located = self.locate(name)?;
+        self.raw_at(located)
+    }
+
+    /// 解码全部字段，结果与 [`crate::codec::MessageDecoder`] 一致
+    pub fn to_message(&self) -> MessageResult<Message> {
+        let mut message = Message::new(self.header.msg_type, self.header.seq_num);
+        let mut reader = FieldReader::new(self.data, MessageHeader::SIZE);
+        reader.decode_fields(self.layout.body(), &mut message)?;
+        if let Some(extension) = self.extension()? {
+            reader.decode_fields(extension, &mut message)?;
+        }
+        Ok(message)
+    }
+
+    /// 当前 BizID 对应的扩展字段布局
+    fn extension(&self) -> MessageResult<Option<&'a FieldsLayout>> {
+        if !self.layout.has_extensions() || self.layout.body().index_of("BizID").is_none() {
+            return Ok(None);
+        }
+        let biz_id = self.get_u32("BizID")?;
+        Ok(self.layout.extension(biz_id))
+    }
+
+    /// 查找字段及其在帧中的位置
+    fn locate(&self, name: &str) -> MessageResult<Located<'a>> {
+        let body = self.layout.body();
+        if let Some(index) = body.index_of(name) {
+            let position = self.position_in(body, index, MessageHeader::SIZE)?;
+            return Ok(Located { field: &body.fields()[index], position });
+        }
+
+        if let Some(extension) = self.extension()?
+            && let Some(index) = extension.index_of(name)
+        {
+            let start = self.position_in(body, body.fields().len(), MessageHeader::SIZE)?;
+            let position = self.position_in(extension, index, start)?;
+            return Ok(Located { field: &extension.fields()[index], position });
+        }
+
+        Err(MessageError::FieldNotFound(format!("{} (message {})", name, self.layout.name)))
+    }
+
+    /// 计算 `fields` 中第 `index` 个字段的位置，`index` 等于字段数时返回末尾位置
+    fn position_in(&self, fields: &FieldsLayout, index: usize, start: usize) -> MessageResult<usize> {
+        if let Some(offset) = fields.fields().get(index).and_then(|field| field.offset) {
+            return Ok(start + offset);
+        }
+
+        // 从第一个变长字段开始逐个跳过；没有变长字段时说明是求末尾位置
+        let (from, mut position) = match fields.first_variable() {
+            Some(first) => (first, start + fields.fields()[first].offset.unwrap_or_default()),
+            None => return Ok(start + fields.fixed_len().unwrap_or_default()),
+        };
+        for field in &fields.fields()[from..index] {
+            position += self.size_at(field, position)?;
+        }
+        Ok(position)
+    }
+
+    /// 字段在指定位置占用的字节数
+    fn size_at(&self, field: &FieldLayout, position: usize) -> MessageResult<usize> {
+        match field.size {
+            FieldSize::Fixed(size) => Ok(size),
+            FieldSize::Prefixed { prefix, unit } => {
+                let bytes = self.data.get(position..position + prefix).ok_or(MessageError::BodyTooShort)?;
+                let count = match prefix {
+                    1 => bytes[0] as usize,
+                    2 => BigEndian::read_u16(bytes) as usize,
+                    4 => BigEndian::read_u32(bytes) as usize,
+                    _ => return Err(MessageError::InvalidArrayCountType),
+                };
+                Ok(prefix + count * unit)
+            },
+            FieldSize::Unknown => Err(MessageError::FieldDecodeError(format!(
+                "Field {} has incomplete definition, size unknown", field.name()
+            ))),
+        }
+    }
+
+    fn raw_at(&self, located: Located<'a>) -> MessageResult<&'a [u8]> {
+        let size = self.size_at(located.field, located.position)?;
+        self.data.get(located.position..located.position + size).ok_or(MessageError::BodyTooShort)
+    }
+
+    fn decode(&self, located: Located<'a>) -> MessageResult<FieldValue> {
+        let mut reader = FieldReader::new(self.data, located.position);
+        Ok(reader.decode_field(&located.field.def.base, Some(&located.field.def))?)
+    }
+
+    /// 字段类型与请求的类型不符
+    fn type_mismatch(&self, name: &str, located: Located<'a>, expected: &'static str) -> MessageError {
+        let actual = match self.decode(located) {
+            Ok(value) => value.variant_name(),
+            Err(e) => return e,
+        };
+        MessageError::FieldConversionError(name.to_string(), ConversionError { expected, actual })
+    }
+}
@@ -0,0 +1,414 @@
+//! 根据 XML 消息定义生成强类型的 Rust 消息结构体
+//!
+//! 该模块只依赖 [`super::types`]，由 `build.rs` 以源码方式引入，
+//! 生成的代码通过 `crate::typed` 中的运行时类型完成编解码。
+
+use std::fmt::Write;
+
+use super::types::{BaseFieldDef, BizExtension, FieldDef, FieldType, MessageConfig, MessageDef};
+
+/// Rust 关键字，字段名与之冲突时使用原始标识符
+const RUST_KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "dyn", "else", "enum", "extern",
+    "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub",
+    "ref", "return", "static", "struct", "trait", "true", "type", "unsafe", "use",
+    "where", "while", "abstract", "become", "box", "do", "final", "gen", "macro", "override", "priv",
+    "try", "typeof", "unsized", "virtual", "yield",
+];
+
+/// 不能作为原始标识符的关键字，字段名与之冲突时在末尾加 `_`
+const RESERVED_IDENTS: &[&str] = &["crate", "self", "super", "Self"];
+
+/// 将 PascalCase 字段名转换为 snake_case，连续大写视为缩写，例如 `ClOrdID` -> `cl_ord_id`
+pub fn to_snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut result = String::with_capacity(name.len() + 4);
+    for (i, c) in chars.iter().enumerate() {
+        if c.is_ascii_uppercase() && i > 0 {
+            let prev = chars[i - 1];
+            let next_is_lower = chars.get(i + 1).is_some_and(|n| n.is_ascii_lowercase());
+            if prev.is_ascii_lowercase() || prev.is_ascii_digit() || (prev.is_ascii_uppercase() && next_is_lower) {
+                result.push('_');
+            }
+        }
+        result.push(c.to_ascii_lowercase());
+    }
+    result
+}
+
+/// 生成字段对应的 Rust 标识符
+fn field_ident(name: &str) -> String {
+    let snake = to_snake_case(name);
+    if RESERVED_IDENTS.contains(&snake.as_str()) {
+        format!("{}_", snake)
+    } else if RUST_KEYWORDS.contains(&snake.as_str()) {
+        format!("r#{}", snake)
+    } else {
+        snake
+    }
+}
+
+/// 校验名称可以直接作为 Rust 类型名使用
+fn check_type_ident(name: &str) -> Result<(), String> {
+    let mut chars = name.chars();
+    let valid = chars.next().is_some_and(|c| c.is_ascii_uppercase())
+        && chars.all(|c| c.is_ascii_alphanumeric());
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("message name '{}' is not a valid Rust type name", name))
+    }
+}
+
+/// 长度字段的字节宽度
+fn count_width(message: &str, field: &str, length_field: &BaseFieldDef) -> Result<usize, String> {
+    match length_field.r#type {
+        FieldType::U8 => Ok(1),
+        FieldType::U16 => Ok(2),
+        FieldType::U32 => Ok(4),
+        ref other => Err(format!(
+            "{}.{}: length field {} must be u8, u16 or u32, got {:?}",
+            message, field, length_field.name, other
+        )),
+    }
+}
+
+/// 字段在生成代码中的编码方式
+enum FieldKind {
+    /// 实现了 `WireField` 的定长类型
+    Scalar(String),
+    /// 带长度前缀的变长字节
+    VarBytes(usize),
+    /// 带长度前缀的结构体数组，元素类型名
+    Array(usize, String),
+}
+
+impl FieldKind {
+    fn rust_type(&self) -> String {
+        match self {
+            FieldKind::Scalar(ty) => ty.clone(),
+            FieldKind::VarBytes(_) => "Vec<u8>".to_string(),
+            FieldKind::Array(_, entry) => format!("Vec<{}>", entry),
+        }
+    }
+}
+
+/// 定长字段的 Rust 类型
+fn scalar_type(message: &str, def: &BaseFieldDef) -> Result<String, String> {
+    let required_length = || def.length.ok_or_else(|| format!("{}.{}: {:?} field requires length", message, def.name, def.r#type));
+    Ok(match def.r#type {
+        FieldType::U8 => "u8".to_string(),
+        FieldType::U16 => "u16".to_string(),
+        FieldType::U32 => "u32".to_string(),
+        FieldType::U64 => "u64".to_string(),
+        FieldType::I8 => "i8".to_string(),
+        FieldType::I16 => "i16".to_string(),
+        FieldType::I32 => "i32".to_string(),
+        FieldType::I64 => "i64".to_string(),
+        FieldType::Char => format!("FixedStr<{}>", required_length()?),
+        FieldType::Price => "Price".to_string(),
+        FieldType::Quantity => "Quantity".to_string(),
+        FieldType::Amount => "Amount".to_string(),
+        FieldType::Date => "Date".to_string(),
+        FieldType::NTime => "NTime".to_string(),
+        FieldType::Bytes => format!("FixedBytes<{}>", required_length()?),
+        FieldType::Array => return Err(format!("{}.{}: nested array is not supported", message, def.name)),
+    })
+}
+
+/// 单个字段的生成信息
+struct FieldSpec<'a> {
+    name: &'a str,
+    ident: String,
+    desc: Option<&'a str>,
+    kind: FieldKind,
+}
+
+impl FieldSpec<'_> {
+    fn write_decl(&self, out: &mut String, indent: &str, visibility: &str) {
+        if let Some(desc) = self.desc {
+            let _ = writeln!(out, "{}/// {}", indent, desc.replace('\n', " "));
+        }
+        let _ = writeln!(out, "{}{}{}: {},", indent, visibility, self.ident, self.kind.rust_type());
+    }
+
+    fn wire_len_expr(&self, owner: &str) -> String {
+        match &self.kind {
+            FieldKind::Scalar(_) => format!("{}{}.wire_len()", owner, self.ident),
+            FieldKind::VarBytes(width) => format!("{} + {}{}.len()", width, owner, self.ident),
+            FieldKind::Array(width, _) => format!("array_len({}, &{}{})", width, owner, self.ident),
+        }
+    }
+
+    fn put_stmt(&self, owner: &str) -> String {
+        match &self.kind {
+            FieldKind::Scalar(_) => format!("{}{}.put({:?}, buffer)?;", owner, self.ident, self.name),
+            FieldKind::VarBytes(width) => format!("put_var_bytes({:?}, {}, &{}{}, buffer)?;", self.name, width, owner, self.ident),
+            FieldKind::Array(width, _) => format!("put_array({:?}, {}, &{}{}, buffer)?;", self.name, width, owner, self.ident),
+        }
+    }
+
+    fn get_expr(&self) -> String {
+        match &self.kind {
+            FieldKind::Scalar(ty) => format!("<{} as WireField>::get({:?}, reader)?", ty, self.name),
+            FieldKind::VarBytes(width) => format!("get_var_bytes({:?}, {}, reader)?", self.name, width),
+            FieldKind::Array(width, _) => format!("get_array({:?}, {}, reader)?", self.name, width),
+        }
+    }
+}
+
+fn base_spec<'a>(message: &str, def: &'a BaseFieldDef) -> Result<FieldSpec<'a>, String> {
+    Ok(FieldSpec {
+        name: &def.name,
+        ident: field_ident(&def.name),
+        desc: def.desc.as_deref(),
+        kind: FieldKind::Scalar(scalar_type(message, def)?),
+    })
+}
+
+fn field_spec<'a>(message: &str, def: &'a FieldDef) -> Result<FieldSpec<'a>, String> {
+    let base = &def.base;
+    let kind = match base.r#type {
+        FieldType::Array => {
+            let length_field = def.length_field.as_ref()
+                .ok_or_else(|| format!("{}.{}: array field requires length_field", message, base.name))?;
+            FieldKind::Array(count_width(message, &base.name, length_field)?, format!("{}{}Entry", message, base.name))
+        },
+        FieldType::Bytes if base.length.is_none() => {
+            let length_field = def.length_field.as_ref()
+                .ok_or_else(|| format!("{}.{}: bytes field requires length or length_field", message, base.name))?;
+            FieldKind::VarBytes(count_width(message, &base.name, length_field)?)
+        },
+        _ => return base_spec(message, base),
+    };
+    Ok(FieldSpec {
+        name: &base.name,
+        ident: field_ident(&base.name),
+        desc: base.desc.as_deref(),
+        kind,
+    })
+}
+
+/// 生成 `WireRecord` 实现，`extension` 为扩展枚举类型名
+fn write_record_impl(out: &mut String, type_name: &str, specs: &[FieldSpec<'_>], extension: Option<&str>) {
+    let _ = writeln!(out, "impl WireRecord for {} {{", type_name);
+
+    let mut lens = specs.iter().map(|spec| spec.wire_len_expr("self.")).collect::<Vec<_>>();
+    if extension.is_some() {
+        lens.push("self.extension.wire_len()".to_string());
+    }
+    if lens.is_empty() {
+        lens.push("0".to_string());
+    }
+    let _ = writeln!(out, "    fn wire_len(&self) -> usize {{\n        {}\n    }}\n", lens.join("\n            + "));
+
+    let empty = specs.is_empty() && extension.is_none();
+    let (buffer, reader) = if empty { ("_buffer", "_reader") } else { ("buffer", "reader") };
+    let _ = writeln!(out, "    fn encode(&self, {}: &mut Vec<u8>) -> MessageResult<()> {{", buffer);
+    for spec in specs {
+        let _ = writeln!(out, "        {}", spec.put_stmt("self."));
+    }
+    if extension.is_some() {
+        out.push_str("        self.extension.put(self.biz_id, buffer)?;\n");
+    }
+    out.push_str("        Ok(())\n    }\n\n");
+
+    let _ = writeln!(out, "    fn decode({}: &mut WireReader<'_>) -> MessageResult<Self> {{", reader);
+    for spec in specs {
+        let _ = writeln!(out, "        let {} = {};", spec.ident, spec.get_expr());
+    }
+    if let Some(extension) = extension {
+        let _ = writeln!(out, "        let extension = {}::get(biz_id, reader)?;", extension);
+    }
+    let mut idents = specs.iter().map(|spec| spec.ident.as_str()).collect::<Vec<_>>();
+    if extension.is_some() {
+        idents.push("extension");
+    }
+    if idents.is_empty() {
+        out.push_str("        Ok(Self {})\n    }\n}\n\n");
+    } else {
+        let _ = writeln!(out, "        Ok(Self {{ {} }})\n    }}\n}}\n", idents.join(", "));
+    }
+}
+
+/// 生成扩展字段枚举，每个 BizID 对应一个变体
+fn write_extension(out: &mut String, message: &MessageDef, enum_name: &str) -> Result<(), String> {
+    let variants = message.extensions.iter()
+        .map(|extension: &BizExtension| {
+            let specs = extension.fields.iter()
+                .map(|def| base_spec(&message.name, def))
+                .collect::<Result<Vec<_>, _>>()?;
+            Ok((extension.biz_id, specs))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    let _ = writeln!(out, "/// {} 按 BizID 区分的扩展字段", message.name);
+    out.push_str("#[derive(Debug, Clone, PartialEq, Eq, Default)]\n");
+    let _ = writeln!(out, "pub enum {} {{", enum_name);
+    out.push_str("    /// BizID 没有扩展字段\n    #[default]\n    None,\n");
+    for (biz_id, specs) in &variants {
+        let _ = writeln!(out, "    /// BizID {}", biz_id);
+        let _ = writeln!(out, "    Biz{} {{", biz_id);
+        for spec in specs {
+            spec.write_decl(out, "        ", "");
+        }
+        out.push_str("    },\n");
+    }
+    out.push_str("}\n\n");
+
+    let biz_ids = variants.iter().map(|(biz_id, _)| biz_id.to_string()).collect::<Vec<_>>().join(" | ");
+    let bindings = |specs: &[FieldSpec<'_>]| specs.iter().map(|spec| spec.ident.clone()).collect::<Vec<_>>().join(", ");
+
+    let _ = writeln!(out, "impl {} {{", enum_name);
+    out.push_str("    /// 扩展对应的 BizID，`None` 变体返回 `None`\n");
+    out.push_str("    pub fn biz_id(&self) -> Option<u32> {\n        match self {\n            Self::None => None,\n");
+    for (biz_id, _) in &variants {
+        let _ = writeln!(out, "            Self::Biz{} {{ .. }} => Some({}),", biz_id, biz_id);
+    }
+    out.push_str("        }\n    }\n\n");
+
+    out.push_str("    fn wire_len(&self) -> usize {\n        match self {\n            Self::None => 0,\n");
+    for (biz_id, specs) in &variants {
+        let lens = specs.iter().map(|spec| spec.wire_len_expr("")).collect::<Vec<_>>();
+        let _ = writeln!(out, "            Self::Biz{} {{ {} }} => {},", biz_id, bindings(specs), lens.join(" + "));
+    }
+    out.push_str("        }\n    }\n\n");
+
+    out.push_str("    fn put(&self, biz_id: u32, buffer: &mut Vec<u8>) -> MessageResult<()> {\n");
+    out.push_str("        match (self, biz_id) {\n");
+    let _ = writeln!(out, "            (Self::None, {}) => Err(MessageError::UnknownBizExtension(biz_id)),", biz_ids);
+    out.push_str("            (Self::None, _) => Ok(()),\n");
+    for (biz_id, specs) in &variants {
+        let _ = writeln!(out, "            (Self::Biz{} {{ {} }}, {}) => {{", biz_id, bindings(specs), biz_id);
+        for spec in specs {
+            let _ = writeln!(out, "                {}", spec.put_stmt(""));
+        }
+        out.push_str("                Ok(())\n            },\n");
+    }
+    out.push_str("            (extension, _) => Err(MessageError::InvalidFieldValue(format!(\n");
+    let _ = writeln!(out, "                \"{} extension {{:?}} does not match BizID {{}}\", extension.biz_id(), biz_id", message.name);
+    out.push_str("            ))),\n        }\n    }\n\n");
+
+    out.push_str("    fn get(biz_id: u32, reader: &mut WireReader<'_>) -> MessageResult<Self> {\n        match biz_id {\n");
+    for (biz_id, specs) in &variants {
+        let _ = writeln!(out, "            {} => Ok(Self::Biz{} {{", biz_id, biz_id);
+        for spec in specs {
+            let _ = writeln!(out, "                {}: {},", spec.ident, spec.get_expr());
+        }
+        out.push_str("            }),\n");
+    }
+    out.push_str("            _ => Ok(Self::None),\n        }\n    }\n}\n\n");
+    Ok(())
+}
+
+fn write_message(out: &mut String, message: &MessageDef) -> Result<(), String> {
+    check_type_ident(&message.name)?;
+    let specs = message.fields.iter()
+        .map(|def| field_spec(&message.name, def))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // 数组元素结构体
+    for (def, spec) in message.fields.iter().zip(&specs) {
+        let (FieldKind::Array(_, entry), Some(struct_def)) = (&spec.kind, def.r#struct.as_ref()) else {
+            if matches!(spec.kind, FieldKind::Array(..)) {
+                return Err(format!("{}.{}: array field requires struct", message.name, def.base.name));
+            }
+            continue;
+        };
+        let entry_specs = struct_def.fields.iter()
+            .map(|field| base_spec(&message.name, field))
+            .collect::<Result<Vec<_>, _>>()?;
+        let _ = writeln!(out, "/// {}.{} 数组元素", message.name, def.base.name);
+        out.push_str("#[derive(Debug, Clone, PartialEq, Eq, Default)]\n");
+        let _ = writeln!(out, "pub struct {} {{", entry);
+        for entry_spec in &entry_specs {
+            entry_spec.write_decl(out, "    ", "pub ");
+        }
+        out.push_str("}\n\n");
+        write_record_impl(out, entry, &entry_specs, None);
+    }
+
+    // 扩展字段依赖 BizID 的取值
+    let extension = if message.extensions.is_empty() {
+        None
+    } else {
+        match message.fields.iter().find(|def| def.base.name == "BizID") {
+            Some(def) if def.base.r#type == FieldType::U32 => Some(format!("{}Extension", message.name)),
+            Some(_) => return Err(format!("{}.BizID must be u32 when extensions are defined", message.name)),
+            None => None,
+        }
+    };
+    if let Some(enum_name) = &extension {
+        write_extension(out, message, enum_name)?;
+    }
+
+    let _ = writeln!(out, "/// {}（MsgType = {}）", message.name, message.msg_type);
+    out.push_str("#[derive(Debug, Clone, PartialEq, Eq, Default)]\n");
+    let _ = writeln!(out, "pub struct {} {{", message.name);
+    for spec in &specs {
+        spec.write_decl(out, "    ", "pub ");
+    }
+    if let Some(enum_name) = &extension {
+        out.push_str("    /// 当前 BizID 对应的扩展字段\n");
+        let _ = writeln!(out, "    pub extension: {},", enum_name);
+    }
+    out.push_str("}\n\n");
+
+    write_record_impl(out, &message.name, &specs, extension.as_deref());
+
+    let _ = writeln!(out, "impl TypedMessage for {} {{", message.name);
+    let _ = writeln!(out, "    const MSG_TYPE: u32 = {};", message.msg_type);
+    let _ = writeln!(out, "    const NAME: &'static str = {:?};", message.name);
+    out.push_str("}\n\n");
+    Ok(())
+}
+
+/// 生成全部消息的 Rust 源码
+///
+/// 生成的代码引用 `crate::typed` 下的运行时类型，需要在本 crate 内通过 `include!` 引入
+pub fn generate(config: &MessageConfig) -> Result<String, String> {
+    let mut out = String::new();
+    out.push_str("// 由 build.rs 根据 XML 消息定义自动生成，请勿手工修改\n\n");
+    out.push_str("#[allow(unused_imports)]\n");
+    out.push_str("use crate::typed::types::{Amount, Date, FixedBytes, FixedStr, NTime, Price, Quantity};\n");
+    out.push_str("#[allow(unused_imports)]\n");
+    out.push_str("use crate::typed::wire::{array_len, get_array, get_var_bytes, put_array, put_var_bytes, WireField, WireReader, WireRecord};\n");
+    out.push_str("#[allow(unused_imports)]\n");
+    out.push_str("use crate::util::{MessageError, MessageResult};\n");
+    out.push_str("use crate::typed::TypedMessage;\n\n");
+
+    for message in &config.messages {
+        write_message(&mut out, message)?;
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_snake_case() {
+        assert_eq!(to_snake_case("ClOrdID"), "cl_ord_id");
+        assert_eq!(to_snake_case("BizID"), "biz_id");
+        assert_eq!(to_snake_case("QSize"), "q_size");
+        assert_eq!(to_snake_case("SenderCompID"), "sender_comp_id");
+        assert_eq!(to_snake_case("Pbu"), "pbu");
+        assert_eq!(field_ident("Type"), "r#type");
+        assert_eq!(field_ident("Self"), "self_");
+        assert_eq!(field_ident("Crate"), "crate_");
+    }
+
+    #[test]
+    fn test_generate_rejects_invalid_definition() {
+        let config: MessageConfig = quick_xml::de::from_str(r#"
+        <messages>
+            <message type="1" name="Bad">
+                <field name="Text" type="char" desc="缺少长度"/>
+            </message>
+        </messages>
+        "#).unwrap();
+        let err = generate(&config).unwrap_err();
+        assert!(err.contains("Bad.Text"), "{}", err);
+    }
+}
@@ -1,17 +1,33 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs::File;
 use std::io::BufReader;
 use std::path::Path;
+use std::sync::Arc;
 
 use quick_xml::de::{from_reader, from_str};
 
-use crate::util::ConfigResult;
-use super::types::{BizExtension, MessageDef, MessageConfig};
+use crate::codec::checksum::{ChecksumAlgorithm, Sum8};
+use crate::codec::layout::MessageLayout;
+use crate::message::{MessageBuilder, MessagePrinter};
+use crate::util::{ConfigError, ConfigResult};
+use super::types::{BaseFieldDef, BizExtension, FieldDef, FieldType, MessageDef, MessageConfig};
 
 /// 配置管理器，用于加载和管理消息定义
+#[derive(Debug)]
 pub struct ConfigManager {
     messages: HashMap<u32, MessageDef>,
     extentions: HashMap<u32, HashMap<u32, BizExtension>>,
+    names: HashMap<String, u32>,
+    /// 加载时按消息定义预先计算的布局
+    layouts: HashMap<u32, MessageLayout>,
+    /// 编解码使用的校验和算法
+    checksum: Arc<dyn ChecksumAlgorithm>,
+}
+
+impl Default for ConfigManager {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl ConfigManager {
@@ -20,6 +36,9 @@ impl ConfigManager {
         Self {
             messages: HashMap::new(),
             extentions: HashMap::new(),
+            names: HashMap::new(),
+            layouts: HashMap::new(),
+            checksum: Arc::new(Sum8),
         }
     }
     
@@ -41,14 +60,18 @@ impl ConfigManager {
     fn load_config(&mut self, config: MessageConfig) -> ConfigResult<()> {
         // 加载消息定义
         for message in config.messages {
-            if !self.extentions.contains_key(&message.msg_type) {
-                self.extentions.insert(message.msg_type, HashMap::new());
-            }
-
+            let extensions = self.extentions.entry(message.msg_type).or_default();
             for extension in &message.extensions {
-                self.extentions.get_mut(&message.msg_type).unwrap().insert(extension.biz_id, extension.clone());
+                extensions.insert(extension.biz_id, extension.clone());
             }
 
+            if let Some(previous) = self.messages.get(&message.msg_type) {
+                self.names.remove(&previous.name);
+            }
+            self.names.insert(message.name.clone(), message.msg_type);
+            // 扩展定义按 BizID 合并，布局使用合并后的扩展
+            let layout = MessageLayout::with_extensions(&message, self.extentions[&message.msg_type].values());
+            self.layouts.insert(message.msg_type, layout);
             self.messages.insert(message.msg_type, message);
         }
         
@@ -60,8 +83,141 @@ impl ConfigManager {
         self.messages.get(&msg_type)
     }
 
+    /// 按消息名称获取消息定义
+    pub fn get_message_def_by_name(&self, name: &str) -> Option<&MessageDef> {
+        self.names.get(name).and_then(|msg_type| self.messages.get(msg_type))
+    }
+
+    /// 获取指定类型的消息布局
+    pub fn get_layout(&self, msg_type: u32) -> Option<&MessageLayout> {
+        self.layouts.get(&msg_type)
+    }
+
+    /// 按消息名称获取消息布局
+    pub fn get_layout_by_name(&self, name: &str) -> Option<&MessageLayout> {
+        self.names.get(name).and_then(|msg_type| self.layouts.get(msg_type))
+    }
+
+    /// 按消息类型排序的全部消息定义
+    pub fn message_defs(&self) -> Vec<&MessageDef> {
+        let mut defs: Vec<_> = self.messages.values().collect();
+        defs.sort_by_key(|def| def.msg_type);
+        defs
+    }
+
+    /// 指定消息类型按 BizID 排序的扩展定义，多次加载的扩展已合并
+    pub fn extensions(&self, msg_type: u32) -> Vec<&BizExtension> {
+        let mut extensions: Vec<_> = self.extentions.get(&msg_type).into_iter().flat_map(HashMap::values).collect();
+        extensions.sort_by_key(|extension| extension.biz_id);
+        extensions
+    }
+
     /// 获取指定消息类型和业务ID的扩展定义
     pub fn get_extension(&self, msg_type: u32, biz_id: u32) -> Option<&BizExtension> {
         self.extentions.get(&msg_type).and_then(|ext| ext.get(&biz_id))
     }
-}
\ No newline at end of file
+
+    /// 编解码使用的校验和算法，默认为 [`Sum8`]
+    pub fn checksum(&self) -> &dyn ChecksumAlgorithm {
+        self.checksum.as_ref()
+    }
+
+    /// 替换校验和算法，例如测试环境中使用 [`crate::codec::NoChecksum`] 关闭校验
+    pub fn set_checksum(&mut self, algorithm: impl ChecksumAlgorithm + 'static) {
+        self.checksum = Arc::new(algorithm);
+    }
+
+    /// 检查已加载的消息定义，返回编解码时才会暴露的问题
+    ///
+    /// 包括 char 和 bytes 缺少长度、数组缺少计数字段或元素结构、计数字段不是 u8/u16/u32、
+    /// 数组嵌套、字段重名，以及定义了扩展字段但消息中没有 BizID
+    pub fn validate(&self) -> Vec<ConfigError> {
+        let mut errors = Vec::new();
+        for def in self.message_defs() {
+            let mut issues = Vec::new();
+            let mut names = HashSet::new();
+            for field in &def.fields {
+                if !names.insert(field.base.name.as_str()) {
+                    issues.push(format!("duplicate field '{}'", field.base.name));
+                }
+                check_field(&field.base, Some(field), &field.base.name, &mut issues);
+            }
+
+            let extensions = self.extensions(def.msg_type);
+            if !extensions.is_empty() && !def.fields.iter().any(|field| field.base.name == "BizID") {
+                issues.push("extensions are defined but the message has no BizID field".to_string());
+            }
+            for extension in extensions {
+                let mut extension_names = HashSet::new();
+                for field in &extension.fields {
+                    let path = format!("{} (BizID {})", field.name, extension.biz_id);
+                    if names.contains(field.name.as_str()) || !extension_names.insert(field.name.as_str()) {
+                        issues.push(format!("duplicate field '{}'", path));
+                    }
+                    check_field(field, None, &path, &mut issues);
+                }
+            }
+
+            errors.extend(issues.into_iter().map(|issue| {
+                ConfigError::InvalidDefinition(format!("{} ({})", def.msg_type, def.name), issue)
+            }));
+        }
+        errors
+    }
+
+    /// 按消息名称创建带字段校验的消息构建器
+    pub fn builder(&self, name: &str) -> ConfigResult<MessageBuilder<'_>> {
+        let message_def = self.get_message_def_by_name(name)
+            .ok_or_else(|| ConfigError::MessageNameNotFound(name.to_string()))?;
+        Ok(MessageBuilder::new(self, message_def))
+    }
+
+    /// 按消息类型创建多行格式的消息打印器
+    pub fn printer(&self, msg_type: u32) -> ConfigResult<MessagePrinter<'_>> {
+        let message_def = self.get_message_def(msg_type).ok_or(ConfigError::MessageNotFound(msg_type))?;
+        Ok(MessagePrinter::new(message_def))
+    }
+}
+
+/// 检查单个字段定义，`field_def` 为 `None` 时是扩展字段或数组元素字段
+fn check_field(base: &BaseFieldDef, field_def: Option<&FieldDef>, path: &str, issues: &mut Vec<String>) {
+    let length_field = field_def.and_then(|def| def.length_field.as_ref());
+    let counted = |issues: &mut Vec<String>, length_field: &BaseFieldDef| {
+        if !matches!(length_field.r#type, FieldType::U8 | FieldType::U16 | FieldType::U32) {
+            issues.push(format!("length field '{}' of '{}' must be u8, u16 or u32, got {:?}", length_field.name, path, length_field.r#type));
+        }
+    };
+
+    match base.r#type {
+        FieldType::Char if base.length.is_none() => issues.push(format!("char field '{}' has no length", path)),
+        FieldType::Bytes => match (base.length, length_field) {
+            (None, None) => issues.push(format!("bytes field '{}' has neither length nor length field", path)),
+            (None, Some(length_field)) => counted(issues, length_field),
+            _ => {},
+        },
+        FieldType::Array => {
+            let Some(field_def) = field_def else {
+                issues.push(format!("nested array '{}' is not supported", path));
+                return;
+            };
+            match length_field {
+                Some(length_field) => counted(issues, length_field),
+                None => issues.push(format!("array '{}' has no length field", path)),
+            }
+            match &field_def.r#struct {
+                Some(struct_def) if !struct_def.fields.is_empty() => {
+                    let mut names = HashSet::new();
+                    for element in &struct_def.fields {
+                        let element_path = format!("{}[].{}", path, element.name);
+                        if !names.insert(element.name.as_str()) {
+                            issues.push(format!("duplicate field '{}'", element_path));
+                        }
+                        check_field(element, None, &element_path, issues);
+                    }
+                },
+                _ => issues.push(format!("array '{}' has no element fields", path)),
+            }
+        },
+        _ => {},
+    }
+}
@@ -1,5 +1,6 @@
 pub mod types;
 pub mod manager;
+pub mod codegen;
 
 pub use types::{BizExtension, FieldDef, MessageDef, MessageConfig};
 pub use manager::ConfigManager;
\ No newline at end of file
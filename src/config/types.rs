@@ -29,6 +29,9 @@ pub enum FieldType {
     U16,
     U32,
     U64,
+    I8,
+    I16,
+    I32,
     I64,
     Char,      // 固定长度 ASCII 字符串
     Price,     // N13(5) 精度价格
@@ -36,6 +39,7 @@ pub enum FieldType {
     Amount,    // N18(5) 金额
     Date,      // YYYYMMDD 格式日期
     NTime,     // HHMMSSsssnnnn 纳秒时间戳
+    Bytes,     // 原始字节，固定长度或由前置长度字段决定
     Array,     // 数组类型
 }
 
@@ -47,11 +51,38 @@ pub struct BaseFieldDef {
     pub r#type: FieldType,
     #[serde(rename = "@length", skip_serializing_if = "Option::is_none", default)]
     #[serde(deserialize_with = "deserialize_length")]
-    pub length: Option<usize>, // for Char
+    pub length: Option<usize>, // for Char / Bytes
     #[serde(rename = "@desc")]
     pub desc: Option<String>,  // 字段描述
 }
 
+impl BaseFieldDef {
+    /// 从字段描述中解析 `代码=含义` 形式的取值说明，例如 `买卖方向：1=买, 2=卖`
+    ///
+    /// 代码只能由数字和大写字母组成，`仅Side=1时有效` 这类引用其他字段的说明不会被当作取值
+    pub fn symbols(&self) -> Vec<(&str, &str)> {
+        const SEPARATORS: &[char] = &[',', '，', ':', '：', '(', '（', ')', '）', ';', '；', '、', '。'];
+        let Some(desc) = &self.desc else {
+            return Vec::new();
+        };
+        desc.split(SEPARATORS)
+            .filter_map(|segment| segment.split_once('='))
+            .map(|(code, label)| (code.trim(), label.trim()))
+            .filter(|(code, label)| {
+                !code.is_empty()
+                    && code.len() <= 4
+                    && code.chars().all(|c| c.is_ascii_digit() || c.is_ascii_uppercase())
+                    && !label.is_empty()
+            })
+            .collect()
+    }
+
+    /// 值对应的取值说明
+    pub fn symbol(&self, code: &str) -> Option<&str> {
+        self.symbols().into_iter().find(|(symbol, _)| *symbol == code).map(|(_, label)| label)
+    }
+}
+
 // 自定义反序列化函数，用于处理字符串形式的length属性
 fn deserialize_length<'de, D>(deserializer: D) -> Result<Option<usize>, D::Error>
 where
@@ -178,6 +209,24 @@ mod tests {
         assert_eq!(message.extensions[1].fields.len(), 1);
     }
 
+    #[test]
+    fn test_symbols() {
+        let field = |desc: &str| BaseFieldDef {
+            name: "Field".to_string(),
+            r#type: FieldType::Char,
+            length: Some(2),
+            desc: Some(desc.to_string()),
+        };
+        assert_eq!(field("买卖方向：1=买, 2=卖").symbols(), vec![("1", "买"), ("2", "卖")]);
+        assert_eq!(field("订单状态（1=部分成交，2=全部成交）").symbol("2"), Some("全部成交"));
+        assert_eq!(
+            field("信用标签：全空格=非信用交易, XY=担保品买卖, RZ=融资交易").symbols(),
+            vec![("XY", "担保品买卖"), ("RZ", "融资交易")]
+        );
+        assert!(field("原始会员内部订单编号，仅撤单成功（ExecType=4）时有意义").symbols().is_empty());
+        assert!(field("激活码，仅Side=1时有效").symbols().is_empty());
+    }
+
     #[test]
     fn test_serialize() {
         let message = MessageDef {
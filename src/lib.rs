@@ -1,4 +1,11 @@
+// 派生宏生成的代码通过 ::sse_tdgw_binary 路径引用本 crate
+extern crate self as sse_tdgw_binary;
+
+pub mod capture;
 pub mod codec;
 pub mod config;
 pub mod message;
-pub mod util;
\ No newline at end of file
+pub mod replay;
+pub mod typed;
+pub mod util;
+pub mod wirelog;
\ No newline at end of file
@@ -1,6 +1,734 @@
+use std::fs;
+use std::io::{self, IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::Duration;
+
+use anyhow::{bail, Context};
+use base64::Engine;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use serde::de::DeserializeSeed;
+use tokio::net::TcpStream;
+
+use sse_tdgw_binary::capture::{format_timestamp, CaptureDecoder, CaptureReader, Direction};
+use sse_tdgw_binary::codec::{ChecksumMode, Frames, HexDump, MessageDecoder, MessageEncoder};
+use sse_tdgw_binary::config::manager::ConfigManager;
+use sse_tdgw_binary::message::{Message, MessageSeed, PrintStyle};
+use sse_tdgw_binary::replay::{Pace, Recording, Replayer};
+use sse_tdgw_binary::wirelog::{self, WireLogReader};
+
+/// 上交所 TDGW 二进制协议工具：解码、编码、检查配置和比较消息
+#[derive(Parser)]
+#[command(name = "tdgw", version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// 解码十六进制、base64 或二进制文件中的一个或多个帧
+    Decode {
+        #[command(flatten)]
+        schema: SchemaArgs,
+        #[command(flatten)]
+        input: FrameInput,
+        /// 输出格式
+        #[arg(short, long, value_enum, default_value_t = DecodeOutput::Pretty)]
+        output: DecodeOutput,
+        /// 校验和的处理方式
+        #[arg(long, value_enum, default_value_t = ChecksumArg::Strict)]
+        checksum: ChecksumArg,
+        /// 字段取值无效时保留原始值继续解码，问题输出到标准错误
+        #[arg(long)]
+        lenient: bool,
+    },
+    /// 把 JSON 或 YAML 描述的消息编码为二进制帧
+    Encode {
+        #[command(flatten)]
+        schema: SchemaArgs,
+        /// 消息文件，省略或为 `-` 时从标准输入读取；可以是一条消息或消息列表，YAML 还可以包含多个文档
+        source: Option<String>,
+        /// 输入格式，默认按文件扩展名判断，其余按 JSON 处理
+        #[arg(short, long, value_enum)]
+        input: Option<MessageFormat>,
+        /// 输出格式
+        #[arg(short, long, value_enum, default_value_t = EncodeOutput::Hex)]
+        output: EncodeOutput,
+        /// 写入文件，默认写到标准输出
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+    /// 检查消息定义 XML
+    Validate {
+        #[command(flatten)]
+        schema: SchemaArgs,
+    },
+    /// 列出消息类型和扩展字段
+    List {
+        #[command(flatten)]
+        schema: SchemaArgs,
+        /// 同时列出每个字段的类型和描述
+        #[arg(long)]
+        fields: bool,
+    },
+    /// 逐个字段比较两个帧，有差异时退出码为 1
+    Diff {
+        #[command(flatten)]
+        schema: SchemaArgs,
+        /// 左侧的帧，十六进制或 base64 文本，或文件路径
+        left: String,
+        /// 右侧的帧
+        right: String,
+        /// 输入格式
+        #[arg(short, long, value_enum, default_value_t = InputFormat::Hex)]
+        input: InputFormat,
+        /// 同时比较序列号
+        #[arg(long)]
+        seq_num: bool,
+    },
+    /// 离线读取 pcap 或 pcapng 抓包，按连接重组 TCP 流并逐帧解码；有流问题或解码失败时退出码为 1
+    Pcap {
+        #[command(flatten)]
+        schema: SchemaArgs,
+        /// 抓包文件
+        file: PathBuf,
+        /// 网关端口，只解析与该端口的连接；默认按握手或较小的端口判断网关
+        #[arg(long)]
+        port: Option<u16>,
+        /// 只输出一个方向的消息
+        #[arg(long, value_enum)]
+        direction: Option<DirectionArg>,
+        /// 输出格式
+        #[arg(short, long, value_enum, default_value_t = LogOutput::Line)]
+        output: LogOutput,
+        /// 校验和的处理方式
+        #[arg(long, value_enum, default_value_t = ChecksumArg::Strict)]
+        checksum: ChecksumArg,
+    },
+    /// 读取并检查报文日志（WireLog），逐条解码；有损坏的记录或解码失败时退出码为 1
+    Wirelog {
+        #[command(flatten)]
+        schema: SchemaArgs,
+        /// 日志文件
+        file: PathBuf,
+        /// 只输出一个方向的记录，与 `--seq-num` 一起使用时从该方向的序列号开始
+        #[arg(long, value_enum)]
+        direction: Option<DirectionArg>,
+        /// 只输出一个会话的记录
+        #[arg(long)]
+        session: Option<String>,
+        /// 从第一条序列号不小于该值的记录开始，需要指定 `--direction`
+        #[arg(long, requires = "direction")]
+        seq_num: Option<u32>,
+        /// 输出格式
+        #[arg(short, long, value_enum, default_value_t = LogOutput::Line)]
+        output: LogOutput,
+    },
+    /// 把 WireLog 或抓包中录制的券商端消息回放给被测端，并与录制的应答逐字段比较；有差异时退出码为 1
+    Replay {
+        #[command(flatten)]
+        schema: SchemaArgs,
+        /// 录制文件，扩展名为 `.wlog` 时按报文日志读取，其余按 pcap 或 pcapng 读取
+        recording: PathBuf,
+        /// 被测端地址，如 `127.0.0.1:9126`
+        #[arg(long)]
+        to: String,
+        /// 只回放报文日志中的一个会话
+        #[arg(long)]
+        session: Option<String>,
+        /// 抓包中的网关端口
+        #[arg(long)]
+        port: Option<u16>,
+        /// 按录制间隔的 1/N 发送，默认保持录制时的间隔
+        #[arg(long, conflicts_with = "asap", value_parser = parse_speed)]
+        speed: Option<f64>,
+        /// 不等待，连续发送
+        #[arg(long)]
+        asap: bool,
+        /// 从该值开始为发出的消息重新编号
+        #[arg(long)]
+        rewrite_seq_num: Option<u32>,
+        /// 发送时把 TransactTime 改为当前时间
+        #[arg(long)]
+        rewrite_transact_time: bool,
+        /// 发送完毕后等待应答的毫秒数
+        #[arg(long, default_value_t = 1000)]
+        timeout: u64,
+        /// 比较时忽略的字段，可以重复指定
+        #[arg(long)]
+        ignore_field: Vec<String>,
+        /// 比较时跳过的消息类型，可以重复指定，例如心跳 33
+        #[arg(long)]
+        ignore_type: Vec<u32>,
+    },
+}
+
+#[derive(Args)]
+struct SchemaArgs {
+    /// 消息定义 XML 文件
+    #[arg(short, long, default_value = "config/sse-message.xml")]
+    config: PathBuf,
+}
+
+impl SchemaArgs {
+    fn load(&self) -> anyhow::Result<ConfigManager> {
+        let mut config_manager = ConfigManager::new();
+        config_manager.load_from_file(&self.config)
+            .with_context(|| format!("failed to load {}", self.config.display()))?;
+        Ok(config_manager)
+    }
+}
+
+#[derive(Args)]
+struct FrameInput {
+    /// 十六进制或 base64 文本，或文件路径；省略或为 `-` 时从标准输入读取
+    source: Option<String>,
+    /// 输入格式
+    #[arg(short, long, value_enum, default_value_t = InputFormat::Hex)]
+    input: InputFormat,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum InputFormat {
+    /// 十六进制文本，忽略空白
+    Hex,
+    /// base64 文本，忽略空白
+    Base64,
+    /// 原始字节
+    Binary,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum DecodeOutput {
+    /// 每个字段一行，附带字段描述
+    Pretty,
+    /// 单行 `Name=Value|` 格式
+    Line,
+    /// JSON
+    Json,
+    /// 带字段标注的十六进制转储
+    Annotated,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ChecksumArg {
+    Strict,
+    Warn,
+    Skip,
+}
+
+impl From<ChecksumArg> for ChecksumMode {
+    fn from(value: ChecksumArg) -> Self {
+        match value {
+            ChecksumArg::Strict => ChecksumMode::Strict,
+            ChecksumArg::Warn => ChecksumMode::Warn,
+            ChecksumArg::Skip => ChecksumMode::Skip,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum DirectionArg {
+    /// 券商端发往网关
+    Client,
+    /// 网关发往券商端
+    Server,
+}
+
+impl From<DirectionArg> for Direction {
+    fn from(value: DirectionArg) -> Self {
+        match value {
+            DirectionArg::Client => Direction::ClientToServer,
+            DirectionArg::Server => Direction::ServerToClient,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum LogOutput {
+    /// 每条消息一行：时间、方向和单行格式的消息
+    Line,
+    /// 每条消息一行 JSON
+    Json,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum MessageFormat {
+    Json,
+    Yaml,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum EncodeOutput {
+    /// 每帧一行十六进制文本
+    Hex,
+    /// 原始字节，写到终端时需要指定 `--out`
+    Binary,
+}
+
 #[tokio::main]
-async fn main() -> anyhow::Result<()> {
-    println!("hello world");
+async fn main() -> anyhow::Result<ExitCode> {
+    let out = &mut io::stdout().lock();
+    let result = match Cli::parse().command {
+        Command::Decode { schema, input, output, checksum, lenient } => {
+            read_input(input.source.as_deref(), input.input)
+                .and_then(|buffer| decode(out, &schema.load()?, &buffer, output, checksum.into(), lenient))
+        },
+        Command::Encode { schema, source, input, output, out: path } => {
+            schema.load().and_then(|config_manager| encode(out, &config_manager, source.as_deref(), input, output, path))
+        },
+        Command::Validate { schema } => validate(out, &schema),
+        Command::List { schema, fields } => schema.load().and_then(|config_manager| list(out, &config_manager, fields)),
+        Command::Diff { schema, left, right, input, seq_num } => {
+            let config_manager = schema.load()?;
+            diff(out, &config_manager, &read_input(Some(&left), input)?, &read_input(Some(&right), input)?, seq_num)
+        },
+        Command::Pcap { schema, file, port, direction, output, checksum } => {
+            schema.load().and_then(|config_manager| {
+                pcap(out, &config_manager, &file, port, direction.map(Direction::from), output, checksum.into())
+            })
+        },
+        Command::Wirelog { schema, file, direction, session, seq_num, output } => {
+            schema.load().and_then(|config_manager| {
+                wirelog(out, &config_manager, &file, direction.map(Direction::from), session.as_deref(), seq_num, output)
+            })
+        },
+        Command::Replay { schema, recording, to, session, port, speed, asap, rewrite_seq_num, rewrite_transact_time, timeout, ignore_field, ignore_type } => {
+            let config_manager = schema.load()?;
+            let recording = load_recording(&config_manager, &recording, session.as_deref(), port)?;
+            let pace = match (asap, speed) {
+                (true, _) => Pace::AsFastAsPossible,
+                (false, Some(speed)) => Pace::Speed(speed),
+                (false, None) => Pace::Original,
+            };
+            let mut replayer = Replayer::new(&config_manager)
+                .with_pace(pace)
+                .with_response_timeout(Duration::from_millis(timeout));
+            if let Some(start) = rewrite_seq_num {
+                replayer = replayer.rewrite_seq_num(start);
+            }
+            if rewrite_transact_time {
+                replayer = replayer.rewrite_transact_time();
+            }
+            replayer = ignore_field.into_iter().fold(replayer, Replayer::ignore_field);
+            replayer = ignore_type.into_iter().fold(replayer, Replayer::ignore_msg_type);
+            replay(out, &replayer, &recording, &to).await
+        },
+    };
+
+    // 输出通过管道交给 head 等命令时，对方提前关闭不算出错
+    match result {
+        Err(e) if e.downcast_ref::<io::Error>().is_some_and(|e| e.kind() == io::ErrorKind::BrokenPipe) => Ok(ExitCode::SUCCESS),
+        result => result,
+    }
+}
+
+/// 读取输入：`-` 或省略时读标准输入，已存在的路径读文件，否则把参数本身当作文本
+fn read_input(source: Option<&str>, format: InputFormat) -> anyhow::Result<Vec<u8>> {
+    let raw = match source {
+        None | Some("-") => {
+            let mut raw = Vec::new();
+            io::stdin().read_to_end(&mut raw).context("failed to read standard input")?;
+            raw
+        },
+        Some(path) if Path::new(path).is_file() => fs::read(path).with_context(|| format!("failed to read {}", path))?,
+        Some(_) if matches!(format, InputFormat::Binary) => bail!("binary input must be a file or standard input"),
+        Some(text) => text.as_bytes().to_vec(),
+    };
+
+    let text = || -> anyhow::Result<String> {
+        let text = std::str::from_utf8(&raw).context("input is not text")?;
+        Ok(text.split_whitespace().collect())
+    };
+    match format {
+        InputFormat::Hex => {
+            let text = text()?;
+            hex::decode(text.strip_prefix("0x").unwrap_or(&text)).context("invalid hex input")
+        },
+        InputFormat::Base64 => base64::engine::general_purpose::STANDARD.decode(text()?).context("invalid base64 input"),
+        InputFormat::Binary => Ok(raw),
+    }
+}
 
+fn decode(out: &mut impl Write, config_manager: &ConfigManager, buffer: &[u8], output: DecodeOutput, checksum_mode: ChecksumMode, lenient: bool) -> anyhow::Result<ExitCode> {
+    let mut failed = false;
+    let mut end = 0;
+    for frame in Frames::new(buffer) {
+        let (offset, frame) = match frame {
+            Ok(frame) => frame,
+            Err(e) => {
+                // 剩余字节不足一帧，转储时仍然标出能识别的部分
+                eprintln!("offset {}: {}", end, e);
+                if output == DecodeOutput::Annotated {
+                    write!(out, "{}", HexDump::new(config_manager, &buffer[end..]))?;
+                }
+                failed = true;
+                break;
+            },
+        };
+        end = offset + frame.len();
+
+        if output == DecodeOutput::Annotated {
+            let dump = HexDump::new(config_manager, frame);
+            failed |= dump.has_errors();
+            writeln!(out, "{}", dump)?;
+        } else {
+            let mut decoder = MessageDecoder::new(config_manager, frame).with_checksum_mode(checksum_mode);
+            let result = match lenient {
+                true => decoder.decode_lenient().map(|decoded| {
+                    for diagnostic in &decoded.diagnostics {
+                        eprintln!("offset {}: {}", offset, diagnostic);
+                    }
+                    failed |= !decoded.is_clean();
+                    decoded.message
+                }),
+                false => decoder.decode_with_context(),
+            };
+            match result {
+                Ok(message) => {
+                    if message.checksum_mismatch {
+                        eprintln!("offset {}: checksum mismatch", offset);
+                    }
+                    print_message(out, config_manager, &message, output)?;
+                },
+                Err(e) => {
+                    eprintln!("offset {}: {}", offset, e);
+                    failed = true;
+                },
+            }
+        }
+    }
+    Ok(exit_code(failed))
+}
+
+fn print_message(out: &mut impl Write, config_manager: &ConfigManager, message: &Message, output: DecodeOutput) -> anyhow::Result<()> {
+    match output {
+        DecodeOutput::Json => writeln!(out, "{}", serde_json::to_string_pretty(message)?)?,
+        DecodeOutput::Pretty | DecodeOutput::Line => {
+            let style = match output {
+                DecodeOutput::Line => PrintStyle::SingleLine,
+                _ => PrintStyle::MultiLine,
+            };
+            writeln!(out, "{}", config_manager.printer(message.msg_type)?.with_style(style).format(message))?;
+        },
+        DecodeOutput::Annotated => unreachable!("annotated output is written from the frame"),
+    }
     Ok(())
 }
+
+fn encode(out: &mut impl Write, config_manager: &ConfigManager, source: Option<&str>, input: Option<MessageFormat>, output: EncodeOutput, path: Option<PathBuf>) -> anyhow::Result<ExitCode> {
+    let text = match source {
+        None | Some("-") => {
+            let mut text = String::new();
+            io::stdin().read_to_string(&mut text).context("failed to read standard input")?;
+            text
+        },
+        Some(path) => fs::read_to_string(path).with_context(|| format!("failed to read {}", path))?,
+    };
+    let format = input.unwrap_or(match source.and_then(|path| Path::new(path).extension()) {
+        Some(extension) if extension == "yaml" || extension == "yml" => MessageFormat::Yaml,
+        _ => MessageFormat::Json,
+    });
+    let messages = match format {
+        MessageFormat::Json => match serde_json::from_str(&text)? {
+            serde_json::Value::Array(values) => read_messages(config_manager, values)?,
+            value => read_messages(config_manager, vec![value])?,
+        },
+        MessageFormat::Yaml => {
+            let mut messages = Vec::new();
+            for document in serde_norway::Deserializer::from_str(&text) {
+                messages.extend(match serde::Deserialize::deserialize(document)? {
+                    serde_norway::Value::Sequence(values) => read_messages(config_manager, values)?,
+                    value => read_messages(config_manager, vec![value])?,
+                });
+            }
+            messages
+        },
+    };
+
+    let mut encoder = MessageEncoder::new(config_manager);
+    let mut encoded = Vec::new();
+    for (index, message) in messages.iter().enumerate() {
+        let frame = encoder.encode(message).with_context(|| format!("failed to encode message #{}", index))?;
+        match output {
+            EncodeOutput::Hex => encoded.extend(format!("{}\n", hex::encode(frame)).into_bytes()),
+            EncodeOutput::Binary => encoded.extend(frame),
+        }
+    }
+
+    match path {
+        Some(path) => fs::write(&path, encoded).with_context(|| format!("failed to write {}", path.display()))?,
+        None => {
+            if matches!(output, EncodeOutput::Binary) && io::stdout().is_terminal() {
+                bail!("refusing to write binary frames to a terminal, use --out or --output hex");
+            }
+            out.write_all(&encoded)?;
+        },
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+/// 按消息定义逐条反序列化 JSON 或 YAML 值
+fn read_messages<'de, V: serde::Deserializer<'de>>(config_manager: &ConfigManager, values: Vec<V>) -> anyhow::Result<Vec<Message>> {
+    values.into_iter().enumerate()
+        .map(|(index, value)| {
+            MessageSeed::new(config_manager).deserialize(value)
+                .map_err(|e| anyhow::anyhow!("message #{}: {}", index, e))
+        })
+        .collect()
+}
+
+fn validate(out: &mut impl Write, schema: &SchemaArgs) -> anyhow::Result<ExitCode> {
+    let config_manager = schema.load()?;
+    let errors = config_manager.validate();
+    for error in &errors {
+        writeln!(out, "{}", error)?;
+    }
+    let defs = config_manager.message_defs();
+    let extensions: usize = defs.iter().map(|def| config_manager.extensions(def.msg_type).len()).sum();
+    writeln!(out, 
+        "{}: {} message types, {} extensions, {} problems",
+        schema.config.display(), defs.len(), extensions, errors.len()
+    )?;
+    Ok(exit_code(!errors.is_empty()))
+}
+
+fn list(out: &mut impl Write, config_manager: &ConfigManager, fields: bool) -> anyhow::Result<ExitCode> {
+    for def in config_manager.message_defs() {
+        let body = config_manager.get_layout(def.msg_type)
+            .and_then(|layout| layout.body().fixed_len())
+            .map_or("variable".to_string(), |len| format!("{} bytes", len));
+        writeln!(out, "{:>6}  {:<32} {:>3} fields, {}", def.msg_type, def.name, def.fields.len(), body)?;
+        if fields {
+            for field in &def.fields {
+                print_field(out, &field.base.name, &field.base.r#type, field.base.length, field.base.desc.as_deref(), 8)?;
+                for element in field.r#struct.iter().flat_map(|struct_def| &struct_def.fields) {
+                    print_field(out, &element.name, &element.r#type, element.length, element.desc.as_deref(), 12)?;
+                }
+            }
+        }
+        for extension in config_manager.extensions(def.msg_type) {
+            let names: Vec<_> = extension.fields.iter().map(|field| field.name.as_str()).collect();
+            writeln!(out, "        BizID {}: {}", extension.biz_id, names.join(", "))?;
+            if fields {
+                for field in &extension.fields {
+                    print_field(out, &field.name, &field.r#type, field.length, field.desc.as_deref(), 12)?;
+                }
+            }
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn print_field(out: &mut impl Write, name: &str, r#type: &impl std::fmt::Debug, length: Option<usize>, desc: Option<&str>, indent: usize) -> io::Result<()> {
+    let r#type = match length {
+        Some(length) => format!("{:?}({})", r#type, length),
+        None => format!("{:?}", r#type),
+    };
+    writeln!(out, "{:indent$}{:<24} {:<12} {}", "", name, r#type, desc.unwrap_or_default(), indent = indent)
+}
+
+fn diff(out: &mut impl Write, config_manager: &ConfigManager, left: &[u8], right: &[u8], seq_num: bool) -> anyhow::Result<ExitCode> {
+    let decode = |frame: &[u8], side: &str| -> anyhow::Result<Message> {
+        let decoded = MessageDecoder::new(config_manager, frame)
+            .with_checksum_mode(ChecksumMode::Warn)
+            .decode_lenient()
+            .with_context(|| format!("failed to decode {} frame", side))?;
+        for diagnostic in &decoded.diagnostics {
+            eprintln!("{}: {}", side, diagnostic);
+        }
+        if decoded.message.checksum_mismatch {
+            eprintln!("{}: checksum mismatch", side);
+        }
+        Ok(decoded.message)
+    };
+    let (left, right) = (decode(left, "left")?, decode(right, "right")?);
+
+    let mut differences = 0;
+    if left.msg_type != right.msg_type {
+        writeln!(out, "MsgType: {} -> {}", left.msg_type, right.msg_type)?;
+        differences += 1;
+    }
+    if seq_num && left.seq_num != right.seq_num {
+        writeln!(out, "SeqNum: {} -> {}", left.seq_num, right.seq_num)?;
+        differences += 1;
+    }
+    for field in left.diff(&right) {
+        writeln!(out, "{}", field)?;
+        differences += 1;
+    }
+    if differences == 0 {
+        writeln!(out, "frames are identical")?;
+    }
+    Ok(exit_code(differences > 0))
+}
+
+fn pcap(out: &mut impl Write, config_manager: &ConfigManager, file: &Path, port: Option<u16>, direction: Option<Direction>, output: LogOutput, checksum_mode: ChecksumMode) -> anyhow::Result<ExitCode> {
+    let reader = CaptureReader::open(file).with_context(|| format!("failed to open {}", file.display()))?;
+    let mut decoder = CaptureDecoder::new(config_manager).with_checksum_mode(checksum_mode);
+    if let Some(port) = port {
+        decoder = decoder.with_port(port);
+    }
+    // 抓包文件末尾损坏时仍然输出已经读到的部分
+    let mut failed = false;
+    for packet in reader {
+        match packet {
+            Ok(packet) => decoder.feed(&packet),
+            Err(e) => {
+                eprintln!("{}: {}", file.display(), e);
+                failed = true;
+                break;
+            },
+        }
+    }
+    let report = decoder.finish();
+
+    let (mut messages, mut issues, mut failures) = (0, 0, 0);
+    for connection in &report.connections {
+        if matches!(output, LogOutput::Line) {
+            writeln!(out, "# {} -> {}", connection.client, connection.server)?;
+        }
+        for captured in connection.messages.iter().filter(|captured| direction.is_none_or(|direction| captured.direction == direction)) {
+            let timestamp = format_timestamp(captured.timestamp);
+            messages += 1;
+            match (&captured.result, output) {
+                (Ok(message), LogOutput::Line) => {
+                    let line = config_manager.printer(message.msg_type)?.single_line().format(message);
+                    writeln!(out, "{} {} {}", timestamp, captured.direction, line)?;
+                },
+                (Ok(message), LogOutput::Json) => {
+                    let record = serde_json::json!({
+                        "timestamp": timestamp,
+                        "client": connection.client.to_string(),
+                        "server": connection.server.to_string(),
+                        "direction": direction_name(captured.direction),
+                        "message": message,
+                    });
+                    writeln!(out, "{}", record)?;
+                },
+                (Err(e), _) => {
+                    failures += 1;
+                    eprintln!("{} {} {} -> {}: {}", timestamp, captured.direction, connection.client, connection.server, e);
+                    eprintln!("    {}", hex::encode(&captured.frame));
+                },
+            }
+        }
+        for event in connection.events.iter().filter(|event| direction.is_none_or(|direction| event.direction == direction)) {
+            issues += 1;
+            eprintln!(
+                "{} {} {} -> {}: {}",
+                format_timestamp(event.timestamp), event.direction, connection.client, connection.server, event.issue,
+            );
+        }
+    }
+    eprintln!(
+        "{} packets ({} skipped), {} connections, {} messages, {} stream issues, {} decode failures",
+        report.packets, report.skipped, report.connections.len(), messages, issues, failures,
+    );
+    Ok(exit_code(failed || issues > 0 || failures > 0))
+}
+
+fn wirelog(out: &mut impl Write, config_manager: &ConfigManager, file: &Path, direction: Option<Direction>, session: Option<&str>, seq_num: Option<u32>, output: LogOutput) -> anyhow::Result<ExitCode> {
+    let mut reader = WireLogReader::open(file).with_context(|| format!("failed to open {}", file.display()))?;
+    if let (Some(direction), Some(seq_num)) = (direction, seq_num) {
+        reader.seek_seq_num(session, direction, seq_num)?;
+    }
+
+    let (mut records, mut failures) = (0, 0);
+    let mut failed = false;
+    for record in reader {
+        let record = match record {
+            Ok(record) => record,
+            Err(e) => {
+                eprintln!("{}: {}", file.display(), e);
+                failed = true;
+                break;
+            },
+        };
+        if direction.is_some_and(|direction| record.direction != direction) || session.is_some_and(|session| record.session_id != session) {
+            continue;
+        }
+        records += 1;
+        let timestamp = format_timestamp(record.timestamp);
+        match (record.decode(config_manager), output) {
+            (Ok(message), LogOutput::Line) => {
+                let line = config_manager.printer(message.msg_type)?.single_line().format(&message);
+                writeln!(out, "{} {} {} {}", timestamp, record.session_id, record.direction, line)?;
+            },
+            (Ok(message), LogOutput::Json) => {
+                let entry = serde_json::json!({
+                    "timestamp": timestamp,
+                    "session": record.session_id,
+                    "direction": direction_name(record.direction),
+                    "message": message,
+                });
+                writeln!(out, "{}", entry)?;
+            },
+            (Err(e), _) => {
+                failures += 1;
+                eprintln!("{} {} {} seq {}: {}", timestamp, record.session_id, record.direction, record.seq_num, e);
+                eprintln!("    {}", hex::encode(&record.frame));
+            },
+        }
+    }
+    eprintln!("{} records, {} decode failures", records, failures);
+    Ok(exit_code(failed || failures > 0))
+}
+
+fn direction_name(direction: Direction) -> &'static str {
+    match direction {
+        Direction::ClientToServer => "client",
+        Direction::ServerToClient => "server",
+    }
+}
+
+/// 读取录制的会话，抓包中有多个连接时取第一个
+fn load_recording(config_manager: &ConfigManager, path: &Path, session: Option<&str>, port: Option<u16>) -> anyhow::Result<Recording> {
+    if path.extension().is_some_and(|extension| extension == wirelog::EXTENSION) {
+        let reader = WireLogReader::open(path).with_context(|| format!("failed to open {}", path.display()))?;
+        return Ok(Recording::from_wirelog(reader, session)?);
+    }
+
+    let mut decoder = CaptureDecoder::new(config_manager);
+    if let Some(port) = port {
+        decoder = decoder.with_port(port);
+    }
+    for packet in CaptureReader::open(path).with_context(|| format!("failed to open {}", path.display()))? {
+        decoder.feed(&packet?);
+    }
+    let report = decoder.finish();
+    if report.connections.len() > 1 {
+        eprintln!("{} connections in capture, replaying the first one", report.connections.len());
+    }
+    match report.connections.first() {
+        Some(connection) => Ok(Recording::from_connection(connection)),
+        None => bail!("no TCP connection found in {}", path.display()),
+    }
+}
+
+/// 回放倍速必须是大于 0 的有限值
+fn parse_speed(value: &str) -> Result<f64, String> {
+    match value.parse::<f64>() {
+        Ok(speed) if speed.is_finite() && speed > 0.0 => Ok(speed),
+        Ok(_) => Err("speed must be a positive number".to_string()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+async fn replay(out: &mut impl Write, replayer: &Replayer<'_>, recording: &Recording, to: &str) -> anyhow::Result<ExitCode> {
+    let stream = TcpStream::connect(to).await.with_context(|| format!("failed to connect to {}", to))?;
+    let report = replayer.replay(recording, stream).await?;
+    for mismatch in &report.mismatches {
+        writeln!(out, "{}", mismatch)?;
+    }
+    writeln!(
+        out, "sent {} frames, received {} frames, {} mismatches",
+        report.sent, report.received.len(), report.mismatches.len(),
+    )?;
+    Ok(exit_code(!report.is_match()))
+}
+
+fn exit_code(failed: bool) -> ExitCode {
+    if failed { ExitCode::FAILURE } else { ExitCode::SUCCESS }
+}
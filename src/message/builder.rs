@@ -0,0 +1,148 @@
+use indexmap::IndexMap;
+
+use crate::codec::coerce_field_value;
+use crate::config::manager::ConfigManager;
+use crate::config::types::{BaseFieldDef, FieldDef, FieldType, MessageDef};
+use crate::message::{FieldValue, Message, Record};
+use crate::util::{MessageError, MessageResult};
+
+/// 基于消息定义的构建器，在设置字段时校验字段名和类型
+///
+/// 字段名在运行时按配置校验，拼写错误在 `set` 时报错而不是在编译期；需要编译期检查时使用
+/// [`crate::typed`] 中由同一份 XML 生成的消息结构体。XML 中没有可选字段的标记，
+/// 因此消息字段和当前 BizID 的扩展字段都必须设置。
+///
+/// 通过 [`ConfigManager::builder`] 按消息名称创建：
+///
+/// ```ignore
+/// let message = config_manager.builder("Logon")?
+///     .seq_num(1)
+///     .set("SenderCompID", "SENDER")?
+///     .set("HeartBtInt", 30u32)?
+///     .build()?;
+/// ```
+pub struct MessageBuilder<'a> {
+    /// 配置管理器，用于查找扩展定义
+    config_manager: &'a ConfigManager,
+    /// 正在构建的消息定义
+    message_def: &'a MessageDef,
+    /// 序列号
+    seq_num: u32,
+    /// 已设置的字段值，均已转换为字段定义要求的类型
+    fields: IndexMap<String, FieldValue>,
+}
+
+impl<'a> MessageBuilder<'a> {
+    /// 创建一个新的消息构建器
+    pub(crate) fn new(config_manager: &'a ConfigManager, message_def: &'a MessageDef) -> Self {
+        Self {
+            config_manager,
+            message_def,
+            seq_num: 0,
+            fields: IndexMap::new(),
+        }
+    }
+
+    /// 设置序列号
+    pub fn seq_num(mut self, seq_num: u32) -> Self {
+        self.seq_num = seq_num;
+        self
+    }
+
+    /// 设置字段值
+    ///
+    /// 字段名必须是消息字段或任一 BizID 扩展中的字段，值会按字段类型做无损转换
+    pub fn set(mut self, name: &str, value: impl Into<FieldValue>) -> MessageResult<Self> {
+        let value = value.into();
+        let value = if let Some(field_def) = self.message_def.fields.iter().find(|def| def.base.name == name) {
+            Self::coerce(&field_def.base, Some(field_def), value)?
+        } else if let Some(base_field_def) = self.extension_field_def(name) {
+            // 扩展字段在 build 时还会按最终的 BizID 重新校验
+            Self::coerce(base_field_def, None, value)?
+        } else {
+            return Err(MessageError::UnknownField(format!("{} (message {})", name, self.message_def.name)));
+        };
+
+        self.fields.insert(name.to_string(), value);
+        Ok(self)
+    }
+
+    /// 构建消息
+    ///
+    /// 字段按消息定义顺序排列，随后是当前 BizID 对应的扩展字段；
+    /// 未设置的字段和不属于当前 BizID 的扩展字段都会报错
+    pub fn build(mut self) -> MessageResult<Message> {
+        let mut message = Message::new(self.message_def.msg_type, self.seq_num);
+        let mut missing = Vec::new();
+
+        for field_def in &self.message_def.fields {
+            match self.fields.shift_remove(&field_def.base.name) {
+                Some(value) => message.add_field(field_def.base.name.clone(), value),
+                None => missing.push(field_def.base.name.clone()),
+            }
+        }
+
+        let biz_id = message.get_field("BizID").and_then(|v| v.as_u32());
+        if let Some(biz_extension) = biz_id.and_then(|id| self.config_manager.get_extension(self.message_def.msg_type, id)) {
+            for field_def in &biz_extension.fields {
+                match self.fields.shift_remove(&field_def.name) {
+                    Some(value) => {
+                        let value = Self::coerce(field_def, None, value)?;
+                        message.add_field(field_def.name.clone(), value);
+                    },
+                    None => missing.push(field_def.name.clone()),
+                }
+            }
+        }
+
+        // 剩余的只可能是其他 BizID 的扩展字段，按设置顺序报告第一个
+        if let Some(name) = self.fields.keys().next() {
+            return Err(MessageError::UnknownField(match biz_id {
+                Some(biz_id) => format!("{} (not an extension field of BizID {})", name, biz_id),
+                None => format!("{} (extension field requires BizID)", name),
+            }));
+        }
+
+        if !missing.is_empty() {
+            return Err(MessageError::MissingRequiredFields(missing));
+        }
+
+        Ok(message)
+    }
+
+    /// 在所有扩展中查找字段定义
+    fn extension_field_def(&self, name: &str) -> Option<&'a BaseFieldDef> {
+        self.message_def.extensions.iter()
+            .flat_map(|extension| extension.fields.iter())
+            .find(|def| def.name == name)
+    }
+
+    /// 按字段定义转换字段值，数组字段逐个元素按字段名转换并按结构定义排序
+    fn coerce(base_field_def: &BaseFieldDef, field_def: Option<&FieldDef>, value: FieldValue) -> MessageResult<FieldValue> {
+        let struct_def = match (&base_field_def.r#type, field_def.and_then(|def| def.r#struct.as_ref())) {
+            (FieldType::Array, Some(struct_def)) => struct_def,
+            _ => return Ok(coerce_field_value(base_field_def, &value)?.into_owned()),
+        };
+
+        let elements = match value {
+            FieldValue::Array(elements) => elements,
+            other => return Err(MessageError::InvalidFieldValue(format!(
+                "Field '{}' type mismatch: expected {:?}, got {:?}",
+                base_field_def.name, base_field_def.r#type, other
+            ))),
+        };
+
+        let mut coerced = Vec::with_capacity(elements.len());
+        for (index, element) in elements.iter().enumerate() {
+            element.check_names(&format!("{}[{}]", base_field_def.name, index), struct_def)?;
+            let element = struct_def.fields.iter()
+                .map(|def| {
+                    coerce_field_value(def, &element[def.name.as_str()]).map(|v| (def.name.clone(), v.into_owned()))
+                })
+                .collect::<MessageResult<Record>>()?;
+            coerced.push(element);
+        }
+
+        Ok(FieldValue::Array(coerced))
+    }
+}
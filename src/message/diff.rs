@@ -0,0 +1,73 @@
+use std::fmt;
+
+use crate::message::{FieldValue, Message};
+
+/// 两条消息中取值不同的一个字段
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldDiff {
+    /// 字段路径，数组元素字段形如 `Parties[1].PartyID`
+    pub path: String,
+    /// 左侧消息中的值，字段不存在时为 `None`
+    pub left: Option<FieldValue>,
+    /// 右侧消息中的值，字段不存在时为 `None`
+    pub right: Option<FieldValue>,
+}
+
+impl fmt::Display for FieldDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let side = |value: &Option<FieldValue>| value.as_ref().map_or("<missing>".to_string(), ToString::to_string);
+        write!(f, "{}: {} -> {}", self.path, side(&self.left), side(&self.right))
+    }
+}
+
+impl Message {
+    /// 逐个字段比较两条消息，数组按元素逐个比较，不比较消息类型和序列号
+    ///
+    /// 结果按本消息的字段顺序排列，只出现在 `other` 中的字段排在最后
+    pub fn diff(&self, other: &Message) -> Vec<FieldDiff> {
+        let mut diffs = Vec::new();
+        diff_fields(
+            "",
+            self.fields.iter().map(|(name, value)| (name.as_str(), value)),
+            |name| other.get_field(name),
+            other.fields.iter().map(|(name, value)| (name.as_str(), value)),
+            |name| self.has_field(name),
+            &mut diffs,
+        );
+        diffs
+    }
+}
+
+fn diff_fields<'a>(
+    prefix: &str,
+    left: impl Iterator<Item = (&'a str, &'a FieldValue)>,
+    right_get: impl Fn(&str) -> Option<&'a FieldValue>,
+    right: impl Iterator<Item = (&'a str, &'a FieldValue)>,
+    left_has: impl Fn(&str) -> bool,
+    diffs: &mut Vec<FieldDiff>,
+) {
+    for (name, value) in left {
+        diff_value(format!("{}{}", prefix, name), Some(value), right_get(name), diffs);
+    }
+    for (name, value) in right.filter(|(name, _)| !left_has(name)) {
+        diff_value(format!("{}{}", prefix, name), None, Some(value), diffs);
+    }
+}
+
+fn diff_value(path: String, left: Option<&FieldValue>, right: Option<&FieldValue>, diffs: &mut Vec<FieldDiff>) {
+    match (left, right) {
+        (Some(FieldValue::Array(left)), Some(FieldValue::Array(right))) => {
+            for index in 0..left.len().max(right.len()) {
+                let prefix = format!("{}[{}].", path, index);
+                match (left.get(index), right.get(index)) {
+                    (Some(left), Some(right)) => diff_fields(&prefix, left.iter(), |name| right.get(name), right.iter(), |name| left.contains(name), diffs),
+                    (Some(left), None) => diff_fields(&prefix, left.iter(), |_| None, std::iter::empty(), |_| true, diffs),
+                    (None, Some(right)) => diff_fields(&prefix, std::iter::empty(), |_| None, right.iter(), |_| false, diffs),
+                    (None, None) => {},
+                }
+            }
+        },
+        (left, right) if left != right => diffs.push(FieldDiff { path, left: left.cloned(), right: right.cloned() }),
+        _ => {},
+    }
+}
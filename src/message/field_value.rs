@@ -1,5 +1,8 @@
 use std::fmt;
 
+use crate::message::Record;
+use crate::util::ConversionError;
+
 /// 字段值枚举，表示消息中各种类型的字段值
 #[derive(Debug, Clone, PartialEq)]
 pub enum FieldValue {
@@ -11,14 +14,22 @@ pub enum FieldValue {
     U32(u32),
     /// 无符号64位整数
     U64(u64),
+    /// 有符号8位整数
+    I8(i8),
+    /// 有符号16位整数
+    I16(i16),
+    /// 有符号32位整数
+    I32(i32),
     /// 有符号64位整数
     I64(i64),
     /// 浮点数类型
     Float(f64),
     /// 字符串类型
     Str(String),
-    /// 数组类型，表示嵌套的字段值数组
-    Array(Vec<Vec<FieldValue>>),
+    /// 原始字节类型，不做任何编码校验和裁剪
+    Bytes(Vec<u8>),
+    /// 数组类型，每个元素按结构定义中的字段名保存字段值
+    Array(Vec<Record>),
 }
 
 impl fmt::Display for FieldValue {
@@ -28,20 +39,17 @@ impl fmt::Display for FieldValue {
             FieldValue::U16(v) => write!(f, "{}", v),
             FieldValue::U32(v) => write!(f, "{}", v),
             FieldValue::U64(v) => write!(f, "{}", v),
+            FieldValue::I8(v) => write!(f, "{}", v),
+            FieldValue::I16(v) => write!(f, "{}", v),
+            FieldValue::I32(v) => write!(f, "{}", v),
             FieldValue::I64(v) => write!(f, "{}", v),
             FieldValue::Float(v) => write!(f, "{}", v),
             FieldValue::Str(v) => write!(f, "{}", v),
+            FieldValue::Bytes(v) => write!(f, "{}", hex::encode(v)),
             FieldValue::Array(v) => {
                 write!(f, "[")?;
                 for (i, item) in v.iter().enumerate() {
-                    write!(f, "{{")?;
-                    for (isub, sub_item) in item.iter().enumerate() {
-                        if isub > 0 {
-                            write!(f, ", ")?;
-                        }
-                        write!(f, "{}", sub_item)?;
-                    }
-                    write!(f, "}}")?;
+                    write!(f, "{}", item)?;
 
                     if i < v.len() - 1 {
                         write!(f, ", ")?;
@@ -54,6 +62,28 @@ impl fmt::Display for FieldValue {
 }
 
 impl FieldValue {
+    /// 变体名称，用于错误信息
+    pub fn variant_name(&self) -> &'static str {
+        match self {
+            FieldValue::U8(_) => "U8",
+            FieldValue::U16(_) => "U16",
+            FieldValue::U32(_) => "U32",
+            FieldValue::U64(_) => "U64",
+            FieldValue::I8(_) => "I8",
+            FieldValue::I16(_) => "I16",
+            FieldValue::I32(_) => "I32",
+            FieldValue::I64(_) => "I64",
+            FieldValue::Float(_) => "Float",
+            FieldValue::Str(_) => "Str",
+            FieldValue::Bytes(_) => "Bytes",
+            FieldValue::Array(_) => "Array",
+        }
+    }
+
+    fn conversion_error(&self, expected: &'static str) -> ConversionError {
+        ConversionError { expected, actual: self.variant_name() }
+    }
+
     pub fn as_u8(&self) -> Option<u8> {
         match self {
             FieldValue::U8(v) => Some(*v),
@@ -82,6 +112,27 @@ impl FieldValue {
         }
     }
 
+    pub fn as_i8(&self) -> Option<i8> {
+        match self {
+            FieldValue::I8(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    pub fn as_i16(&self) -> Option<i16> {
+        match self {
+            FieldValue::I16(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    pub fn as_i32(&self) -> Option<i32> {
+        match self {
+            FieldValue::I32(v) => Some(*v),
+            _ => None,
+        }
+    }
+
     pub fn as_i64(&self) -> Option<i64> {
         match self {
             FieldValue::I64(v) => Some(*v),
@@ -89,6 +140,21 @@ impl FieldValue {
         }
     }
 
+    /// 将任意整数值转换为 i128，便于跨宽度比较和范围检查
+    pub fn as_integer(&self) -> Option<i128> {
+        match self {
+            FieldValue::U8(v) => Some(*v as i128),
+            FieldValue::U16(v) => Some(*v as i128),
+            FieldValue::U32(v) => Some(*v as i128),
+            FieldValue::U64(v) => Some(*v as i128),
+            FieldValue::I8(v) => Some(*v as i128),
+            FieldValue::I16(v) => Some(*v as i128),
+            FieldValue::I32(v) => Some(*v as i128),
+            FieldValue::I64(v) => Some(*v as i128),
+            _ => None,
+        }
+    }
+
     pub fn as_f64(&self) -> Option<f64> {
         match self {
             FieldValue::Float(v) => Some(*v),
@@ -103,7 +169,14 @@ impl FieldValue {
         }
     }
 
-    pub fn as_array(&self) -> Option<&Vec<Vec<FieldValue>>> {
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            FieldValue::Bytes(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&[Record]> {
         match self {
             FieldValue::Array(v) => Some(v),
             _ => None,
@@ -136,6 +209,24 @@ impl From<u64> for FieldValue {
     }
 }
 
+impl From<i8> for FieldValue {
+    fn from(value: i8) -> Self {
+        FieldValue::I8(value)
+    }
+}
+
+impl From<i16> for FieldValue {
+    fn from(value: i16) -> Self {
+        FieldValue::I16(value)
+    }
+}
+
+impl From<i32> for FieldValue {
+    fn from(value: i32) -> Self {
+        FieldValue::I32(value)
+    }
+}
+
 impl From<i64> for FieldValue {
     fn from(value: i64) -> Self {
         FieldValue::I64(value)
@@ -160,56 +251,115 @@ impl From<String> for FieldValue {
     }
 }
 
-impl Into<u8> for FieldValue {
-    fn into(self) -> u8 {
-        match self {
-            FieldValue::U8(v) => v,
-            _ => panic!("Cannot convert FieldValue to u8"),
-        }
+impl From<Vec<u8>> for FieldValue {
+    fn from(value: Vec<u8>) -> Self {
+        FieldValue::Bytes(value)
     }
 }
 
-impl Into<u16> for FieldValue {
-    fn into(self) -> u16 {
-        match self {
-            FieldValue::U16(v) => v,
-            _ => panic!("Cannot convert FieldValue to u16"),
-        }
+impl From<&[u8]> for FieldValue {
+    fn from(value: &[u8]) -> Self {
+        FieldValue::Bytes(value.to_vec())
     }
 }
 
-impl Into<u32> for FieldValue {
-    fn into(self) -> u32 {
-        match self {
-            FieldValue::U32(v) => v,
-            _ => panic!("Cannot convert FieldValue to u32"),
-        }
+impl From<Vec<Record>> for FieldValue {
+    fn from(value: Vec<Record>) -> Self {
+        FieldValue::Array(value)
     }
 }
 
-impl Into<u64> for FieldValue {
-    fn into(self) -> u64 {
-        match self {
-            FieldValue::U64(v) => v,
-            _ => panic!("Cannot convert FieldValue to u64"),
+/// 按变体取出基础类型的值，变体不符时返回 [`ConversionError`]
+macro_rules! try_from_field_value {
+    ($($ty:ty => $variant:ident),+ $(,)?) => {
+        $(
+            impl TryFrom<&FieldValue> for $ty {
+                type Error = ConversionError;
+
+                fn try_from(value: &FieldValue) -> Result<Self, Self::Error> {
+                    match value {
+                        FieldValue::$variant(v) => Ok(*v),
+                        other => Err(other.conversion_error(stringify!($variant))),
+                    }
+                }
+            }
+
+            impl TryFrom<FieldValue> for $ty {
+                type Error = ConversionError;
+
+                fn try_from(value: FieldValue) -> Result<Self, Self::Error> {
+                    <$ty>::try_from(&value)
+                }
+            }
+        )+
+    };
+}
+
+try_from_field_value! {
+    u8 => U8,
+    u16 => U16,
+    u32 => U32,
+    u64 => U64,
+    i8 => I8,
+    i16 => I16,
+    i32 => I32,
+    i64 => I64,
+    f64 => Float,
+}
+
+impl TryFrom<FieldValue> for String {
+    type Error = ConversionError;
+
+    fn try_from(value: FieldValue) -> Result<Self, Self::Error> {
+        match value {
+            FieldValue::Str(v) => Ok(v),
+            other => Err(other.conversion_error("Str")),
         }
     }
 }
 
-impl Into<i64> for FieldValue {
-    fn into(self) -> i64 {
-        match self {
-            FieldValue::I64(v) => v,
-            _ => panic!("Cannot convert FieldValue to i64"),
+impl<'a> TryFrom<&'a FieldValue> for &'a str {
+    type Error = ConversionError;
+
+    fn try_from(value: &'a FieldValue) -> Result<Self, Self::Error> {
+        value.as_str().ok_or_else(|| value.conversion_error("Str"))
+    }
+}
+
+impl TryFrom<FieldValue> for Vec<u8> {
+    type Error = ConversionError;
+
+    fn try_from(value: FieldValue) -> Result<Self, Self::Error> {
+        match value {
+            FieldValue::Bytes(v) => Ok(v),
+            other => Err(other.conversion_error("Bytes")),
         }
     }
 }
 
-impl Into<f64> for FieldValue {
-    fn into(self) -> f64 {
-        match self {
-            FieldValue::Float(v) => v,
-            _ => panic!("Cannot convert FieldValue to f64"),
+impl<'a> TryFrom<&'a FieldValue> for &'a [u8] {
+    type Error = ConversionError;
+
+    fn try_from(value: &'a FieldValue) -> Result<Self, Self::Error> {
+        value.as_bytes().ok_or_else(|| value.conversion_error("Bytes"))
+    }
+}
+
+impl TryFrom<FieldValue> for Vec<Record> {
+    type Error = ConversionError;
+
+    fn try_from(value: FieldValue) -> Result<Self, Self::Error> {
+        match value {
+            FieldValue::Array(v) => Ok(v),
+            other => Err(other.conversion_error("Array")),
         }
     }
 }
+
+impl<'a> TryFrom<&'a FieldValue> for &'a [Record] {
+    type Error = ConversionError;
+
+    fn try_from(value: &'a FieldValue) -> Result<Self, Self::Error> {
+        value.as_array().ok_or_else(|| value.conversion_error("Array"))
+    }
+}
@@ -0,0 +1,272 @@
+use crate::config::manager::ConfigManager;
+use crate::config::types::{BaseFieldDef, FieldType};
+use crate::message::{FieldValue, Message};
+use crate::typed::types::{Amount, Date, FixedBytes, FixedStr, NTime, Price, Quantity};
+use crate::util::{ConfigError, MessageError, MessageResult};
+
+/// 可以映射到消息字段的 Rust 类型
+pub trait MappedField: Sized {
+    /// 兼容的字段类型
+    const FIELD_TYPES: &'static [FieldType];
+    /// 要求的字段长度，仅定长类型需要
+    const LENGTH: Option<usize> = None;
+
+    /// 转换为字段值
+    fn to_field_value(&self) -> FieldValue;
+
+    /// 从字段值转换，类型不兼容或超出范围时返回 `None`
+    fn from_field_value(value: &FieldValue) -> Option<Self>;
+}
+
+/// 结构体成员与消息字段的映射关系，由 `#[derive(TdgwMessage)]` 生成
+#[derive(Debug, Clone, Copy)]
+pub struct FieldMapping {
+    /// 结构体成员名
+    pub member: &'static str,
+    /// 消息字段名
+    pub field: &'static str,
+    /// 成员的 Rust 类型，用于错误信息
+    pub rust_type: &'static str,
+    /// 兼容的字段类型
+    pub field_types: &'static [FieldType],
+    /// 要求的字段长度
+    pub length: Option<usize>,
+}
+
+/// 与配置中的消息定义相互转换的结构体，通常通过 `#[derive(TdgwMessage)]` 实现
+pub trait TdgwMessage: Sized {
+    /// 映射的消息类型
+    const MSG_TYPE: u32;
+
+    /// 全部成员映射
+    fn field_mappings() -> &'static [FieldMapping];
+
+    /// 转换为动态消息
+    fn to_message(&self, seq_num: u32) -> Message;
+
+    /// 从动态消息转换
+    fn from_message(message: &Message) -> MessageResult<Self>;
+
+    /// 按已加载的配置校验映射，建议在启动时调用
+    ///
+    /// 映射的字段必须存在于消息定义或其扩展中，且字段类型和长度与成员类型兼容
+    fn validate(config_manager: &ConfigManager) -> MessageResult<()> {
+        validate_mappings(config_manager, Self::MSG_TYPE, Self::field_mappings())
+    }
+}
+
+/// 校验成员映射，一次报告所有问题
+pub fn validate_mappings(config_manager: &ConfigManager, msg_type: u32, mappings: &[FieldMapping]) -> MessageResult<()> {
+    let message_def = config_manager.get_message_def(msg_type)
+        .ok_or(ConfigError::MessageNotFound(msg_type))?;
+
+    let mut problems = Vec::new();
+    for (index, mapping) in mappings.iter().enumerate() {
+        if mappings[..index].iter().any(|other| other.field == mapping.field) {
+            problems.push(format!("{}: field {} is mapped more than once", mapping.member, mapping.field));
+            continue;
+        }
+
+        let field_def = message_def.fields.iter()
+            .map(|def| &def.base)
+            .chain(message_def.extensions.iter().flat_map(|extension| extension.fields.iter()))
+            .find(|def| def.name == mapping.field);
+        match field_def {
+            None => problems.push(format!(
+                "{}: field {} is not defined in message {}",
+                mapping.member, mapping.field, message_def.name
+            )),
+            Some(def) => {
+                if let Some(problem) = check_compatible(mapping, def) {
+                    problems.push(problem);
+                }
+            },
+        }
+    }
+
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(MessageError::InvalidFieldMapping(problems))
+    }
+}
+
+fn check_compatible(mapping: &FieldMapping, def: &BaseFieldDef) -> Option<String> {
+    if !mapping.field_types.contains(&def.r#type) {
+        return Some(format!(
+            "{}: {} is not compatible with field {} of type {:?}",
+            mapping.member, mapping.rust_type, def.name, def.r#type
+        ));
+    }
+    match (mapping.length, def.length) {
+        (Some(expected), actual) if actual != Some(expected) => Some(format!(
+            "{}: {} requires length {}, field {} has length {:?}",
+            mapping.member, mapping.rust_type, expected, def.name, actual
+        )),
+        _ => None,
+    }
+}
+
+/// 读取映射字段，供派生代码使用
+pub fn mapped_field<T: MappedField>(message: &Message, field: &str) -> MessageResult<T> {
+    let value = message.get_field(field)
+        .ok_or_else(|| MessageError::MissingRequiredFields(vec![field.to_string()]))?;
+    T::from_field_value(value).ok_or_else(|| MessageError::InvalidFieldValue(format!(
+        "Field '{}' value {:?} cannot be converted to {}",
+        field, value, std::any::type_name::<T>()
+    )))
+}
+
+macro_rules! integer_mapping {
+    ($ty:ty, [$($field_type:ident),+]) => {
+        impl MappedField for $ty {
+            const FIELD_TYPES: &'static [FieldType] = &[$(FieldType::$field_type),+];
+
+            fn to_field_value(&self) -> FieldValue {
+                FieldValue::from(*self)
+            }
+
+            fn from_field_value(value: &FieldValue) -> Option<Self> {
+                value.as_integer().and_then(|integer| <$ty>::try_from(integer).ok())
+            }
+        }
+    };
+}
+
+integer_mapping!(u8, [U8]);
+integer_mapping!(u16, [U16]);
+integer_mapping!(u32, [U32, Date]);
+integer_mapping!(u64, [U64, NTime]);
+integer_mapping!(i8, [I8]);
+integer_mapping!(i16, [I16]);
+integer_mapping!(i32, [I32]);
+integer_mapping!(i64, [I64]);
+
+impl MappedField for f64 {
+    const FIELD_TYPES: &'static [FieldType] = &[FieldType::Price, FieldType::Quantity, FieldType::Amount];
+
+    fn to_field_value(&self) -> FieldValue {
+        FieldValue::Float(*self)
+    }
+
+    fn from_field_value(value: &FieldValue) -> Option<Self> {
+        value.as_f64()
+    }
+}
+
+impl MappedField for String {
+    const FIELD_TYPES: &'static [FieldType] = &[FieldType::Char];
+
+    fn to_field_value(&self) -> FieldValue {
+        FieldValue::Str(self.clone())
+    }
+
+    fn from_field_value(value: &FieldValue) -> Option<Self> {
+        value.as_str().map(str::to_string)
+    }
+}
+
+impl MappedField for Vec<u8> {
+    const FIELD_TYPES: &'static [FieldType] = &[FieldType::Bytes];
+
+    fn to_field_value(&self) -> FieldValue {
+        FieldValue::Bytes(self.clone())
+    }
+
+    fn from_field_value(value: &FieldValue) -> Option<Self> {
+        value.as_bytes().map(<[u8]>::to_vec)
+    }
+}
+
+/// 原样保存字段值，可映射任意类型的字段，例如数组
+impl MappedField for FieldValue {
+    const FIELD_TYPES: &'static [FieldType] = &[
+        FieldType::U8, FieldType::U16, FieldType::U32, FieldType::U64,
+        FieldType::I8, FieldType::I16, FieldType::I32, FieldType::I64,
+        FieldType::Char, FieldType::Price, FieldType::Quantity, FieldType::Amount,
+        FieldType::Date, FieldType::NTime, FieldType::Bytes, FieldType::Array,
+    ];
+
+    fn to_field_value(&self) -> FieldValue {
+        self.clone()
+    }
+
+    fn from_field_value(value: &FieldValue) -> Option<Self> {
+        Some(value.clone())
+    }
+}
+
+macro_rules! fixed_point_mapping {
+    ($ty:ident) => {
+        impl MappedField for $ty {
+            const FIELD_TYPES: &'static [FieldType] = &[FieldType::$ty];
+
+            fn to_field_value(&self) -> FieldValue {
+                FieldValue::Float(self.to_f64())
+            }
+
+            fn from_field_value(value: &FieldValue) -> Option<Self> {
+                // 解码得到的浮点数乘回倍数后可能略小于原整数，按四舍五入还原
+                value.as_f64().map($ty::from_f64_rounded)
+            }
+        }
+    };
+}
+
+fixed_point_mapping!(Price);
+fixed_point_mapping!(Quantity);
+fixed_point_mapping!(Amount);
+
+impl MappedField for Date {
+    const FIELD_TYPES: &'static [FieldType] = &[FieldType::Date];
+
+    fn to_field_value(&self) -> FieldValue {
+        FieldValue::U32(self.0)
+    }
+
+    fn from_field_value(value: &FieldValue) -> Option<Self> {
+        u32::from_field_value(value).map(Date)
+    }
+}
+
+impl MappedField for NTime {
+    const FIELD_TYPES: &'static [FieldType] = &[FieldType::NTime];
+
+    fn to_field_value(&self) -> FieldValue {
+        FieldValue::U64(self.0)
+    }
+
+    fn from_field_value(value: &FieldValue) -> Option<Self> {
+        u64::from_field_value(value).map(NTime)
+    }
+}
+
+impl<const N: usize> MappedField for FixedStr<N> {
+    const FIELD_TYPES: &'static [FieldType] = &[FieldType::Char];
+    const LENGTH: Option<usize> = Some(N);
+
+    fn to_field_value(&self) -> FieldValue {
+        FieldValue::Str(self.as_str().to_string())
+    }
+
+    fn from_field_value(value: &FieldValue) -> Option<Self> {
+        // 超长的字符串无法无损放入定长类型
+        value.as_str().filter(|s| s.len() <= N).map(FixedStr::new)
+    }
+}
+
+impl<const N: usize> MappedField for FixedBytes<N> {
+    const FIELD_TYPES: &'static [FieldType] = &[FieldType::Bytes];
+    const LENGTH: Option<usize> = Some(N);
+
+    fn to_field_value(&self) -> FieldValue {
+        FieldValue::Bytes(self.0.to_vec())
+    }
+
+    fn from_field_value(value: &FieldValue) -> Option<Self> {
+        let bytes = value.as_bytes().filter(|bytes| bytes.len() <= N)?;
+        let mut fixed = [0u8; N];
+        fixed[..bytes.len()].copy_from_slice(bytes);
+        Some(FixedBytes(fixed))
+    }
+}
@@ -1,5 +1,19 @@
+use std::fmt;
+
 use indexmap::IndexMap;
-use crate::message::FieldValue;
+use crate::message::{FieldValue, Record};
+use crate::util::{ConversionError, MessageError, MessageResult};
+
+macro_rules! typed_getter {
+    ($($method:ident => $ty:ty),+ $(,)?) => {
+        $(
+            #[doc = concat!("读取 `", stringify!($ty), "` 类型的字段")]
+            pub fn $method(&self, name: &str) -> MessageResult<$ty> {
+                self.get_as(name)
+            }
+        )+
+    };
+}
 
 /// 解析后的消息结构，使用 IndexMap 保持字段的插入顺序
 #[derive(Debug, Clone)]
@@ -10,6 +24,8 @@ pub struct Message {
     pub seq_num: u32,
     /// 消息字段，使用 IndexMap 保持字段的插入顺序
     pub fields: IndexMap<String, FieldValue>,
+    /// 以 [`crate::codec::ChecksumMode::Warn`] 解码且校验和不一致时为 `true`
+    pub checksum_mismatch: bool,
 }
 
 impl Message {
@@ -19,6 +35,7 @@ impl Message {
             msg_type,
             seq_num,
             fields: IndexMap::new(),
+            checksum_mismatch: false,
         }
     }
 
@@ -42,15 +59,55 @@ impl Message {
         self.fields.contains_key(name)
     }
 
-    pub fn to_string(&self) -> String {
-        let mut s = format!("Message {{ msg_type: {}, seq_num: {}, fields: {{", self.msg_type, self.seq_num);
+    /// 读取字段并转换为指定类型
+    ///
+    /// 字段不存在时返回 [`MessageError::FieldNotFound`]，
+    /// 类型不符时返回 [`MessageError::FieldConversionError`]
+    pub fn get_as<'a, T>(&'a self, name: &str) -> MessageResult<T>
+    where
+        T: TryFrom<&'a FieldValue, Error = ConversionError>,
+    {
+        let value = self.fields.get(name).ok_or_else(|| MessageError::FieldNotFound(name.to_string()))?;
+        T::try_from(value).map_err(|e| MessageError::FieldConversionError(name.to_string(), e))
+    }
+
+    typed_getter! {
+        get_u8 => u8,
+        get_u16 => u16,
+        get_u32 => u32,
+        get_u64 => u64,
+        get_i8 => i8,
+        get_i16 => i16,
+        get_i32 => i32,
+        get_i64 => i64,
+        get_f64 => f64,
+    }
+
+    /// 读取字符串字段
+    pub fn get_str(&self, name: &str) -> MessageResult<&str> {
+        self.get_as(name)
+    }
+
+    /// 读取字节字段
+    pub fn get_bytes(&self, name: &str) -> MessageResult<&[u8]> {
+        self.get_as(name)
+    }
+
+    /// 读取数组字段
+    pub fn get_array(&self, name: &str) -> MessageResult<&[Record]> {
+        self.get_as(name)
+    }
+}
+
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Message {{ msg_type: {}, seq_num: {}, fields: {{", self.msg_type, self.seq_num)?;
         for (i, (name, value)) in self.fields.iter().enumerate() {
-            s.push_str(&format!("{}: {:?}", name, value));
+            write!(f, "{}: {:?}", name, value)?;
             if i < self.fields.len() - 1 {
-                s.push_str(", ");
+                write!(f, ", ")?;
             }
         }
-        s.push_str("} }}");
-        s
+        write!(f, "}} }}")
     }
-}
\ No newline at end of file
+}
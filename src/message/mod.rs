@@ -1,8 +1,23 @@
 
 // 导出子模块
+mod builder;
+mod diff;
 mod field_value;
+mod mapping;
+#[allow(clippy::module_inception)]
 mod message;
+mod path;
+pub(crate) mod pretty;
+mod record;
+mod serialization;
 
 // 重新导出公共接口
+pub use builder::MessageBuilder;
+pub use diff::FieldDiff;
 pub use field_value::FieldValue;
+pub use mapping::{mapped_field, validate_mappings, FieldMapping, MappedField, TdgwMessage};
+pub use tdgw_derive::TdgwMessage;
 pub use message::Message;
+pub use pretty::{MessagePrinter, PrintStyle};
+pub use record::Record;
+pub use serialization::MessageSeed;
@@ -0,0 +1,202 @@
+use indexmap::IndexMap;
+
+use crate::message::{FieldValue, Message, Record};
+use crate::util::{ConversionError, MessageError, MessageResult};
+
+/// 字段路径中的一段，`end` 为该段在路径字符串中的结束位置，用于错误信息
+#[derive(Debug, Clone, Copy)]
+enum Segment<'p> {
+    Field { name: &'p str, end: usize },
+    Index { index: usize, end: usize },
+}
+
+impl Segment<'_> {
+    fn end(&self) -> usize {
+        match self {
+            Segment::Field { end, .. } | Segment::Index { end, .. } => *end,
+        }
+    }
+}
+
+fn path_error(path: &str, end: usize, reason: impl Into<String>) -> MessageError {
+    MessageError::FieldPathError(path.to_string(), path[..end].to_string(), reason.into())
+}
+
+/// 解析形如 `SyncResponses[1].EndReportIndex` 的字段路径
+fn parse_path(path: &str) -> MessageResult<Vec<Segment<'_>>> {
+    let mut segments = Vec::new();
+    let mut start = 0;
+    for part in path.split('.') {
+        let end = start + part.len();
+        let name_end = part.find('[').unwrap_or(part.len());
+        let name = &part[..name_end];
+        if name.is_empty() {
+            return Err(path_error(path, end, "empty field name"));
+        }
+        segments.push(Segment::Field { name, end: start + name_end });
+
+        let mut rest = &part[name_end..];
+        let mut offset = start + name_end;
+        while !rest.is_empty() {
+            let close = rest.find(']').filter(|_| rest.starts_with('['))
+                .ok_or_else(|| path_error(path, end, "expected '[index]'"))?;
+            let index = rest[1..close].parse::<usize>()
+                .map_err(|_| path_error(path, offset + close + 1, format!("invalid index '{}'", &rest[1..close])))?;
+            offset += close + 1;
+            segments.push(Segment::Index { index, end: offset });
+            rest = &rest[close + 1..];
+        }
+        start = end + 1;
+    }
+    Ok(segments)
+}
+
+/// 路径遍历过程中的当前位置
+enum Cursor<'a> {
+    Fields(&'a IndexMap<String, FieldValue>),
+    Record(&'a Record),
+    Value(&'a FieldValue),
+}
+
+enum CursorMut<'a> {
+    Fields(&'a mut IndexMap<String, FieldValue>),
+    Record(&'a mut Record),
+    Value(&'a mut FieldValue),
+}
+
+/// 当前值无法继续按下一段访问时的原因
+fn mismatch_reason(value: &FieldValue, segment: &Segment) -> String {
+    match (value, segment) {
+        (FieldValue::Array(_), Segment::Field { .. }) => "array requires an index".to_string(),
+        (_, Segment::Field { .. }) => format!("{:?} has no fields", value),
+        (_, Segment::Index { .. }) => format!("{:?} is not an array", value),
+    }
+}
+
+fn step<'a>(path: &str, cursor: Cursor<'a>, segment: &Segment) -> MessageResult<Cursor<'a>> {
+    match (cursor, *segment) {
+        (Cursor::Fields(fields), Segment::Field { name, end }) => {
+            fields.get(name).map(Cursor::Value).ok_or_else(|| path_error(path, end, "field not found"))
+        },
+        (Cursor::Record(record), Segment::Field { name, end }) => {
+            record.get(name).map(Cursor::Value).ok_or_else(|| path_error(path, end, "field not found"))
+        },
+        (Cursor::Value(FieldValue::Array(elements)), Segment::Index { index, end }) => {
+            elements.get(index).map(Cursor::Record).ok_or_else(|| {
+                path_error(path, end, format!("index {} out of bounds for array of length {}", index, elements.len()))
+            })
+        },
+        (Cursor::Value(value), segment) => Err(path_error(path, segment.end(), mismatch_reason(value, &segment))),
+        (_, segment) => Err(path_error(path, segment.end(), "array element is not an array")),
+    }
+}
+
+fn step_mut<'a>(path: &str, cursor: CursorMut<'a>, segment: &Segment) -> MessageResult<CursorMut<'a>> {
+    match (cursor, *segment) {
+        (CursorMut::Fields(fields), Segment::Field { name, end }) => {
+            fields.get_mut(name).map(CursorMut::Value).ok_or_else(|| path_error(path, end, "field not found"))
+        },
+        (CursorMut::Record(record), Segment::Field { name, end }) => {
+            record.get_mut(name).map(CursorMut::Value).ok_or_else(|| path_error(path, end, "field not found"))
+        },
+        (CursorMut::Value(FieldValue::Array(elements)), Segment::Index { index, end }) => {
+            let len = elements.len();
+            elements.get_mut(index).map(CursorMut::Record).ok_or_else(|| {
+                path_error(path, end, format!("index {} out of bounds for array of length {}", index, len))
+            })
+        },
+        (CursorMut::Value(value), segment) => Err(path_error(path, segment.end(), mismatch_reason(value, &segment))),
+        (_, segment) => Err(path_error(path, segment.end(), "array element is not an array")),
+    }
+}
+
+macro_rules! typed_path_getter {
+    ($($method:ident => $ty:ty),+ $(,)?) => {
+        $(
+            #[doc = concat!("按路径读取 `", stringify!($ty), "` 类型的字段")]
+            pub fn $method(&self, path: &str) -> MessageResult<$ty> {
+                self.get_path_as(path)
+            }
+        )+
+    };
+}
+
+impl Message {
+    /// 按路径读取字段值，例如 `BizID` 或 `SyncResponses[1].EndReportIndex`
+    ///
+    /// 出错时返回 [`MessageError::FieldPathError`]，其中包含出错的路径段
+    pub fn get_path(&self, path: &str) -> MessageResult<&FieldValue> {
+        let segments = parse_path(path)?;
+        let mut cursor = Cursor::Fields(&self.fields);
+        for segment in &segments {
+            cursor = step(path, cursor, segment)?;
+        }
+        match cursor {
+            Cursor::Value(value) => Ok(value),
+            _ => Err(path_error(path, path.len(), "path refers to an array element, expected a field")),
+        }
+    }
+
+    /// 按路径获取可修改的字段值
+    pub fn get_path_mut(&mut self, path: &str) -> MessageResult<&mut FieldValue> {
+        let segments = parse_path(path)?;
+        let mut cursor = CursorMut::Fields(&mut self.fields);
+        for segment in &segments {
+            cursor = step_mut(path, cursor, segment)?;
+        }
+        match cursor {
+            CursorMut::Value(value) => Ok(value),
+            _ => Err(path_error(path, path.len(), "path refers to an array element, expected a field")),
+        }
+    }
+
+    /// 按路径设置字段值，返回原来的值
+    ///
+    /// 路径最后一段必须是字段名；该字段不存在时添加到所在的消息或数组元素中，
+    /// 中间经过的字段和数组下标必须已经存在
+    pub fn set_path(&mut self, path: &str, value: impl Into<FieldValue>) -> MessageResult<Option<FieldValue>> {
+        let segments = parse_path(path)?;
+        let (last, parents) = segments.split_last().expect("path has at least one segment");
+        let Segment::Field { name, .. } = *last else {
+            return Err(path_error(path, path.len(), "path must end with a field name"));
+        };
+
+        let mut cursor = CursorMut::Fields(&mut self.fields);
+        for segment in parents {
+            cursor = step_mut(path, cursor, segment)?;
+        }
+        match cursor {
+            CursorMut::Fields(fields) => Ok(fields.insert(name.to_string(), value.into())),
+            CursorMut::Record(record) => Ok(record.insert(name, value)),
+            CursorMut::Value(parent) => {
+                let reason = mismatch_reason(parent, last);
+                Err(path_error(path, last.end(), reason))
+            },
+        }
+    }
+
+    /// 按路径读取字段并转换为指定类型，类型不符时错误指向整个路径
+    pub fn get_path_as<'a, T>(&'a self, path: &str) -> MessageResult<T>
+    where
+        T: TryFrom<&'a FieldValue, Error = ConversionError>,
+    {
+        T::try_from(self.get_path(path)?).map_err(|e| path_error(path, path.len(), e.to_string()))
+    }
+
+    typed_path_getter! {
+        get_u8_path => u8,
+        get_u16_path => u16,
+        get_u32_path => u32,
+        get_u64_path => u64,
+        get_i8_path => i8,
+        get_i16_path => i16,
+        get_i32_path => i32,
+        get_i64_path => i64,
+        get_f64_path => f64,
+    }
+
+    /// 按路径读取字符串字段
+    pub fn get_str_path(&self, path: &str) -> MessageResult<&str> {
+        self.get_path_as(path)
+    }
+}
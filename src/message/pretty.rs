@@ -0,0 +1,223 @@
+use std::fmt::{self, Write};
+
+use crate::codec::types::{validate_date_format, validate_ntime_format};
+use crate::config::types::{BaseFieldDef, FieldDef, FieldType, MessageDef};
+use crate::message::{FieldValue, Message, Record};
+
+/// 输出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PrintStyle {
+    /// 每个字段一行并附带字段描述，数组元素逐个缩进
+    #[default]
+    MultiLine,
+    /// 单行 `Name=Value|` 格式，数组先输出个数字段再依次输出元素字段，便于写入日志
+    ///
+    /// 字段名和值中的 `\`、`|`、`=` 前加 `\` 转义，换行和回车输出为 `\n`、`\r`，
+    /// 因此按未转义的 `|` 和 `=` 切分即可还原每个字段
+    SingleLine,
+}
+
+/// 按消息定义格式化消息
+///
+/// 价格、数量、金额按精度输出小数，日期输出为 `YYYY-MM-DD`，时间输出为 `HH:MM:SS.sssnnnn`，
+/// 字段描述中带有 `代码=含义` 说明的字段在值后面附上含义：
+///
+/// ```ignore
+/// let def = config_manager.get_message_def(message.msg_type).unwrap();
+/// println!("{}", MessagePrinter::new(def).format(&message));
+/// // MsgType=58|MsgName=NewOrderSingle|SeqNum=1|ClOrdID=ORD001|Side=1|...
+/// println!("{}", MessagePrinter::new(def).single_line().format(&message));
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct MessagePrinter<'a> {
+    def: &'a MessageDef,
+    style: PrintStyle,
+}
+
+impl<'a> MessagePrinter<'a> {
+    /// 创建多行格式的打印器
+    pub fn new(def: &'a MessageDef) -> Self {
+        Self { def, style: PrintStyle::MultiLine }
+    }
+
+    /// 使用单行格式
+    pub fn single_line(self) -> Self {
+        self.with_style(PrintStyle::SingleLine)
+    }
+
+    /// 设置输出格式
+    pub fn with_style(mut self, style: PrintStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// 格式化消息
+    pub fn format(&self, message: &Message) -> String {
+        let mut out = String::new();
+        self.write(message, &mut out).expect("writing to a String cannot fail");
+        out
+    }
+
+    /// 将格式化结果写入 `out`
+    ///
+    /// 字段按消息定义的顺序输出，其后是当前 BizID 对应的扩展字段，最后是消息定义之外的字段
+    pub fn write<W: Write>(&self, message: &Message, out: &mut W) -> fmt::Result {
+        let extension = message.get_field("BizID")
+            .and_then(FieldValue::as_u32)
+            .and_then(|biz_id| self.def.extensions.iter().find(|extension| extension.biz_id == biz_id));
+        let fields: Vec<(&BaseFieldDef, Option<&FieldDef>)> = self.def.fields.iter()
+            .map(|def| (&def.base, Some(def)))
+            .chain(extension.into_iter().flat_map(|extension| extension.fields.iter().map(|base| (base, None))))
+            .collect();
+        let unknown: Vec<_> = message.fields.iter()
+            .filter(|(name, _)| !fields.iter().any(|(def, _)| def.name == **name))
+            .collect();
+
+        match self.style {
+            PrintStyle::MultiLine => {
+                writeln!(out, "{} ({}) seq={}", self.def.name, message.msg_type, message.seq_num)?;
+                let width = fields.iter()
+                    .filter(|(def, _)| message.get_field(&def.name).is_some())
+                    .map(|(def, _)| def.name.len())
+                    .chain(unknown.iter().map(|(name, _)| name.len()))
+                    .max()
+                    .unwrap_or_default();
+                for (def, field_def) in &fields {
+                    if let Some(value) = message.get_field(&def.name) {
+                        write_line(out, 1, width, def, field_def.and_then(|def| def.r#struct.as_ref().map(|s| &s.fields[..])), value)?;
+                    }
+                }
+                for (name, value) in unknown {
+                    writeln!(out, "  {:width$} = {}", name, value, width = width)?;
+                }
+            },
+            PrintStyle::SingleLine => {
+                write!(out, "MsgType={}|MsgName={}|SeqNum={}|", message.msg_type, self.def.name, message.seq_num)?;
+                for (def, field_def) in &fields {
+                    if let Some(value) = message.get_field(&def.name) {
+                        write_pair(out, def, *field_def, value)?;
+                    }
+                }
+                for (name, value) in unknown {
+                    write_escaped(out, name, &value.to_string())?;
+                }
+            },
+        }
+        Ok(())
+    }
+}
+
+/// 多行格式：输出一个字段，数组元素按结构定义中的字段名逐个缩进输出
+fn write_line<W: Write>(
+    out: &mut W,
+    depth: usize,
+    width: usize,
+    def: &BaseFieldDef,
+    struct_fields: Option<&[BaseFieldDef]>,
+    value: &FieldValue,
+) -> fmt::Result {
+    let indent = "  ".repeat(depth);
+    match (struct_fields, value) {
+        (Some(struct_fields), FieldValue::Array(elements)) => {
+            write!(out, "{}{:width$} = {} item(s)", indent, def.name, elements.len(), width = width)?;
+            write_desc(out, def)?;
+            let element_width = struct_fields.iter().map(|field| field.name.len()).max().unwrap_or_default();
+            for (index, element) in elements.iter().enumerate() {
+                writeln!(out, "{}  [{}]", indent, index)?;
+                write_record(out, depth + 2, element_width, struct_fields, element)?;
+            }
+            Ok(())
+        },
+        _ => {
+            write!(out, "{}{:width$} = {}", indent, def.name, format_value(def, value), width = width)?;
+            if let Some(label) = symbol_of(def, value) {
+                write!(out, " ({})", label)?;
+            }
+            write_desc(out, def)
+        },
+    }
+}
+
+fn write_record<W: Write>(out: &mut W, depth: usize, width: usize, fields: &[BaseFieldDef], element: &Record) -> fmt::Result {
+    for field in fields {
+        if let Some(value) = element.get(&field.name) {
+            write_line(out, depth, width, field, None, value)?;
+        }
+    }
+    Ok(())
+}
+
+fn write_desc<W: Write>(out: &mut W, def: &BaseFieldDef) -> fmt::Result {
+    match &def.desc {
+        Some(desc) => writeln!(out, "  # {}", desc),
+        None => writeln!(out),
+    }
+}
+
+/// 单行格式：输出一个或一组 `Name=Value|`，数组先输出个数字段
+fn write_pair<W: Write>(out: &mut W, def: &BaseFieldDef, field_def: Option<&FieldDef>, value: &FieldValue) -> fmt::Result {
+    let struct_def = field_def.and_then(|def| def.r#struct.as_ref());
+    match (struct_def, value) {
+        (Some(struct_def), FieldValue::Array(elements)) => {
+            let count_name = field_def.and_then(|def| def.length_field.as_ref()).map_or(def.name.as_str(), |field| field.name.as_str());
+            write_escaped(out, count_name, &elements.len().to_string())?;
+            for element in elements {
+                for field in &struct_def.fields {
+                    if let Some(value) = element.get(&field.name) {
+                        write_escaped(out, &field.name, &format_value(field, value))?;
+                    }
+                }
+            }
+            Ok(())
+        },
+        _ => write_escaped(out, &def.name, &format_value(def, value)),
+    }
+}
+
+/// 单行格式：输出转义后的 `Name=Value|`
+fn write_escaped<W: Write>(out: &mut W, name: &str, value: &str) -> fmt::Result {
+    write_escaped_str(out, name)?;
+    out.write_char('=')?;
+    write_escaped_str(out, value)?;
+    out.write_char('|')
+}
+
+fn write_escaped_str<W: Write>(out: &mut W, text: &str) -> fmt::Result {
+    for c in text.chars() {
+        match c {
+            '\\' | '|' | '=' => {
+                out.write_char('\\')?;
+                out.write_char(c)?;
+            },
+            '\n' => out.write_str("\\n")?,
+            '\r' => out.write_str("\\r")?,
+            _ => out.write_char(c)?,
+        }
+    }
+    Ok(())
+}
+
+/// 按字段类型格式化值，值与字段类型不符（例如宽松解码保留的原始值）时按原样输出
+pub(crate) fn format_value(def: &BaseFieldDef, value: &FieldValue) -> String {
+    match (&def.r#type, value) {
+        (FieldType::Price | FieldType::Amount, FieldValue::Float(v)) => format!("{:.5}", v),
+        (FieldType::Quantity, FieldValue::Float(v)) => format!("{:.3}", v),
+        (FieldType::Date, FieldValue::U32(v)) if validate_date_format(*v) => {
+            format!("{:04}-{:02}-{:02}", v / 10000, v / 100 % 100, v % 100)
+        },
+        (FieldType::NTime, FieldValue::U64(v)) if validate_ntime_format(*v) => {
+            format!("{:02}:{:02}:{:02}.{:07}", v / 100_000_000_000, v / 1_000_000_000 % 100, v / 10_000_000 % 100, v % 10_000_000)
+        },
+        _ => value.to_string(),
+    }
+}
+
+/// 字段描述中与值对应的含义
+pub(crate) fn symbol_of<'d>(def: &'d BaseFieldDef, value: &FieldValue) -> Option<&'d str> {
+    let code = match value {
+        FieldValue::Str(s) => s.trim().to_string(),
+        FieldValue::Bytes(_) | FieldValue::Array(_) | FieldValue::Float(_) => return None,
+        other => other.to_string(),
+    };
+    def.symbol(&code)
+}
@@ -0,0 +1,141 @@
+use std::fmt;
+use std::ops::Index;
+
+use indexmap::IndexMap;
+use crate::config::types::StructDef;
+use crate::message::FieldValue;
+use crate::util::{MessageError, MessageResult};
+
+/// 数组元素，按结构定义中的字段名保存字段值并保持插入顺序
+///
+/// ```ignore
+/// let element = Record::new()
+///     .with("Pbu", "PBU001")
+///     .with("SetID", 1u32)
+///     .with("EndReportIndex", 100u64);
+/// assert_eq!(element["SetID"], FieldValue::U32(1));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Record {
+    fields: IndexMap<String, FieldValue>,
+}
+
+impl Record {
+    /// 创建一个空元素
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 创建一个预留容量的空元素
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self { fields: IndexMap::with_capacity(capacity) }
+    }
+
+    /// 设置字段值并返回自身，便于链式构造
+    pub fn with(mut self, name: impl Into<String>, value: impl Into<FieldValue>) -> Self {
+        self.insert(name, value);
+        self
+    }
+
+    /// 设置字段值，返回该字段原来的值
+    pub fn insert(&mut self, name: impl Into<String>, value: impl Into<FieldValue>) -> Option<FieldValue> {
+        self.fields.insert(name.into(), value.into())
+    }
+
+    /// 按字段名获取字段值
+    pub fn get(&self, name: &str) -> Option<&FieldValue> {
+        self.fields.get(name)
+    }
+
+    /// 按字段名获取可修改的字段值
+    pub fn get_mut(&mut self, name: &str) -> Option<&mut FieldValue> {
+        self.fields.get_mut(name)
+    }
+
+    /// 检查是否包含指定字段
+    pub fn contains(&self, name: &str) -> bool {
+        self.fields.contains_key(name)
+    }
+
+    /// 字段数量
+    pub fn len(&self) -> usize {
+        self.fields.len()
+    }
+
+    /// 是否没有任何字段
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+
+    /// 按插入顺序遍历字段名和字段值
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &FieldValue)> {
+        self.fields.iter().map(|(name, value)| (name.as_str(), value))
+    }
+
+    /// 按插入顺序遍历字段名
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.fields.keys().map(String::as_str)
+    }
+
+    /// 按结构定义检查字段名：不允许结构中未定义的字段，也不允许缺少字段
+    ///
+    /// `path` 为该元素在消息中的位置，例如 `SyncRequests[0]`，用于错误信息
+    pub(crate) fn check_names(&self, path: &str, struct_def: &StructDef) -> MessageResult<()> {
+        if let Some(name) = self.names().find(|name| !struct_def.fields.iter().any(|def| def.name == *name)) {
+            return Err(MessageError::UnknownField(format!("{}.{}", path, name)));
+        }
+
+        let missing: Vec<String> = struct_def.fields.iter()
+            .filter(|def| !self.contains(&def.name))
+            .map(|def| format!("{}.{}", path, def.name))
+            .collect();
+        if !missing.is_empty() {
+            return Err(MessageError::MissingRequiredFields(missing));
+        }
+
+        Ok(())
+    }
+}
+
+impl Index<&str> for Record {
+    type Output = FieldValue;
+
+    /// 按字段名取值，字段不存在时 panic
+    fn index(&self, name: &str) -> &FieldValue {
+        self.get(name).unwrap_or_else(|| panic!("Record has no field '{}'", name))
+    }
+}
+
+impl<K: Into<String>, V: Into<FieldValue>> FromIterator<(K, V)> for Record {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        Self { fields: iter.into_iter().map(|(name, value)| (name.into(), value.into())).collect() }
+    }
+}
+
+impl<K: Into<String>, V: Into<FieldValue>, const N: usize> From<[(K, V); N]> for Record {
+    fn from(fields: [(K, V); N]) -> Self {
+        fields.into_iter().collect()
+    }
+}
+
+impl IntoIterator for Record {
+    type Item = (String, FieldValue);
+    type IntoIter = indexmap::map::IntoIter<String, FieldValue>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.fields.into_iter()
+    }
+}
+
+impl fmt::Display for Record {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{{")?;
+        for (i, (name, value)) in self.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}: {}", name, value)?;
+        }
+        write!(f, "}}")
+    }
+}
@@ -0,0 +1,344 @@
+use std::fmt;
+
+use serde::de::{self, DeserializeSeed, MapAccess, SeqAccess, Visitor};
+use serde::ser::SerializeStruct;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::codec::coerce_field_value;
+use crate::config::manager::ConfigManager;
+use crate::config::types::{BaseFieldDef, FieldDef, FieldType, StructDef};
+use crate::message::{FieldValue, Message, Record};
+use crate::util::{MessageError, MessageResult};
+
+const MESSAGE_FIELDS: &[&str] = &["msg_type", "seq_num", "fields"];
+
+/// 字节在可读格式（如 JSON）中输出为十六进制字符串，其他格式输出为原始字节
+fn serialize_bytes<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+    if serializer.is_human_readable() {
+        serializer.serialize_str(&hex::encode(bytes))
+    } else {
+        serializer.serialize_bytes(bytes)
+    }
+}
+
+/// 数组元素输出为带字段名的对象
+impl Serialize for FieldValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            FieldValue::U8(v) => serializer.serialize_u8(*v),
+            FieldValue::U16(v) => serializer.serialize_u16(*v),
+            FieldValue::U32(v) => serializer.serialize_u32(*v),
+            FieldValue::U64(v) => serializer.serialize_u64(*v),
+            FieldValue::I8(v) => serializer.serialize_i8(*v),
+            FieldValue::I16(v) => serializer.serialize_i16(*v),
+            FieldValue::I32(v) => serializer.serialize_i32(*v),
+            FieldValue::I64(v) => serializer.serialize_i64(*v),
+            FieldValue::Float(v) => serializer.serialize_f64(*v),
+            FieldValue::Str(v) => serializer.serialize_str(v),
+            FieldValue::Bytes(v) => serialize_bytes(v, serializer),
+            FieldValue::Array(elements) => elements.serialize(serializer),
+        }
+    }
+}
+
+/// 按插入顺序输出字段
+impl Serialize for Record {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(self.iter())
+    }
+}
+
+/// 按插入顺序输出消息字段
+struct FieldsRef<'a>(&'a Message);
+
+impl Serialize for FieldsRef<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(&self.0.fields)
+    }
+}
+
+/// 输出 `msg_type`、`seq_num` 和按插入顺序排列的 `fields`
+impl Serialize for Message {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("Message", MESSAGE_FIELDS.len())?;
+        state.serialize_field("msg_type", &self.msg_type)?;
+        state.serialize_field("seq_num", &self.seq_num)?;
+        state.serialize_field("fields", &FieldsRef(self))?;
+        state.end()
+    }
+}
+
+/// 反序列化时暂存的原始值，待确定消息类型后再按字段定义转换
+#[derive(Debug)]
+enum RawValue {
+    Bool,
+    Int(i128),
+    Float(f64),
+    Str(String),
+    Bytes(Vec<u8>),
+    Seq(Vec<RawValue>),
+    Map(Vec<(String, RawValue)>),
+    Unit,
+}
+
+impl<'de> Deserialize<'de> for RawValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct RawVisitor;
+
+        impl<'de> Visitor<'de> for RawVisitor {
+            type Value = RawValue;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a field value")
+            }
+
+            fn visit_bool<E>(self, _v: bool) -> Result<RawValue, E> {
+                // 协议中没有布尔类型，仅用于报告类型不匹配
+                Ok(RawValue::Bool)
+            }
+
+            fn visit_i64<E>(self, v: i64) -> Result<RawValue, E> {
+                Ok(RawValue::Int(v as i128))
+            }
+
+            fn visit_i128<E>(self, v: i128) -> Result<RawValue, E> {
+                Ok(RawValue::Int(v))
+            }
+
+            fn visit_u64<E>(self, v: u64) -> Result<RawValue, E> {
+                Ok(RawValue::Int(v as i128))
+            }
+
+            fn visit_u128<E: de::Error>(self, v: u128) -> Result<RawValue, E> {
+                i128::try_from(v).map(RawValue::Int).map_err(|_| E::custom(format!("integer {} out of range", v)))
+            }
+
+            fn visit_f64<E>(self, v: f64) -> Result<RawValue, E> {
+                Ok(RawValue::Float(v))
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<RawValue, E> {
+                Ok(RawValue::Str(v.to_string()))
+            }
+
+            fn visit_string<E>(self, v: String) -> Result<RawValue, E> {
+                Ok(RawValue::Str(v))
+            }
+
+            fn visit_bytes<E>(self, v: &[u8]) -> Result<RawValue, E> {
+                Ok(RawValue::Bytes(v.to_vec()))
+            }
+
+            fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<RawValue, E> {
+                Ok(RawValue::Bytes(v))
+            }
+
+            fn visit_unit<E>(self) -> Result<RawValue, E> {
+                Ok(RawValue::Unit)
+            }
+
+            fn visit_none<E>(self) -> Result<RawValue, E> {
+                Ok(RawValue::Unit)
+            }
+
+            fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<RawValue, D::Error> {
+                RawValue::deserialize(deserializer)
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<RawValue, A::Error> {
+                let mut values = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+                while let Some(value) = seq.next_element()? {
+                    values.push(value);
+                }
+                Ok(RawValue::Seq(values))
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<RawValue, A::Error> {
+                let mut entries = Vec::with_capacity(map.size_hint().unwrap_or(0));
+                while let Some(entry) = map.next_entry()? {
+                    entries.push(entry);
+                }
+                Ok(RawValue::Map(entries))
+            }
+        }
+
+        deserializer.deserialize_any(RawVisitor)
+    }
+}
+
+/// 按消息定义反序列化 [`Message`]，数值字段转换为字段定义要求的类型
+///
+/// ```ignore
+/// let mut deserializer = serde_json::Deserializer::from_str(json);
+/// let message = MessageSeed::new(&config_manager).deserialize(&mut deserializer)?;
+/// ```
+pub struct MessageSeed<'a> {
+    config_manager: &'a ConfigManager,
+}
+
+impl<'a> MessageSeed<'a> {
+    /// 创建按指定配置反序列化的种子
+    pub fn new(config_manager: &'a ConfigManager) -> Self {
+        Self { config_manager }
+    }
+}
+
+impl<'de> DeserializeSeed<'de> for MessageSeed<'_> {
+    type Value = Message;
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Message, D::Error> {
+        deserializer.deserialize_struct("Message", MESSAGE_FIELDS, self)
+    }
+}
+
+impl<'de> Visitor<'de> for MessageSeed<'_> {
+    type Value = Message;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a message with msg_type, seq_num and fields")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Message, A::Error> {
+        let msg_type = seq.next_element()?.ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let seq_num = seq.next_element()?.ok_or_else(|| de::Error::invalid_length(1, &self))?;
+        let fields = seq.next_element()?.ok_or_else(|| de::Error::invalid_length(2, &self))?;
+        build_message(self.config_manager, msg_type, seq_num, fields).map_err(de::Error::custom)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Message, A::Error> {
+        let mut msg_type = None;
+        let mut seq_num = None;
+        let mut fields = None;
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "msg_type" => msg_type = Some(map.next_value()?),
+                "seq_num" => seq_num = Some(map.next_value()?),
+                // 字段可能出现在 msg_type 之前，先暂存原始值
+                "fields" => fields = Some(map.next_value()?),
+                other => return Err(de::Error::unknown_field(other, MESSAGE_FIELDS)),
+            }
+        }
+        let msg_type = msg_type.ok_or_else(|| de::Error::missing_field("msg_type"))?;
+        let seq_num = seq_num.ok_or_else(|| de::Error::missing_field("seq_num"))?;
+        let fields = fields.unwrap_or(RawValue::Map(Vec::new()));
+        build_message(self.config_manager, msg_type, seq_num, fields).map_err(de::Error::custom)
+    }
+}
+
+fn build_message(config_manager: &ConfigManager, msg_type: u32, seq_num: u32, fields: RawValue) -> MessageResult<Message> {
+    let message_def = config_manager.get_message_def(msg_type)
+        .ok_or(MessageError::UnknownMessageType(msg_type))?;
+    let RawValue::Map(fields) = fields else {
+        return Err(MessageError::InvalidFieldValue("fields must be a map of field name to value".to_string()));
+    };
+
+    let mut message = Message::new(msg_type, seq_num);
+    for (name, raw) in fields {
+        let value = if let Some(field_def) = message_def.fields.iter().find(|def| def.base.name == name) {
+            field_value(&field_def.base, Some(field_def), &name, raw)?
+        } else if let Some(base_field_def) = message_def.extensions.iter()
+            .flat_map(|extension| extension.fields.iter())
+            .find(|def| def.name == name)
+        {
+            field_value(base_field_def, None, &name, raw)?
+        } else {
+            return Err(MessageError::UnknownField(format!("{} (message {})", name, message_def.name)));
+        };
+        message.add_field(name, value);
+    }
+    Ok(message)
+}
+
+/// 按字段定义转换原始值，`path` 用于错误信息
+fn field_value(base_field_def: &BaseFieldDef, field_def: Option<&FieldDef>, path: &str, raw: RawValue) -> MessageResult<FieldValue> {
+    let mismatch = |raw: &RawValue| MessageError::InvalidFieldValue(format!(
+        "Field '{}' type mismatch: expected {:?}, got {:?}", path, base_field_def.r#type, raw
+    ));
+
+    match base_field_def.r#type {
+        FieldType::Array => {
+            let struct_def = field_def.and_then(|def| def.r#struct.as_ref()).ok_or_else(|| {
+                MessageError::ArrayElementDecodeError(format!("Array field {} missing struct definition", path))
+            })?;
+            let RawValue::Seq(elements) = raw else {
+                return Err(mismatch(&raw));
+            };
+            let elements = elements.into_iter()
+                .enumerate()
+                .map(|(index, element)| array_element(struct_def, &format!("{}[{}]", path, index), element))
+                .collect::<MessageResult<Vec<_>>>()?;
+            Ok(FieldValue::Array(elements))
+        },
+        FieldType::Bytes => match raw {
+            RawValue::Bytes(bytes) => Ok(FieldValue::Bytes(bytes)),
+            RawValue::Str(s) => hex::decode(s.trim()).map(FieldValue::Bytes).map_err(|e| {
+                MessageError::InvalidFieldValue(format!("Field '{}' is not valid hex: {}", path, e))
+            }),
+            RawValue::Seq(values) => values.into_iter()
+                .map(|value| match value {
+                    RawValue::Int(v) => u8::try_from(v).ok(),
+                    _ => None,
+                })
+                .collect::<Option<Vec<u8>>>()
+                .map(FieldValue::Bytes)
+                .ok_or_else(|| MessageError::InvalidFieldValue(format!("Field '{}' is not a list of bytes", path))),
+            other => Err(mismatch(&other)),
+        },
+        _ => {
+            let value = match raw {
+                RawValue::Int(v) => match (i64::try_from(v), u64::try_from(v)) {
+                    (Ok(v), _) => FieldValue::I64(v),
+                    (_, Ok(v)) => FieldValue::U64(v),
+                    _ => return Err(MessageError::ValueExceedsRange(format!("Field '{}' value {} out of range", path, v))),
+                },
+                RawValue::Float(v) => FieldValue::Float(v),
+                RawValue::Str(v) => FieldValue::Str(v),
+                other => return Err(mismatch(&other)),
+            };
+            Ok(coerce_field_value(base_field_def, &value)?.into_owned())
+        },
+    }
+}
+
+/// 数组元素可以是带字段名的对象，也可以是按位置排列的列表
+fn array_element(struct_def: &StructDef, path: &str, raw: RawValue) -> MessageResult<Record> {
+    match raw {
+        RawValue::Map(mut entries) => {
+            if let Some((name, _)) = entries.iter().find(|(name, _)| !struct_def.fields.iter().any(|def| &def.name == name)) {
+                return Err(MessageError::UnknownField(format!("{}.{}", path, name)));
+            }
+            let mut missing = Vec::new();
+            let mut element = Record::with_capacity(struct_def.fields.len());
+            for def in &struct_def.fields {
+                match entries.iter().position(|(name, _)| name == &def.name) {
+                    Some(position) => {
+                        let (name, raw) = entries.swap_remove(position);
+                        let value = field_value(def, None, &format!("{}.{}", path, name), raw)?;
+                        element.insert(name, value);
+                    },
+                    None => missing.push(format!("{}.{}", path, def.name)),
+                }
+            }
+            if !missing.is_empty() {
+                return Err(MessageError::MissingRequiredFields(missing));
+            }
+            Ok(element)
+        },
+        RawValue::Seq(values) => {
+            if values.len() != struct_def.fields.len() {
+                return Err(MessageError::InvalidFieldValue(format!(
+                    "Field '{}' has {} values, expected {}", path, values.len(), struct_def.fields.len()
+                )));
+            }
+            struct_def.fields.iter()
+                .zip(values)
+                .map(|(def, raw)| {
+                    field_value(def, None, &format!("{}.{}", path, def.name), raw).map(|value| (def.name.clone(), value))
+                })
+                .collect()
+        },
+        other => Err(MessageError::InvalidFieldValue(format!(
+            "Field '{}' expects an object or list, got {:?}", path, other
+        ))),
+    }
+}
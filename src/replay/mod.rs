@@ -0,0 +1,514 @@
+//! 会话回放：把 WireLog 或抓包中录制的一方发出的帧按原始节奏重新发给被测端，并与录制的应答逐字段比较
+
+use std::fmt;
+use std::io::{Read, Seek};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::sync::watch;
+use tokio::time::Instant;
+
+use crate::capture::{Connection, Direction};
+use crate::codec::types::split_frame;
+use crate::codec::{Frames, MessageDecoder, MessageEncoder, MessageHeader};
+use crate::config::manager::ConfigManager;
+use crate::message::{FieldDiff, FieldValue, Message};
+use crate::util::{MessageError, MessageResult, ReplayError, ReplayResult, WireLogResult};
+use crate::wirelog::{WireLogReader, WireRecord};
+
+/// 回放时重写的时间字段
+const TRANSACT_TIME: &str = "TransactTime";
+/// 北京时间相对 UTC 的偏移
+const UTC_OFFSET: u64 = 8 * 3600;
+
+/// 录制的会话，记录按接收顺序排列
+#[derive(Debug, Clone, Default)]
+pub struct Recording {
+    pub records: Vec<WireRecord>,
+}
+
+impl Recording {
+    pub fn new(records: Vec<WireRecord>) -> Self {
+        Self { records }
+    }
+
+    /// 读取日志中的全部记录，可以只取某个会话
+    pub fn from_wirelog<R: Read + Seek>(reader: WireLogReader<R>, session_id: Option<&str>) -> WireLogResult<Self> {
+        let mut records = Vec::new();
+        for record in reader {
+            let record = record?;
+            if session_id.is_none_or(|session_id| record.session_id == session_id) {
+                records.push(record);
+            }
+        }
+        Ok(Self { records })
+    }
+
+    /// 抓包中一个连接上的全部帧，会话标识为客户端地址
+    pub fn from_connection(connection: &Connection) -> Self {
+        let session_id = connection.client.to_string();
+        let records = connection.messages.iter()
+            .map(|captured| WireRecord::new(captured.timestamp, captured.direction, session_id.as_str(), captured.frame.clone()))
+            .collect();
+        Self { records }
+    }
+
+    /// 某个方向上的记录
+    pub fn frames(&self, direction: Direction) -> impl Iterator<Item = &WireRecord> {
+        self.records.iter().filter(move |record| record.direction == direction)
+    }
+}
+
+/// 回放节奏
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Pace {
+    /// 保持录制时的间隔
+    Original,
+    /// 按录制间隔的 1/N 发送，例如 `Speed(2.0)` 为两倍速
+    Speed(f64),
+    /// 不等待，连续发送
+    AsFastAsPossible,
+}
+
+/// 应答与录制不一致的一处
+#[derive(Debug)]
+pub enum Mismatch {
+    /// 录制中有而回放时没有收到的应答
+    Missing(Message),
+    /// 回放时多收到的应答
+    Unexpected(Message),
+    /// 消息类型不同
+    MsgType { expected: Message, actual: Message },
+    /// 同一类型的应答中取值不同的字段
+    Fields { msg_type: u32, diffs: Vec<FieldDiff> },
+    /// 无法解码的帧，`recorded` 表示帧来自录制
+    Undecodable { recorded: bool, frame: Vec<u8>, error: MessageError },
+}
+
+/// 一处应答差异，下标不计忽略的消息类型
+#[derive(Debug)]
+pub struct ResponseMismatch {
+    /// 录制中的应答下标，多收到的应答为 `None`
+    pub expected: Option<usize>,
+    /// 收到的应答下标，缺少的应答为 `None`
+    pub actual: Option<usize>,
+    pub kind: Mismatch,
+}
+
+impl fmt::Display for ResponseMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.expected, self.actual) {
+            (Some(index), _) => write!(f, "response #{}: ", index)?,
+            (None, Some(index)) => write!(f, "received #{}: ", index)?,
+            (None, None) => {},
+        }
+        match &self.kind {
+            Mismatch::Missing(message) => write!(f, "missing {} (seq {})", message.msg_type, message.seq_num),
+            Mismatch::Unexpected(message) => write!(f, "unexpected {} (seq {})", message.msg_type, message.seq_num),
+            Mismatch::MsgType { expected, actual } => write!(f, "expected message {}, got {}", expected.msg_type, actual.msg_type),
+            Mismatch::Fields { msg_type, diffs } => {
+                write!(f, "message {}", msg_type)?;
+                for diff in diffs {
+                    write!(f, "\n    {}", diff)?;
+                }
+                Ok(())
+            },
+            Mismatch::Undecodable { recorded, frame, error } => {
+                let side = if *recorded { "recorded" } else { "received" };
+                write!(f, "{} frame {} cannot be decoded: {}", side, hex::encode(frame), error)
+            },
+        }
+    }
+}
+
+/// 回放结果
+#[derive(Debug, Default)]
+pub struct ReplayReport {
+    /// 发出的帧数
+    pub sent: usize,
+    /// 收到的完整帧，按到达顺序排列，时间自 UNIX 纪元起
+    pub received: Vec<(Duration, Vec<u8>)>,
+    pub mismatches: Vec<ResponseMismatch>,
+}
+
+impl ReplayReport {
+    /// 应答是否与录制一致
+    pub fn is_match(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+}
+
+/// 会话回放器
+///
+/// 默认扮演客户端：按录制的间隔发出券商端发往网关的帧，同时接收被测端的应答，发送完毕且
+/// `response_timeout` 内没有新的应答后结束，再把收到的应答与录制中网关发出的帧对齐后逐个比较。
+/// 序列号和 `TransactTime` 可以在发送时重写
+///
+/// ```ignore
+/// let recording = Recording::from_wirelog(WireLogReader::open("logs/tdgw-20240105.wlog")?, Some("S1"))?;
+/// let report = Replayer::new(&config_manager)
+///     .with_pace(Pace::Speed(10.0))
+///     .rewrite_seq_num(1)
+///     .rewrite_transact_time()
+///     .ignore_field("TransactTime")
+///     .replay(&recording, TcpStream::connect(endpoint).await?)
+///     .await?;
+/// for mismatch in &report.mismatches {
+///     println!("{}", mismatch);
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Replayer<'a> {
+    config_manager: &'a ConfigManager,
+    pace: Pace,
+    /// 回放的方向，另一个方向为期望的应答
+    direction: Direction,
+    /// 重写后的第一个序列号
+    seq_num_start: Option<u32>,
+    rewrite_transact_time: bool,
+    response_timeout: Duration,
+    ignored_fields: Vec<String>,
+    ignored_msg_types: Vec<u32>,
+}
+
+impl<'a> Replayer<'a> {
+    pub fn new(config_manager: &'a ConfigManager) -> Self {
+        Self {
+            config_manager,
+            pace: Pace::Original,
+            direction: Direction::ClientToServer,
+            seq_num_start: None,
+            rewrite_transact_time: false,
+            response_timeout: Duration::from_secs(1),
+            ignored_fields: Vec::new(),
+            ignored_msg_types: Vec::new(),
+        }
+    }
+
+    /// 设置回放节奏，默认保持录制时的间隔
+    pub fn with_pace(mut self, pace: Pace) -> Self {
+        self.pace = pace;
+        self
+    }
+
+    /// 回放录制中某个方向的帧，默认回放券商端发出的帧；回放网关发出的帧时被测端为券商端
+    pub fn with_direction(mut self, direction: Direction) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    /// 从 `start` 开始为发出的帧重新编号
+    pub fn rewrite_seq_num(mut self, start: u32) -> Self {
+        self.seq_num_start = Some(start);
+        self
+    }
+
+    /// 发送时把 `TransactTime` 改为当前的北京时间
+    pub fn rewrite_transact_time(mut self) -> Self {
+        self.rewrite_transact_time = true;
+        self
+    }
+
+    /// 发送完毕后等待应答的时间，每收到一次数据重新计时，默认 1 秒
+    pub fn with_response_timeout(mut self, timeout: Duration) -> Self {
+        self.response_timeout = timeout;
+        self
+    }
+
+    /// 比较应答时忽略某个字段，数组元素中的字段同样忽略
+    pub fn ignore_field(mut self, name: impl Into<String>) -> Self {
+        self.ignored_fields.push(name.into());
+        self
+    }
+
+    /// 比较应答时跳过某个消息类型，例如心跳
+    pub fn ignore_msg_type(mut self, msg_type: u32) -> Self {
+        self.ignored_msg_types.push(msg_type);
+        self
+    }
+
+    /// 发出的帧及其相对第一帧的发送时间
+    pub fn schedule<'r>(&self, recording: &'r Recording) -> Vec<(Duration, &'r WireRecord)> {
+        let mut records = recording.frames(self.direction).peekable();
+        let Some(start) = records.peek().map(|record| record.timestamp) else {
+            return Vec::new();
+        };
+        records
+            .map(|record| {
+                let offset = record.timestamp.saturating_sub(start);
+                let offset = match self.pace {
+                    Pace::Original => offset,
+                    // 倍速极小时间隔超出 Duration 的范围，按最大值处理
+                    Pace::Speed(speed) if speed > 0.0 => {
+                        Duration::try_from_secs_f64(offset.as_secs_f64() / speed).unwrap_or(Duration::MAX)
+                    },
+                    Pace::Speed(_) | Pace::AsFastAsPossible => Duration::ZERO,
+                };
+                (offset, record)
+            })
+            .collect()
+    }
+
+    /// 按需要重写第 `index` 个发出的帧
+    pub fn rewrite(&self, index: usize, frame: &[u8], now: Duration) -> ReplayResult<Vec<u8>> {
+        let seq_num = self.seq_num_start.map(|start| start.wrapping_add(index as u32));
+        if self.rewrite_transact_time {
+            let mut message = MessageDecoder::new(self.config_manager, frame)
+                .decode()
+                .map_err(|source| ReplayError::Rewrite { index, source })?;
+            if message.has_field(TRANSACT_TIME) {
+                message.add_field(TRANSACT_TIME.to_string(), FieldValue::U64(ntime_of(now)));
+            }
+            if let Some(seq_num) = seq_num {
+                message.seq_num = seq_num;
+            }
+            return MessageEncoder::new(self.config_manager)
+                .encode(&message)
+                .map_err(|source| ReplayError::Rewrite { index, source });
+        }
+
+        let mut frame = frame.to_vec();
+        if let Some(seq_num) = seq_num {
+            // 只改头部时不重新编码，保留录制的原始字节；先按头部中的消息体长度确认帧完整
+            let (_, body_end) = split_frame(&frame).map_err(|e| ReplayError::Rewrite { index, source: e.kind })?;
+            frame.truncate(body_end + 4);
+            frame[4..8].copy_from_slice(&seq_num.to_be_bytes());
+            let checksum = self.config_manager.checksum().compute(&frame[..body_end]);
+            frame[body_end..].copy_from_slice(&checksum.to_be_bytes());
+        }
+        Ok(frame)
+    }
+
+    /// 向被测端回放录制的会话，收集应答并与录制比较
+    pub async fn replay<S: AsyncRead + AsyncWrite + Unpin>(&self, recording: &Recording, stream: S) -> ReplayResult<ReplayReport> {
+        let schedule = self.schedule(recording);
+        let (mut reader, mut writer) = tokio::io::split(stream);
+        let (done_tx, mut done_rx) = watch::channel(false);
+
+        let send = async {
+            // 发送出错时随发送任务一起释放，接收一侧同样开始等待最后的应答
+            let done_tx = done_tx;
+            let start = Instant::now();
+            for (index, (offset, record)) in schedule.iter().enumerate() {
+                match start.checked_add(*offset) {
+                    Some(deadline) => tokio::time::sleep_until(deadline).await,
+                    None => tokio::time::sleep(*offset).await,
+                }
+                let frame = self.rewrite(index, &record.frame, now())?;
+                writer.write_all(&frame).await?;
+            }
+            writer.flush().await?;
+            let _ = done_tx.send(true);
+            Ok::<_, ReplayError>(schedule.len())
+        };
+
+        let receive = async {
+            let mut buffer = Vec::new();
+            let mut chunks = Vec::new();
+            let mut chunk = [0u8; 4096];
+            loop {
+                let idle = async {
+                    let _ = done_rx.wait_for(|done| *done).await;
+                    tokio::time::sleep(self.response_timeout).await;
+                };
+                tokio::select! {
+                    read = reader.read(&mut chunk) => match read? {
+                        0 => break,
+                        n => {
+                            buffer.extend_from_slice(&chunk[..n]);
+                            chunks.push((now(), buffer.len()));
+                        },
+                    },
+                    _ = idle => break,
+                }
+            }
+            Ok::<_, ReplayError>((buffer, chunks))
+        };
+
+        let (sent, received) = tokio::join!(send, receive);
+        let sent = sent?;
+        let (buffer, chunks) = received?;
+
+        // 帧的到达时间为收到其最后一个字节的那次读取的时间
+        let mut report = ReplayReport { sent, ..Default::default() };
+        let mut trailing = None;
+        let mut end = 0;
+        for frame in Frames::new(&buffer) {
+            match frame {
+                Ok((offset, frame)) => {
+                    end = offset + frame.len();
+                    let timestamp = chunks.iter().find(|(_, len)| *len >= end).map_or(Duration::ZERO, |(timestamp, _)| *timestamp);
+                    report.received.push((timestamp, frame.to_vec()));
+                },
+                Err(e) => trailing = Some(e),
+            }
+        }
+
+        let expected: Vec<&[u8]> = recording.frames(self.direction.opposite()).map(|record| record.frame.as_slice()).collect();
+        let actual: Vec<&[u8]> = report.received.iter().map(|(_, frame)| frame.as_slice()).collect();
+        report.mismatches = self.compare(&expected, &actual);
+        // 连接关闭或超时时还有不够一帧的字节
+        if let Some(error) = trailing {
+            report.mismatches.push(ResponseMismatch {
+                expected: None,
+                actual: Some(report.received.len()),
+                kind: Mismatch::Undecodable { recorded: false, frame: buffer[end..].to_vec(), error },
+            });
+        }
+        Ok(report)
+    }
+
+    /// 把收到的应答与录制的应答对齐后逐个比较，跳过忽略的消息类型
+    ///
+    /// 只有类型相同的应答才能对齐，比较的字段都相同的应答优先对齐，对齐的两个应答比较字段；
+    /// 少收或多收一个应答只报告一处 `Missing` 或 `Unexpected`，之后的应答仍然对齐。两次对齐之间
+    /// 剩下的应答一一配对为 `MsgType`，多出的部分报告为缺少或多出，无法解码的帧单独报告
+    pub fn compare(&self, expected: &[&[u8]], actual: &[&[u8]]) -> Vec<ResponseMismatch> {
+        let mut expected: Vec<_> = self.decode_all(expected).map(Some).collect();
+        let mut actual: Vec<_> = self.decode_all(actual).map(Some).collect();
+        let pairs = align(expected.len(), actual.len(), |i, j| self.weight(&expected[i], &actual[j]));
+
+        let mut mismatches = Vec::new();
+        let (mut next_expected, mut next_actual) = (0, 0);
+        for (matched_expected, matched_actual) in pairs.into_iter().chain([(expected.len(), actual.len())]) {
+            // 两次对齐之间剩下的应答
+            let mut gap_expected = Vec::new();
+            for (index, entry) in expected.iter_mut().enumerate().take(matched_expected).skip(next_expected) {
+                match entry.take() {
+                    Some((frame, Err(error))) => mismatches.push(ResponseMismatch {
+                        expected: Some(index),
+                        actual: None,
+                        kind: Mismatch::Undecodable { recorded: true, frame: frame.to_vec(), error },
+                    }),
+                    Some((_, Ok(message))) => gap_expected.push((index, message)),
+                    None => {},
+                }
+            }
+            let mut gap_actual = Vec::new();
+            for (index, entry) in actual.iter_mut().enumerate().take(matched_actual).skip(next_actual) {
+                match entry.take() {
+                    Some((frame, Err(error))) => mismatches.push(ResponseMismatch {
+                        expected: None,
+                        actual: Some(index),
+                        kind: Mismatch::Undecodable { recorded: false, frame: frame.to_vec(), error },
+                    }),
+                    Some((_, Ok(message))) => gap_actual.push((index, message)),
+                    None => {},
+                }
+            }
+            let mut gap_expected = gap_expected.into_iter();
+            let mut gap_actual = gap_actual.into_iter();
+            loop {
+                let mismatch = match (gap_expected.next(), gap_actual.next()) {
+                    (None, None) => break,
+                    (Some((e, expected)), Some((a, actual))) => {
+                        ResponseMismatch { expected: Some(e), actual: Some(a), kind: Mismatch::MsgType { expected, actual } }
+                    },
+                    (Some((e, expected)), None) => ResponseMismatch { expected: Some(e), actual: None, kind: Mismatch::Missing(expected) },
+                    (None, Some((a, actual))) => ResponseMismatch { expected: None, actual: Some(a), kind: Mismatch::Unexpected(actual) },
+                };
+                mismatches.push(mismatch);
+            }
+
+            // 对齐的两个应答消息类型相同，比较字段
+            if let (Some(Some((_, Ok(expected_message)))), Some(Some((_, Ok(actual_message))))) =
+                (expected.get(matched_expected), actual.get(matched_actual))
+            {
+                let diffs: Vec<FieldDiff> = expected_message.diff(actual_message).into_iter().filter(|diff| !self.is_ignored(&diff.path)).collect();
+                if !diffs.is_empty() {
+                    mismatches.push(ResponseMismatch {
+                        expected: Some(matched_expected),
+                        actual: Some(matched_actual),
+                        kind: Mismatch::Fields { msg_type: expected_message.msg_type, diffs },
+                    });
+                }
+            }
+            next_expected = matched_expected + 1;
+            next_actual = matched_actual + 1;
+        }
+        mismatches
+    }
+
+    /// 逐个解码，跳过忽略的消息类型
+    fn decode_all<'f>(&self, frames: &[&'f [u8]]) -> impl Iterator<Item = (&'f [u8], MessageResult<Message>)> {
+        frames.iter()
+            .filter(|frame| {
+                MessageHeader::parse(frame).map_or(true, |header| !self.ignored_msg_types.contains(&header.msg_type))
+            })
+            .map(|frame| (*frame, MessageDecoder::new(self.config_manager, frame).decode()))
+    }
+
+    /// 两个应答对齐的权重：类型不同或无法解码时为 0，类型相同为 1，比较的字段都相同为 2
+    fn weight(&self, expected: &Option<(&[u8], MessageResult<Message>)>, actual: &Option<(&[u8], MessageResult<Message>)>) -> u32 {
+        match (expected, actual) {
+            (Some((_, Ok(expected))), Some((_, Ok(actual)))) if expected.msg_type == actual.msg_type => {
+                match expected.diff(actual).iter().all(|diff| self.is_ignored(&diff.path)) {
+                    true => 2,
+                    false => 1,
+                }
+            },
+            _ => 0,
+        }
+    }
+
+    /// 路径的最后一段是否为忽略的字段，如 `Parties[1].PartyID` 的 `PartyID`
+    fn is_ignored(&self, path: &str) -> bool {
+        let name = path.rsplit('.').next().unwrap_or(path);
+        self.ignored_fields.iter().any(|ignored| ignored == name || ignored == path)
+    }
+}
+
+/// 求两组应答权重之和最大的对齐，返回对齐的下标对；权重为 0 的两个应答不对齐
+///
+/// 完全相同的开头和结尾直接对齐，只对中间部分动态规划，回放结果与录制接近时开销很小
+fn align(rows: usize, columns: usize, weight: impl Fn(usize, usize) -> u32) -> Vec<(usize, usize)> {
+    let mut prefix = 0;
+    while prefix < rows && prefix < columns && weight(prefix, prefix) == 2 {
+        prefix += 1;
+    }
+    let mut suffix = 0;
+    while prefix + suffix < rows && prefix + suffix < columns && weight(rows - 1 - suffix, columns - 1 - suffix) == 2 {
+        suffix += 1;
+    }
+
+    // scores[i * width + j] 为中间部分从 i、j 开始的最大权重之和
+    let (height, width) = (rows - prefix - suffix, columns - prefix - suffix + 1);
+    let mut scores = vec![0u32; (height + 1) * width];
+    for i in (0..height).rev() {
+        for j in (0..width - 1).rev() {
+            let skip = scores[(i + 1) * width + j].max(scores[i * width + j + 1]);
+            scores[i * width + j] = match weight(prefix + i, prefix + j) {
+                0 => skip,
+                weight => skip.max(scores[(i + 1) * width + j + 1] + weight),
+            };
+        }
+    }
+
+    let mut pairs: Vec<(usize, usize)> = (0..prefix).map(|i| (i, i)).collect();
+    let (mut i, mut j) = (0, 0);
+    while i < height && j < width - 1 {
+        let weight = weight(prefix + i, prefix + j);
+        if weight > 0 && scores[i * width + j] == scores[(i + 1) * width + j + 1] + weight {
+            pairs.push((prefix + i, prefix + j));
+            i += 1;
+            j += 1;
+        } else if scores[i * width + j] == scores[(i + 1) * width + j] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    pairs.extend((0..suffix).map(|k| (rows - suffix + k, columns - suffix + k)));
+    pairs
+}
+
+fn now() -> Duration {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default()
+}
+
+/// 时间对应的北京时间 `HHMMSSsssnnnn`
+pub fn ntime_of(timestamp: Duration) -> u64 {
+    let seconds = (timestamp.as_secs() + UTC_OFFSET) % 86_400;
+    let hhmmss = seconds / 3600 * 10_000 + seconds / 60 % 60 * 100 + seconds % 60;
+    hhmmss * 10_000_000 + (timestamp.subsec_nanos() / 100) as u64
+}
@@ -0,0 +1,3 @@
+//! 编译期生成的消息结构体，见 `src/config/codegen.rs`
+
+include!(concat!(env!("OUT_DIR"), "/sse_messages.rs"));
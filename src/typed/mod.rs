@@ -0,0 +1,70 @@
+//! 由 XML 消息定义在编译期生成的强类型消息
+//!
+//! `build.rs` 读取 `config/sse-message.xml`（可通过环境变量 `SSE_TDGW_MESSAGE_XML` 指定其他文件），
+//! 为每个消息生成一个结构体，编解码结果与 [`crate::codec::MessageEncoder`] /
+//! [`crate::codec::MessageDecoder`] 逐字节一致，且不经过 `FieldValue` 中转。
+
+pub mod messages;
+pub mod types;
+pub mod wire;
+
+use byteorder::{BigEndian, ByteOrder};
+
+use crate::codec::checksum::{ChecksumAlgorithm, Sum8};
+use crate::codec::types::{check_frame, MessageHeader};
+use crate::util::{MessageError, MessageResult};
+use wire::{WireReader, WireRecord};
+
+pub use messages::*;
+pub use types::{Amount, Date, FixedBytes, FixedStr, NTime, Price, Quantity};
+
+/// 强类型消息，提供完整消息帧（头部 + 消息体 + 校验和）的编解码
+pub trait TypedMessage: WireRecord {
+    /// 消息类型
+    const MSG_TYPE: u32;
+    /// 消息名称
+    const NAME: &'static str;
+
+    /// 编码后的完整消息帧长度
+    fn encoded_len(&self) -> usize {
+        MessageHeader::SIZE + self.wire_len() + 4
+    }
+
+    /// 将完整消息帧追加到 `buffer` 末尾，出错时 `buffer` 保持原样
+    ///
+    /// 复用同一个 `buffer` 时，容量足够的情况下不会产生堆分配
+    fn encode_into(&self, seq_num: u32, buffer: &mut Vec<u8>) -> MessageResult<()> {
+        let start = buffer.len();
+        buffer.reserve(self.encoded_len());
+        buffer.resize(start + MessageHeader::SIZE, 0);
+
+        if let Err(e) = self.encode(buffer) {
+            buffer.truncate(start);
+            return Err(e);
+        }
+
+        let body_length = (buffer.len() - start - MessageHeader::SIZE) as u32;
+        let header = &mut buffer[start..start + MessageHeader::SIZE];
+        BigEndian::write_u32(&mut header[0..4], Self::MSG_TYPE);
+        BigEndian::write_u32(&mut header[4..8], seq_num);
+        BigEndian::write_u32(&mut header[8..12], body_length);
+
+        let mut checksum_bytes = [0u8; 4];
+        BigEndian::write_u32(&mut checksum_bytes, Sum8.compute(&buffer[start..]));
+        buffer.extend_from_slice(&checksum_bytes);
+        Ok(())
+    }
+
+    /// 从完整消息帧解码，校验消息类型和校验和
+    fn decode_from(frame: &[u8]) -> MessageResult<(MessageHeader, Self)> {
+        let header = MessageHeader::parse(frame)?;
+        if header.msg_type != Self::MSG_TYPE {
+            return Err(MessageError::UnexpectedMessageType(Self::MSG_TYPE, header.msg_type));
+        }
+        let (header, body_end) = check_frame(frame, &Sum8)?;
+
+        let mut reader = WireReader::new(&frame[..body_end], MessageHeader::SIZE);
+        let message = Self::decode(&mut reader)?;
+        Ok((header, message))
+    }
+}
@@ -0,0 +1,197 @@
+use std::fmt;
+
+use crate::codec::types::{truncate_str, TYPE_AMOUNT_SCALE, TYPE_PRICE_SCALE, TYPE_QUANTITY_SCALE};
+
+macro_rules! fixed_point {
+    ($(#[$meta:meta])* $name:ident, $scale:expr, $precision:expr) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+        pub struct $name(pub i64);
+
+        impl $name {
+            /// 从浮点数构造，换算方式与动态编码器一致
+            pub fn from_f64(value: f64) -> Self {
+                Self((value * $scale) as i64)
+            }
+
+            /// 从浮点数构造并四舍五入，用于还原解码得到的浮点数
+            pub fn from_f64_rounded(value: f64) -> Self {
+                Self((value * $scale).round() as i64)
+            }
+
+            /// 转换为浮点数，换算方式与动态解码器一致
+            pub fn to_f64(self) -> f64 {
+                self.0 as f64 / $scale
+            }
+
+            /// 获取线上传输的原始整数值
+            pub fn raw(self) -> i64 {
+                self.0
+            }
+        }
+
+        impl From<f64> for $name {
+            fn from(value: f64) -> Self {
+                Self::from_f64(value)
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "{:.*}", $precision, self.to_f64())
+            }
+        }
+    };
+}
+
+fixed_point!(
+    /// N13(5) 精度价格，内部保存放大 10^5 倍后的整数
+    Price, TYPE_PRICE_SCALE, 5
+);
+fixed_point!(
+    /// N15(3) 精度数量，内部保存放大 10^3 倍后的整数
+    Quantity, TYPE_QUANTITY_SCALE, 3
+);
+fixed_point!(
+    /// N18(5) 精度金额，内部保存放大 10^5 倍后的整数
+    Amount, TYPE_AMOUNT_SCALE, 5
+);
+
+/// YYYYMMDD 格式日期
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Date(pub u32);
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:08}", self.0)
+    }
+}
+
+impl From<u32> for Date {
+    fn from(value: u32) -> Self {
+        Self(value)
+    }
+}
+
+/// HHMMSSsssnnnn 格式时间戳
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct NTime(pub u64);
+
+impl fmt::Display for NTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:013}", self.0)
+    }
+}
+
+impl From<u64> for NTime {
+    fn from(value: u64) -> Self {
+        Self(value)
+    }
+}
+
+/// 定长字符串，内部保存右侧补空格的 N 个字节，不产生堆分配
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FixedStr<const N: usize>([u8; N]);
+
+impl<const N: usize> FixedStr<N> {
+    /// 从字符串构造，超长部分在字符边界处截断，不足部分补空格
+    pub fn new(value: &str) -> Self {
+        let value = truncate_str(value, N).as_bytes();
+        let mut bytes = [b' '; N];
+        bytes[..value.len()].copy_from_slice(value);
+        Self(bytes)
+    }
+
+    /// 从线上字节构造，要求是合法的 UTF-8
+    pub fn from_bytes(bytes: [u8; N]) -> Result<Self, std::str::Utf8Error> {
+        std::str::from_utf8(&bytes)?;
+        Ok(Self(bytes))
+    }
+
+    /// 去除首尾空白后的字符串，与动态解码器的结果一致
+    pub fn as_str(&self) -> &str {
+        std::str::from_utf8(&self.0).unwrap_or_default().trim()
+    }
+
+    /// 线上传输的原始字节
+    pub fn as_bytes(&self) -> &[u8; N] {
+        &self.0
+    }
+}
+
+impl<const N: usize> Default for FixedStr<N> {
+    fn default() -> Self {
+        Self([b' '; N])
+    }
+}
+
+impl<const N: usize> From<&str> for FixedStr<N> {
+    fn from(value: &str) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<const N: usize> fmt::Debug for FixedStr<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self.as_str())
+    }
+}
+
+impl<const N: usize> fmt::Display for FixedStr<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// 定长原始字节，不足部分补 0
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FixedBytes<const N: usize>(pub [u8; N]);
+
+impl<const N: usize> FixedBytes<N> {
+    /// 线上传输的原始字节
+    pub fn as_bytes(&self) -> &[u8; N] {
+        &self.0
+    }
+}
+
+impl<const N: usize> Default for FixedBytes<N> {
+    fn default() -> Self {
+        Self([0; N])
+    }
+}
+
+impl<const N: usize> fmt::Debug for FixedBytes<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", hex::encode(self.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixed_str() {
+        let s: FixedStr<8> = FixedStr::new("PBU001");
+        assert_eq!(s.as_bytes(), b"PBU001  ");
+        assert_eq!(s.as_str(), "PBU001");
+
+        // 超长字符串在字符边界处截断
+        let s: FixedStr<4> = FixedStr::new("上海");
+        assert_eq!(s.as_bytes(), b"\xe4\xb8\x8a ");
+        assert_eq!(s.as_str(), "上");
+
+        assert!(FixedStr::<2>::from_bytes([0xff, 0x20]).is_err());
+        assert_eq!(FixedStr::<3>::default().as_str(), "");
+    }
+
+    #[test]
+    fn test_fixed_point() {
+        assert_eq!(Price::from_f64(10.5).raw(), 1_050_000);
+        assert_eq!(Price::from_f64_rounded(Price(29_000).to_f64()).raw(), 29_000);
+        assert_eq!(Quantity(1_000_123).to_string(), "1000.123");
+        assert_eq!(Amount(123_456_789).to_string(), "1234.56789");
+        assert_eq!(Date(20231201).to_string(), "20231201");
+        assert_eq!(NTime(930000000000).to_string(), "0930000000000");
+    }
+}
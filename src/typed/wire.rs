@@ -0,0 +1,274 @@
+use byteorder::{BigEndian, ByteOrder};
+
+use crate::codec::types::{
+    validate_amount,
+    validate_date_format,
+    validate_ntime_format,
+    validate_price,
+    validate_quantity,
+};
+use crate::typed::types::{Amount, Date, FixedBytes, FixedStr, NTime, Price, Quantity};
+use crate::util::{MessageError, MessageResult};
+
+/// 按顺序读取消息体的游标
+pub struct WireReader<'a> {
+    buffer: &'a [u8],
+    position: usize,
+}
+
+impl<'a> WireReader<'a> {
+    /// 从指定位置开始读取
+    pub fn new(buffer: &'a [u8], position: usize) -> Self {
+        Self { buffer, position }
+    }
+
+    /// 当前读取位置
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// 读取 n 个字节
+    pub fn take(&mut self, n: usize) -> MessageResult<&'a [u8]> {
+        if self.position + n > self.buffer.len() {
+            return Err(MessageError::BodyTooShort);
+        }
+        let bytes = &self.buffer[self.position..self.position + n];
+        self.position += n;
+        Ok(bytes)
+    }
+
+    /// 读取 N 个字节到定长数组
+    pub fn take_array<const N: usize>(&mut self) -> MessageResult<[u8; N]> {
+        let mut bytes = [0u8; N];
+        bytes.copy_from_slice(self.take(N)?);
+        Ok(bytes)
+    }
+}
+
+/// 单个字段的线上编解码，与动态编解码器的字节布局和校验规则保持一致
+pub trait WireField: Sized {
+    /// 编码后的字节数
+    fn wire_len(&self) -> usize;
+
+    /// 写入字段，`name` 仅用于错误信息
+    fn put(&self, name: &str, buffer: &mut Vec<u8>) -> MessageResult<()>;
+
+    /// 读取字段，`name` 仅用于错误信息
+    fn get(name: &str, reader: &mut WireReader<'_>) -> MessageResult<Self>;
+}
+
+/// 由多个字段组成的记录，例如消息体或数组元素
+pub trait WireRecord: Sized {
+    /// 编码后的字节数
+    fn wire_len(&self) -> usize;
+
+    /// 按字段顺序写入
+    fn encode(&self, buffer: &mut Vec<u8>) -> MessageResult<()>;
+
+    /// 按字段顺序读取
+    fn decode(reader: &mut WireReader<'_>) -> MessageResult<Self>;
+}
+
+macro_rules! integer_field {
+    ($ty:ty, $size:expr, $write:expr, $read:expr) => {
+        impl WireField for $ty {
+            fn wire_len(&self) -> usize {
+                $size
+            }
+
+            fn put(&self, _name: &str, buffer: &mut Vec<u8>) -> MessageResult<()> {
+                let mut bytes = [0u8; $size];
+                $write(&mut bytes, *self);
+                buffer.extend_from_slice(&bytes);
+                Ok(())
+            }
+
+            fn get(_name: &str, reader: &mut WireReader<'_>) -> MessageResult<Self> {
+                Ok($read(reader.take($size)?))
+            }
+        }
+    };
+}
+
+integer_field!(u8, 1, |b: &mut [u8], v: u8| b[0] = v, |b: &[u8]| b[0]);
+integer_field!(u16, 2, BigEndian::write_u16, BigEndian::read_u16);
+integer_field!(u32, 4, BigEndian::write_u32, BigEndian::read_u32);
+integer_field!(u64, 8, BigEndian::write_u64, BigEndian::read_u64);
+integer_field!(i8, 1, |b: &mut [u8], v: i8| b[0] = v as u8, |b: &[u8]| b[0] as i8);
+integer_field!(i16, 2, BigEndian::write_i16, BigEndian::read_i16);
+integer_field!(i32, 4, BigEndian::write_i32, BigEndian::read_i32);
+integer_field!(i64, 8, BigEndian::write_i64, BigEndian::read_i64);
+
+macro_rules! fixed_point_field {
+    ($ty:ident, $validate:ident, $label:expr) => {
+        impl WireField for $ty {
+            fn wire_len(&self) -> usize {
+                8
+            }
+
+            fn put(&self, name: &str, buffer: &mut Vec<u8>) -> MessageResult<()> {
+                if !$validate(self.0) {
+                    return Err(MessageError::ValueExceedsRange(format!(
+                        "{} value {} of field {} exceeds maximum limit", $label, self, name
+                    )));
+                }
+                self.0.put(name, buffer)
+            }
+
+            fn get(name: &str, reader: &mut WireReader<'_>) -> MessageResult<Self> {
+                let value = i64::get(name, reader)?;
+                if !$validate(value) {
+                    return Err(MessageError::ValueExceedsRange(format!(
+                        "{} value {} of field {} exceeds maximum limit", $label, value, name
+                    )));
+                }
+                Ok($ty(value))
+            }
+        }
+    };
+}
+
+fixed_point_field!(Price, validate_price, "Price");
+fixed_point_field!(Quantity, validate_quantity, "Quantity");
+fixed_point_field!(Amount, validate_amount, "Amount");
+
+impl WireField for Date {
+    fn wire_len(&self) -> usize {
+        4
+    }
+
+    fn put(&self, name: &str, buffer: &mut Vec<u8>) -> MessageResult<()> {
+        if !validate_date_format(self.0) {
+            return Err(MessageError::InvalidFieldValue(format!(
+                "Invalid date format: {} in field {}. Expected YYYYMMDD", self.0, name
+            )));
+        }
+        self.0.put(name, buffer)
+    }
+
+    fn get(name: &str, reader: &mut WireReader<'_>) -> MessageResult<Self> {
+        let value = u32::get(name, reader)?;
+        if !validate_date_format(value) {
+            return Err(MessageError::InvalidFieldValue(format!(
+                "Invalid date format: {} in field {}. Expected YYYYMMDD", value, name
+            )));
+        }
+        Ok(Date(value))
+    }
+}
+
+impl WireField for NTime {
+    fn wire_len(&self) -> usize {
+        8
+    }
+
+    fn put(&self, name: &str, buffer: &mut Vec<u8>) -> MessageResult<()> {
+        if !validate_ntime_format(self.0) {
+            return Err(MessageError::InvalidFieldValue(format!(
+                "Invalid ntime format: {} in field {}. Expected HHMMSSsssnnnn", self.0, name
+            )));
+        }
+        self.0.put(name, buffer)
+    }
+
+    fn get(name: &str, reader: &mut WireReader<'_>) -> MessageResult<Self> {
+        let value = u64::get(name, reader)?;
+        if !validate_ntime_format(value) {
+            return Err(MessageError::InvalidFieldValue(format!(
+                "Invalid ntime format: {} in field {}. Expected HHMMSSsssnnnn", value, name
+            )));
+        }
+        Ok(NTime(value))
+    }
+}
+
+impl<const N: usize> WireField for FixedStr<N> {
+    fn wire_len(&self) -> usize {
+        N
+    }
+
+    fn put(&self, _name: &str, buffer: &mut Vec<u8>) -> MessageResult<()> {
+        buffer.extend_from_slice(self.as_bytes());
+        Ok(())
+    }
+
+    fn get(name: &str, reader: &mut WireReader<'_>) -> MessageResult<Self> {
+        FixedStr::from_bytes(reader.take_array()?)
+            .map_err(|e| MessageError::FieldDecodeError(format!("UTF-8 error in field {}: {}", name, e)))
+    }
+}
+
+impl<const N: usize> WireField for FixedBytes<N> {
+    fn wire_len(&self) -> usize {
+        N
+    }
+
+    fn put(&self, _name: &str, buffer: &mut Vec<u8>) -> MessageResult<()> {
+        buffer.extend_from_slice(self.as_bytes());
+        Ok(())
+    }
+
+    fn get(_name: &str, reader: &mut WireReader<'_>) -> MessageResult<Self> {
+        Ok(FixedBytes(reader.take_array()?))
+    }
+}
+
+/// 写入 1/2/4 字节宽的计数值
+pub fn put_count(name: &str, width: usize, count: usize, buffer: &mut Vec<u8>) -> MessageResult<()> {
+    let overflow = || MessageError::ValueExceedsRange(format!(
+        "Field {} count {} exceeds {}-byte length field", name, count, width
+    ));
+    match width {
+        1 => u8::try_from(count).map_err(|_| overflow())?.put(name, buffer),
+        2 => u16::try_from(count).map_err(|_| overflow())?.put(name, buffer),
+        4 => u32::try_from(count).map_err(|_| overflow())?.put(name, buffer),
+        _ => Err(MessageError::InvalidArrayCountType),
+    }
+}
+
+/// 读取 1/2/4 字节宽的计数值
+pub fn get_count(name: &str, width: usize, reader: &mut WireReader<'_>) -> MessageResult<usize> {
+    match width {
+        1 => Ok(u8::get(name, reader)? as usize),
+        2 => Ok(u16::get(name, reader)? as usize),
+        4 => Ok(u32::get(name, reader)? as usize),
+        _ => Err(MessageError::InvalidArrayCountType),
+    }
+}
+
+/// 写入带长度前缀的数组
+pub fn put_array<T: WireRecord>(name: &str, width: usize, elements: &[T], buffer: &mut Vec<u8>) -> MessageResult<()> {
+    put_count(name, width, elements.len(), buffer)?;
+    for element in elements {
+        element.encode(buffer)?;
+    }
+    Ok(())
+}
+
+/// 读取带长度前缀的数组
+pub fn get_array<T: WireRecord>(name: &str, width: usize, reader: &mut WireReader<'_>) -> MessageResult<Vec<T>> {
+    let count = get_count(name, width, reader)?;
+    let mut elements = Vec::with_capacity(count);
+    for _ in 0..count {
+        elements.push(T::decode(reader)?);
+    }
+    Ok(elements)
+}
+
+/// 带长度前缀的数组编码后的字节数
+pub fn array_len<T: WireRecord>(width: usize, elements: &[T]) -> usize {
+    width + elements.iter().map(WireRecord::wire_len).sum::<usize>()
+}
+
+/// 写入带长度前缀的变长字节
+pub fn put_var_bytes(name: &str, width: usize, bytes: &[u8], buffer: &mut Vec<u8>) -> MessageResult<()> {
+    put_count(name, width, bytes.len(), buffer)?;
+    buffer.extend_from_slice(bytes);
+    Ok(())
+}
+
+/// 读取带长度前缀的变长字节
+pub fn get_var_bytes(name: &str, width: usize, reader: &mut WireReader<'_>) -> MessageResult<Vec<u8>> {
+    let length = get_count(name, width, reader)?;
+    Ok(reader.take(length)?.to_vec())
+}
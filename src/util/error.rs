@@ -1,3 +1,5 @@
+use std::fmt;
+
 use thiserror::Error;
 
 // 编解码相关错误
@@ -31,6 +33,9 @@ pub enum ConfigError {
     #[error("Message type {0} not found")]
     MessageNotFound(u32),
     
+    #[error("Message name {0} not found")]
+    MessageNameNotFound(String),
+    
     #[error("Invalid message type")]
     InvalidMessageType,
     
@@ -54,6 +59,54 @@ pub enum ConfigError {
     
     #[error("UTF-8 conversion error: {0}")]
     Utf8Error(#[from] std::str::Utf8Error),
+
+    #[error("Invalid definition in message {0}: {1}")]
+    InvalidDefinition(String, String),
+}
+
+// 抓包文件读取相关错误
+#[derive(Error, Debug)]
+pub enum CaptureError {
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+
+    #[error("Unknown capture file format: magic {0:#010x}")]
+    UnknownFormat(u32),
+
+    #[error("Truncated capture file: {0}")]
+    Truncated(String),
+
+    #[error("Invalid pcapng block: {0}")]
+    InvalidBlock(String),
+}
+
+// 报文日志（WireLog）相关错误
+#[derive(Error, Debug)]
+pub enum WireLogError {
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+
+    #[error("Invalid WireLog header: {0}")]
+    InvalidHeader(String),
+
+    #[error("Corrupted WireLog record at offset {offset}: {reason}")]
+    Corrupted { offset: u64, reason: String },
+
+    #[error("Truncated WireLog record at offset {0}")]
+    Truncated(u64),
+
+    #[error("WireLog writer thread has stopped")]
+    Closed,
+}
+
+// 会话回放相关错误
+#[derive(Error, Debug)]
+pub enum ReplayError {
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+
+    #[error("Failed to rewrite frame #{index}: {source}")]
+    Rewrite { index: usize, source: MessageError },
 }
 
 // 消息解析相关错误
@@ -67,6 +120,9 @@ pub enum MessageError {
     
     #[error("Unknown message type: {0}")]
     UnknownMessageType(u32),
+
+    #[error("Unexpected message type: expected {0}, got {1}")]
+    UnexpectedMessageType(u32, u32),
     
     #[error("Failed to decode field: {0}")]
     FieldDecodeError(String),
@@ -101,14 +157,186 @@ pub enum MessageError {
     #[error("Checksum error")]
     ChecksumError,
 
+    #[error("Buffer too small: {0} bytes required, {1} available")]
+    BufferTooSmall(usize, usize),
+
     #[error("Value exceeds range: {0}")]
     ValueExceedsRange(String),
 
     #[error("Unknown business extension: {0}")]
     UnknownBizExtension(u32),
+
+    #[error("Unknown field: {0}")]
+    UnknownField(String),
+
+    #[error("Missing required fields: {}", .0.join(", "))]
+    MissingRequiredFields(Vec<String>),
+
+    #[error("Invalid field mapping: {}", .0.join("; "))]
+    InvalidFieldMapping(Vec<String>),
+
+    #[error("Field path '{0}' failed at '{1}': {2}")]
+    FieldPathError(String, String, String),
+
+    #[error("Field not found: {0}")]
+    FieldNotFound(String),
+
+    #[error("Field '{0}' {1}")]
+    FieldConversionError(String, ConversionError),
+}
+
+/// 带上下文的编解码错误
+///
+/// `kind` 是原始错误，仍然可以按变体匹配；`context` 记录出错的消息、字段路径、字节偏移和期望值。
+/// 编解码器的 `*_with_context` 方法返回这个类型，其余方法只返回 `kind`
+#[derive(Error, Debug)]
+pub struct ContextError {
+    /// 原始错误
+    pub kind: MessageError,
+    /// 出错的位置
+    pub context: Box<ErrorContext>,
+}
+
+impl fmt::Display for ContextError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if *self.context == ErrorContext::default() {
+            return write!(f, "{}", self.kind);
+        }
+        write!(f, "{}: {}", self.context, self.kind)
+    }
+}
+
+impl From<MessageError> for ContextError {
+    fn from(kind: MessageError) -> Self {
+        Self { kind, context: Box::default() }
+    }
+}
+
+impl From<ContextError> for MessageError {
+    fn from(error: ContextError) -> Self {
+        error.kind
+    }
+}
+
+impl ContextError {
+    /// 记录出错的字节偏移，已有偏移时保留更精确的内层偏移
+    pub(crate) fn at(mut self, offset: usize) -> Self {
+        self.context.offset.get_or_insert(offset);
+        self
+    }
+
+    /// 记录期望值和实际值
+    pub(crate) fn expected(mut self, expected: impl fmt::Display, actual: impl fmt::Display) -> Self {
+        self.context.expected = Some(expected.to_string());
+        self.context.actual = Some(actual.to_string());
+        self
+    }
+
+    /// 在字段路径前加上外层字段名，`name` 为 `[i]` 形式时表示数组下标
+    pub(crate) fn in_field(mut self, name: &str) -> Self {
+        self.context.field = Some(match self.context.field.take() {
+            Some(inner) if inner.starts_with('[') => format!("{}{}", name, inner),
+            Some(inner) => format!("{}.{}", name, inner),
+            None => name.to_string(),
+        });
+        self
+    }
+
+    /// 记录出错的消息
+    pub(crate) fn in_message(mut self, msg_type: u32, msg_name: Option<&str>, seq_num: Option<u32>) -> Self {
+        self.context.msg_type = Some(msg_type);
+        self.context.msg_name = msg_name.map(str::to_string);
+        self.context.seq_num = seq_num;
+        self
+    }
+}
+
+impl MessageError {
+    /// 转为带上下文的错误并记录字节偏移
+    pub(crate) fn at(self, offset: usize) -> ContextError {
+        ContextError::from(self).at(offset)
+    }
+
+    /// 转为带上下文的错误并记录期望值和实际值
+    pub(crate) fn expected(self, expected: impl fmt::Display, actual: impl fmt::Display) -> ContextError {
+        ContextError::from(self).expected(expected, actual)
+    }
+
+    /// 转为带上下文的错误并记录字段名
+    pub(crate) fn in_field(self, name: &str) -> ContextError {
+        ContextError::from(self).in_field(name)
+    }
+
+    /// 转为带上下文的错误并记录出错的消息
+    pub(crate) fn in_message(self, msg_type: u32, msg_name: Option<&str>, seq_num: Option<u32>) -> ContextError {
+        ContextError::from(self).in_message(msg_type, msg_name, seq_num)
+    }
+}
+
+/// 编解码错误的上下文
+///
+/// `offset` 是相对于帧起始位置的字节偏移；`field` 是字段路径，数组元素带下标，
+/// 例如 `NoPartyIDs[2].PartyID`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ErrorContext {
+    /// 消息类型
+    pub msg_type: Option<u32>,
+    /// 消息名称
+    pub msg_name: Option<String>,
+    /// 序列号
+    pub seq_num: Option<u32>,
+    /// 字段路径
+    pub field: Option<String>,
+    /// 字节偏移
+    pub offset: Option<usize>,
+    /// 期望的长度或取值
+    pub expected: Option<String>,
+    /// 实际的长度或取值
+    pub actual: Option<String>,
+}
+
+impl fmt::Display for ErrorContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = Vec::new();
+        match (self.msg_type, &self.msg_name) {
+            (Some(msg_type), Some(name)) => parts.push(format!("message {} ({})", msg_type, name)),
+            (Some(msg_type), None) => parts.push(format!("message {}", msg_type)),
+            _ => {},
+        }
+        if let Some(seq_num) = self.seq_num {
+            parts.push(format!("seq {}", seq_num));
+        }
+        if let Some(field) = &self.field {
+            parts.push(format!("field '{}'", field));
+        }
+        if let Some(offset) = self.offset {
+            parts.push(format!("offset {}", offset));
+        }
+        match (&self.expected, &self.actual) {
+            (Some(expected), Some(actual)) => parts.push(format!("expected {}, got {}", expected, actual)),
+            (Some(expected), None) => parts.push(format!("expected {}", expected)),
+            (None, Some(actual)) => parts.push(format!("got {}", actual)),
+            (None, None) => {},
+        }
+        f.write_str(&parts.join(", "))
+    }
+}
+
+// 字段值类型转换错误
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+#[error("expected FieldValue::{expected}, got FieldValue::{actual}")]
+pub struct ConversionError {
+    /// 目标类型对应的 FieldValue 变体名
+    pub expected: &'static str,
+    /// 实际的 FieldValue 变体名
+    pub actual: &'static str,
 }
 
 // 类型别名
 pub type CodecResult<T> = std::result::Result<T, CodecError>;
 pub type ConfigResult<T> = std::result::Result<T, ConfigError>;
-pub type MessageResult<T> = std::result::Result<T, MessageError>;
\ No newline at end of file
+pub type MessageResult<T> = std::result::Result<T, MessageError>;
+pub type ContextResult<T> = std::result::Result<T, ContextError>;
+pub type CaptureResult<T> = std::result::Result<T, CaptureError>;
+pub type WireLogResult<T> = std::result::Result<T, WireLogError>;
+pub type ReplayResult<T> = std::result::Result<T, ReplayError>;
\ No newline at end of file
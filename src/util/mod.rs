@@ -1,3 +1,3 @@
 pub mod error;
 
-pub use error::{CodecError, ConfigError, MessageError, CodecResult, ConfigResult, MessageResult};
\ No newline at end of file
+pub use error::{CaptureError, CodecError, ConfigError, ContextError, ConversionError, ErrorContext, MessageError, ReplayError, WireLogError, CaptureResult, CodecResult, ConfigResult, ContextResult, MessageResult, ReplayResult, WireLogResult};
\ No newline at end of file
@@ -0,0 +1,115 @@
+use std::sync::mpsc::{self, Receiver, Sender, SyncSender, TryRecvError};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::thread;
+
+use crate::util::{WireLogError, WireLogResult};
+use crate::wirelog::{WireLogWriter, WireRecord};
+
+/// 后台写日志线程的句柄，可以克隆后由多个连接共享
+///
+/// 追加记录只是把记录交给后台线程，不会阻塞调用方，因此可以在异步任务中使用；后台线程在队列清空时
+/// 把缓冲的记录写入文件。写日志失败时通过 `tracing` 输出错误，并保留第一个错误供 `take_error` 取出
+///
+/// ```ignore
+/// let log = WireLogWriter::open("logs", "tdgw")?.spawn()?;
+/// log.append(WireRecord::new(timestamp, Direction::ServerToClient, "S1", frame));
+/// let writer = log.close()?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct WireLogHandle {
+    sender: Sender<Command>,
+    /// 后台线程遇到的第一个错误
+    error: Arc<Mutex<Option<WireLogError>>>,
+}
+
+enum Command {
+    Append(WireRecord),
+    Flush(SyncSender<WireLogResult<()>>),
+    Close(SyncSender<WireLogWriter>),
+}
+
+impl WireLogWriter {
+    /// 把写入器移到后台线程中，返回提交记录的句柄
+    pub fn spawn(self) -> WireLogResult<WireLogHandle> {
+        let (sender, receiver) = mpsc::channel();
+        let error = Arc::new(Mutex::new(None));
+        let slot = error.clone();
+        thread::Builder::new()
+            .name("wirelog".to_string())
+            .spawn(move || run(self, receiver, &slot))?;
+        Ok(WireLogHandle { sender, error })
+    }
+}
+
+impl WireLogHandle {
+    /// 提交一条记录，后台线程已经停止时记录错误
+    pub fn append(&self, record: WireRecord) {
+        if self.sender.send(Command::Append(record)).is_err() {
+            report(&self.error, WireLogError::Closed);
+        }
+    }
+
+    /// 等待后台线程写完已提交的记录并写入文件，会阻塞当前线程
+    pub fn flush(&self) -> WireLogResult<()> {
+        let (reply, result) = mpsc::sync_channel(1);
+        self.sender.send(Command::Flush(reply)).map_err(|_| WireLogError::Closed)?;
+        result.recv().map_err(|_| WireLogError::Closed)?
+    }
+
+    /// 写完已提交的记录后停止后台线程并取回写入器，会阻塞当前线程；之后提交的记录不再写入
+    pub fn close(&self) -> WireLogResult<WireLogWriter> {
+        let (reply, writer) = mpsc::sync_channel(1);
+        self.sender.send(Command::Close(reply)).map_err(|_| WireLogError::Closed)?;
+        writer.recv().map_err(|_| WireLogError::Closed)
+    }
+
+    /// 取出后台线程遇到的第一个错误
+    pub fn take_error(&self) -> Option<WireLogError> {
+        self.error.lock().unwrap_or_else(PoisonError::into_inner).take()
+    }
+}
+
+fn run(mut writer: WireLogWriter, receiver: Receiver<Command>, error: &Mutex<Option<WireLogError>>) {
+    loop {
+        let command = match receiver.try_recv() {
+            Ok(command) => command,
+            Err(TryRecvError::Empty) => {
+                // 队列清空时写入文件，再等待下一条命令
+                if let Err(e) = writer.flush() {
+                    report(error, e);
+                }
+                match receiver.recv() {
+                    Ok(command) => command,
+                    Err(_) => return,
+                }
+            },
+            Err(TryRecvError::Disconnected) => break,
+        };
+        match command {
+            Command::Append(record) => {
+                if let Err(e) = writer.append(&record) {
+                    report(error, e);
+                }
+            },
+            Command::Flush(reply) => {
+                let _ = reply.send(writer.flush());
+            },
+            Command::Close(reply) => {
+                if let Err(e) = writer.flush() {
+                    report(error, e);
+                }
+                let _ = reply.send(writer);
+                return;
+            },
+        }
+    }
+    if let Err(e) = writer.flush() {
+        report(error, e);
+    }
+}
+
+/// 输出错误并保留第一个错误
+fn report(error: &Mutex<Option<WireLogError>>, e: WireLogError) {
+    tracing::error!("failed to write wire log: {}", e);
+    error.lock().unwrap_or_else(PoisonError::into_inner).get_or_insert(e);
+}
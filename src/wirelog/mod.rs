@@ -0,0 +1,224 @@
+//! 报文日志（WireLog）：只追加的二进制日志，按接收顺序记录每个连接上收发的原始帧
+//!
+//! 每个文件对应一个交易日，文件名为 `{prefix}-{TradeDate}.wlog`。文件以 16 字节的文件头开始：
+//!
+//! | 偏移 | 长度 | 内容 |
+//! |------|------|------|
+//! | 0 | 8 | 魔数 `TDGWWLOG` |
+//! | 8 | 2 | 格式版本，当前为 1 |
+//! | 10 | 2 | 保留 |
+//! | 12 | 4 | 交易日期 YYYYMMDD |
+//!
+//! 之后是连续的记录，整数均为大端序：
+//!
+//! | 长度 | 内容 |
+//! |------|------|
+//! | 4 | 记录体长度 |
+//! | 8 | 接收时间，自 UNIX 纪元起的纳秒数 |
+//! | 4 | 帧头部中的序列号 |
+//! | 1 | 方向，0 为券商端发往网关，1 为网关发往券商端 |
+//! | 1 | 会话标识长度 |
+//! | n | 会话标识，UTF-8 |
+//! | m | 原始帧 |
+//! | 4 | 记录体长度，与开头相同，用于发现写了一半的记录 |
+
+pub mod handle;
+pub mod reader;
+pub mod tap;
+pub mod writer;
+
+pub use handle::WireLogHandle;
+pub use reader::WireLogReader;
+pub use tap::WireLogTap;
+pub use writer::WireLogWriter;
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use byteorder::{BigEndian, ByteOrder};
+
+use crate::capture::{civil_date, Direction};
+use crate::codec::types::validate_date_format;
+use crate::codec::{ChecksumMode, MessageDecoder, MessageHeader};
+use crate::config::manager::ConfigManager;
+use crate::message::Message;
+use crate::util::{MessageResult, WireLogError, WireLogResult};
+
+const MAGIC: &[u8; 8] = b"TDGWWLOG";
+const VERSION: u16 = 1;
+const FILE_HEADER_SIZE: usize = 16;
+/// 记录体中会话标识之前的固定部分
+const RECORD_FIXED_SIZE: usize = 14;
+/// 日志文件的扩展名
+pub const EXTENSION: &str = "wlog";
+/// 没有登录消息时，交易日按北京时间的日期划分
+const TRADE_DATE_UTC_OFFSET: u64 = 8 * 3600;
+/// 登录消息的类型
+const LOGON_MSG_TYPE: u32 = 40;
+/// 登录消息中 TradeDate 在帧中的偏移：头部 12 + SenderCompID 32 + TargetCompID 32 + HeartBtInt 2 + PrtcVersion 8
+const LOGON_TRADE_DATE_OFFSET: usize = 86;
+
+/// 日志中的一条记录
+#[derive(Debug, Clone, PartialEq)]
+pub struct WireRecord {
+    /// 接收或发送的时间，自 UNIX 纪元起
+    pub timestamp: Duration,
+    pub direction: Direction,
+    /// 会话标识，如 `SenderCompID`，最长 255 字节
+    pub session_id: String,
+    /// 帧头部中的序列号，帧不足头部长度时为 0
+    pub seq_num: u32,
+    /// 原始帧
+    pub frame: Vec<u8>,
+}
+
+impl WireRecord {
+    /// 创建记录，序列号取自帧头部
+    pub fn new(timestamp: Duration, direction: Direction, session_id: impl Into<String>, frame: Vec<u8>) -> Self {
+        let seq_num = MessageHeader::parse(&frame).map_or(0, |header| header.seq_num);
+        Self { timestamp, direction, session_id: session_id.into(), seq_num, frame }
+    }
+
+    /// 帧头部中的消息类型
+    pub fn msg_type(&self) -> Option<u32> {
+        MessageHeader::parse(&self.frame).ok().map(|header| header.msg_type)
+    }
+
+    /// 按消息定义解码帧，严格校验校验和
+    pub fn decode(&self, config_manager: &ConfigManager) -> MessageResult<Message> {
+        self.decode_with(config_manager, ChecksumMode::Strict)
+    }
+
+    /// 按指定的校验和处理方式解码帧
+    pub fn decode_with(&self, config_manager: &ConfigManager, checksum_mode: ChecksumMode) -> MessageResult<Message> {
+        MessageDecoder::new(config_manager, &self.frame).with_checksum_mode(checksum_mode).decode()
+    }
+
+    /// 接收时间的北京时间日期，没有登录消息中的 TradeDate 时用作交易日
+    pub fn trade_date(&self) -> u32 {
+        trade_date_of(self.timestamp)
+    }
+
+    /// 登录消息中的 TradeDate，其他消息或日期无效时为 `None`
+    pub fn logon_trade_date(&self) -> Option<u32> {
+        if self.msg_type()? != LOGON_MSG_TYPE {
+            return None;
+        }
+        let trade_date = BigEndian::read_u32(self.frame.get(LOGON_TRADE_DATE_OFFSET..LOGON_TRADE_DATE_OFFSET + 4)?);
+        validate_date_format(trade_date).then_some(trade_date)
+    }
+
+    /// 记录体长度
+    fn body_len(&self) -> usize {
+        RECORD_FIXED_SIZE + self.session_id.len() + self.frame.len()
+    }
+
+    /// 编码为完整的记录，包括开头和末尾的长度
+    fn encode(&self) -> WireLogResult<Vec<u8>> {
+        let session_len = u8::try_from(self.session_id.len()).map_err(|_| {
+            WireLogError::InvalidHeader(format!("session id of {} bytes exceeds 255", self.session_id.len()))
+        })?;
+        let body_len = self.body_len() as u32;
+        let mut record = Vec::with_capacity(self.body_len() + 8);
+        record.extend_from_slice(&body_len.to_be_bytes());
+        record.extend_from_slice(&(self.timestamp.as_nanos() as u64).to_be_bytes());
+        record.extend_from_slice(&self.seq_num.to_be_bytes());
+        record.push(direction_code(self.direction));
+        record.push(session_len);
+        record.extend_from_slice(self.session_id.as_bytes());
+        record.extend_from_slice(&self.frame);
+        record.extend_from_slice(&body_len.to_be_bytes());
+        Ok(record)
+    }
+
+    /// 从记录体解析，`offset` 为记录在文件中的位置
+    fn parse(body: &[u8], offset: u64) -> WireLogResult<Self> {
+        let (timestamp, seq_num, direction, session_len) = parse_fixed(body, offset)?;
+        let corrupted = |reason: String| WireLogError::Corrupted { offset, reason };
+        let session_id = body.get(RECORD_FIXED_SIZE..RECORD_FIXED_SIZE + session_len)
+            .ok_or_else(|| corrupted(format!("session id of {} bytes exceeds record", session_len)))?;
+        let session_id = std::str::from_utf8(session_id)
+            .map_err(|e| corrupted(format!("session id is not UTF-8: {}", e)))?;
+        Ok(Self {
+            timestamp,
+            direction,
+            session_id: session_id.to_string(),
+            seq_num,
+            frame: body[RECORD_FIXED_SIZE + session_len..].to_vec(),
+        })
+    }
+}
+
+/// 解析记录体的固定部分：时间、序列号、方向和会话标识长度
+fn parse_fixed(body: &[u8], offset: u64) -> WireLogResult<(Duration, u32, Direction, usize)> {
+    let corrupted = |reason: String| WireLogError::Corrupted { offset, reason };
+    if body.len() < RECORD_FIXED_SIZE {
+        return Err(corrupted(format!("record body of {} bytes", body.len())));
+    }
+    let direction = match body[12] {
+        0 => Direction::ClientToServer,
+        1 => Direction::ServerToClient,
+        other => return Err(corrupted(format!("unknown direction {}", other))),
+    };
+    Ok((Duration::from_nanos(BigEndian::read_u64(body)), BigEndian::read_u32(&body[8..]), direction, body[13] as usize))
+}
+
+fn direction_code(direction: Direction) -> u8 {
+    match direction {
+        Direction::ClientToServer => 0,
+        Direction::ServerToClient => 1,
+    }
+}
+
+fn file_header(trade_date: u32) -> [u8; FILE_HEADER_SIZE] {
+    let mut header = [0u8; FILE_HEADER_SIZE];
+    header[..8].copy_from_slice(MAGIC);
+    header[8..10].copy_from_slice(&VERSION.to_be_bytes());
+    header[12..].copy_from_slice(&trade_date.to_be_bytes());
+    header
+}
+
+/// 检查文件头，返回交易日期
+fn parse_file_header(header: &[u8; FILE_HEADER_SIZE]) -> WireLogResult<u32> {
+    if &header[..8] != MAGIC {
+        return Err(WireLogError::InvalidHeader(format!("magic {}", hex::encode(&header[..8]))));
+    }
+    let version = BigEndian::read_u16(&header[8..10]);
+    if version != VERSION {
+        return Err(WireLogError::InvalidHeader(format!("unsupported version {}", version)));
+    }
+    Ok(BigEndian::read_u32(&header[12..]))
+}
+
+/// 时间对应的北京时间日期，格式为 YYYYMMDD
+///
+/// 夜间和节假日收到的记录与协议中的交易日不一定相同，写入器收到登录消息后改用其中的 TradeDate
+pub fn trade_date_of(timestamp: Duration) -> u32 {
+    let (year, month, day) = civil_date((timestamp.as_secs() + TRADE_DATE_UTC_OFFSET) / 86_400);
+    (year * 10_000 + month * 100 + day) as u32
+}
+
+/// 某个交易日的日志文件路径
+pub fn log_path<P: AsRef<Path>>(dir: P, prefix: &str, trade_date: u32) -> PathBuf {
+    dir.as_ref().join(format!("{}-{}.{}", prefix, trade_date, EXTENSION))
+}
+
+/// 列出目录中某个前缀的日志文件，按交易日期排序
+pub fn log_files<P: AsRef<Path>>(dir: P, prefix: &str) -> io::Result<Vec<(u32, PathBuf)>> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let trade_date = path.file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_prefix(prefix)?.strip_prefix('-')?.strip_suffix(EXTENSION)?.strip_suffix('.'))
+            .filter(|date| date.len() == 8)
+            .and_then(|date| date.parse().ok());
+        if let Some(trade_date) = trade_date {
+            files.push((trade_date, path));
+        }
+    }
+    files.sort();
+    Ok(files)
+}
@@ -0,0 +1,237 @@
+use std::fs::File;
+use std::io::{BufReader, ErrorKind, Read, Seek, SeekFrom};
+use std::path::Path;
+use std::time::Duration;
+
+use byteorder::{BigEndian, ByteOrder};
+
+use crate::capture::Direction;
+use crate::util::{WireLogError, WireLogResult};
+use crate::wirelog::{parse_file_header, parse_fixed, WireRecord, FILE_HEADER_SIZE, RECORD_FIXED_SIZE};
+
+/// 单条记录的长度上限，超过时认为文件已损坏
+const MAX_RECORD_LEN: usize = 64 * 1024 * 1024;
+
+/// 按顺序读取日志记录，可以按时间或序列号定位
+///
+/// 定位时第一次会扫描全部记录的固定部分建立索引，不读取帧；文件末尾写了一半的记录
+/// 在遍历到时产生一次 `Truncated` 错误后结束
+///
+/// ```ignore
+/// let mut reader = WireLogReader::open("logs/tdgw-20240105.wlog")?;
+/// reader.seek_seq_num(None, Direction::ServerToClient, 120)?;
+/// for record in reader {
+///     let message = record?.decode(&config_manager)?;
+/// }
+/// ```
+#[derive(Debug)]
+pub struct WireLogReader<R> {
+    reader: R,
+    trade_date: u32,
+    /// 下一条记录的位置
+    position: u64,
+    index: Option<Vec<IndexEntry>>,
+    done: bool,
+}
+
+/// 一条完整记录的位置和固定部分
+#[derive(Debug, Clone)]
+struct IndexEntry {
+    offset: u64,
+    /// 记录的总长度，包括开头和末尾的长度字段
+    len: u64,
+    timestamp: Duration,
+    seq_num: u32,
+    direction: Direction,
+    session_id: String,
+}
+
+impl WireLogReader<BufReader<File>> {
+    /// 打开日志文件
+    pub fn open<P: AsRef<Path>>(path: P) -> WireLogResult<Self> {
+        Self::new(BufReader::new(File::open(path)?))
+    }
+}
+
+impl<R: Read + Seek> WireLogReader<R> {
+    /// 读取并检查文件头
+    pub fn new(mut reader: R) -> WireLogResult<Self> {
+        reader.seek(SeekFrom::Start(0))?;
+        let mut header = [0u8; FILE_HEADER_SIZE];
+        reader.read_exact(&mut header).map_err(|e| match e.kind() {
+            ErrorKind::UnexpectedEof => WireLogError::InvalidHeader("file shorter than header".to_string()),
+            _ => WireLogError::IoError(e),
+        })?;
+        let trade_date = parse_file_header(&header)?;
+        Ok(Self { reader, trade_date, position: FILE_HEADER_SIZE as u64, index: None, done: false })
+    }
+
+    /// 文件头中的交易日期
+    pub fn trade_date(&self) -> u32 {
+        self.trade_date
+    }
+
+    /// 回到第一条记录
+    pub fn rewind(&mut self) -> WireLogResult<()> {
+        self.seek_to(FILE_HEADER_SIZE as u64)
+    }
+
+    /// 定位到第一条接收时间不早于 `timestamp` 的记录，没有时定位到末尾
+    pub fn seek_time(&mut self, timestamp: Duration) -> WireLogResult<()> {
+        let offset = self.find(|entry| entry.timestamp >= timestamp)?;
+        self.seek_to(offset)
+    }
+
+    /// 定位到某个方向上第一条序列号不小于 `seq_num` 的记录，可以只查找某个会话；没有时定位到末尾并返回 `false`
+    pub fn seek_seq_num(&mut self, session_id: Option<&str>, direction: Direction, seq_num: u32) -> WireLogResult<bool> {
+        let end = self.valid_len()?;
+        let offset = self.find(|entry| {
+            entry.direction == direction
+                && entry.seq_num >= seq_num
+                && session_id.is_none_or(|session_id| entry.session_id == session_id)
+        })?;
+        self.seek_to(offset)?;
+        Ok(offset < end)
+    }
+
+    /// 最后一条完整记录的末尾位置，文件末尾没有写了一半或长度不合理的记录时等于文件长度
+    pub fn valid_len(&mut self) -> WireLogResult<u64> {
+        Ok(self.index()?.last().map_or(FILE_HEADER_SIZE as u64, |entry| entry.offset + entry.len))
+    }
+
+    /// 第一条满足条件的记录的位置，没有时为最后一条完整记录的末尾
+    fn find(&mut self, predicate: impl Fn(&IndexEntry) -> bool) -> WireLogResult<u64> {
+        let end = self.valid_len()?;
+        Ok(self.index()?.iter().find(|entry| predicate(entry)).map_or(end, |entry| entry.offset))
+    }
+
+    fn seek_to(&mut self, offset: u64) -> WireLogResult<()> {
+        self.reader.seek(SeekFrom::Start(offset))?;
+        self.position = offset;
+        self.done = false;
+        Ok(())
+    }
+
+    /// 扫描全部完整记录，建立索引后回到原来的位置
+    fn index(&mut self) -> WireLogResult<&[IndexEntry]> {
+        if self.index.is_none() {
+            let entries = self.scan();
+            self.reader.seek(SeekFrom::Start(self.position))?;
+            self.index = Some(entries?);
+        }
+        Ok(self.index.as_deref().unwrap_or_default())
+    }
+
+    /// 逐条读取记录的固定部分，遇到写了一半的记录时停止
+    ///
+    /// 长度不合理的记录之后无法再分出记录，例如断电后末尾补零的部分，同样视为写了一半的记录
+    fn scan(&mut self) -> WireLogResult<Vec<IndexEntry>> {
+        let mut entries = Vec::new();
+        let mut offset = FILE_HEADER_SIZE as u64;
+        loop {
+            self.reader.seek(SeekFrom::Start(offset))?;
+            let mut length = [0u8; 4];
+            let body_len = match read_full(&mut self.reader, &mut length)? {
+                4 => match check_len(BigEndian::read_u32(&length) as usize, offset) {
+                    Ok(body_len) => body_len,
+                    Err(_) => break,
+                },
+                _ => break,
+            };
+            let mut fixed = [0u8; RECORD_FIXED_SIZE + 255];
+            let fixed_len = (RECORD_FIXED_SIZE + 255).min(body_len);
+            if read_full(&mut self.reader, &mut fixed[..fixed_len])? < fixed_len {
+                break;
+            }
+            let (timestamp, seq_num, direction, session_len) = parse_fixed(&fixed[..fixed_len], offset)?;
+            let session_id = fixed.get(RECORD_FIXED_SIZE..RECORD_FIXED_SIZE + session_len)
+                .filter(|_| RECORD_FIXED_SIZE + session_len <= body_len)
+                .ok_or_else(|| WireLogError::Corrupted { offset, reason: format!("session id of {} bytes exceeds record", session_len) })?;
+
+            self.reader.seek(SeekFrom::Start(offset + 4 + body_len as u64))?;
+            if read_full(&mut self.reader, &mut length)? < 4 {
+                break;
+            }
+            check_trailer(&length, body_len, offset)?;
+            entries.push(IndexEntry {
+                offset,
+                len: body_len as u64 + 8,
+                timestamp,
+                seq_num,
+                direction,
+                session_id: String::from_utf8_lossy(session_id).into_owned(),
+            });
+            offset += body_len as u64 + 8;
+        }
+        Ok(entries)
+    }
+
+    fn read_record(&mut self) -> WireLogResult<Option<WireRecord>> {
+        let offset = self.position;
+        let mut length = [0u8; 4];
+        match read_full(&mut self.reader, &mut length)? {
+            0 => return Ok(None),
+            4 => {},
+            _ => return Err(WireLogError::Truncated(offset)),
+        }
+        let body_len = check_len(BigEndian::read_u32(&length) as usize, offset)?;
+        let mut body = vec![0u8; body_len + 4];
+        if read_full(&mut self.reader, &mut body)? < body.len() {
+            return Err(WireLogError::Truncated(offset));
+        }
+        check_trailer(&body[body_len..], body_len, offset)?;
+        let record = WireRecord::parse(&body[..body_len], offset)?;
+        self.position += body_len as u64 + 8;
+        Ok(Some(record))
+    }
+}
+
+impl<R: Read + Seek> Iterator for WireLogReader<R> {
+    type Item = WireLogResult<WireRecord>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        match self.read_record() {
+            Ok(Some(record)) => Some(Ok(record)),
+            Ok(None) => {
+                self.done = true;
+                None
+            },
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            },
+        }
+    }
+}
+
+fn check_len(body_len: usize, offset: u64) -> WireLogResult<usize> {
+    if !(RECORD_FIXED_SIZE..=MAX_RECORD_LEN).contains(&body_len) {
+        return Err(WireLogError::Corrupted { offset, reason: format!("record body of {} bytes", body_len) });
+    }
+    Ok(body_len)
+}
+
+fn check_trailer(trailer: &[u8], body_len: usize, offset: u64) -> WireLogResult<()> {
+    let trailer = BigEndian::read_u32(trailer) as usize;
+    if trailer != body_len {
+        return Err(WireLogError::Corrupted { offset, reason: format!("trailing length {} does not match {}", trailer, body_len) });
+    }
+    Ok(())
+}
+
+/// 尽量读满缓冲区，返回读到的字节数，文件结束时少于缓冲区长度
+fn read_full<R: Read>(reader: &mut R, buffer: &mut [u8]) -> WireLogResult<usize> {
+    let mut filled = 0;
+    while filled < buffer.len() {
+        match reader.read(&mut buffer[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == ErrorKind::Interrupted => {},
+            Err(e) => return Err(WireLogError::IoError(e)),
+        }
+    }
+    Ok(filled)
+}
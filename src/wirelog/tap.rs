@@ -0,0 +1,123 @@
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use byteorder::{BigEndian, ByteOrder};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+
+use crate::capture::Direction;
+use crate::capture::stream::MAX_BODY_LENGTH;
+use crate::codec::MessageHeader;
+use crate::wirelog::{WireLogHandle, WireRecord};
+
+/// 挂在连接上的日志记录器，透明地转发读写，并把收发的每一帧交给后台线程追加到日志
+///
+/// 同一个 [`WireLogHandle`] 可以由多个连接共享；帧头部中的消息体长度明显不合理时，
+/// 缓冲区中剩余的字节作为一条记录写入日志，从下一次读写重新分帧。读写的结果与内层连接完全相同，
+/// 写日志失败不影响连接，错误见 [`WireLogHandle::take_error`]
+///
+/// ```ignore
+/// let log = WireLogWriter::open("logs", "tdgw")?.spawn()?;
+/// let stream = TcpStream::connect(gateway).await?;
+/// let mut stream = WireLogTap::client(stream, log.clone(), "S1");
+/// stream.write_all(&logon_frame).await?;
+/// ```
+#[derive(Debug)]
+pub struct WireLogTap<S> {
+    inner: S,
+    log: WireLogHandle,
+    session_id: String,
+    /// 写出的方向，读入的方向与之相反
+    outbound: Direction,
+    read_buffer: Vec<u8>,
+    write_buffer: Vec<u8>,
+}
+
+impl<S> WireLogTap<S> {
+    /// 券商端的连接，写出的帧记为发往网关
+    pub fn client(inner: S, log: WireLogHandle, session_id: impl Into<String>) -> Self {
+        Self::new(inner, log, session_id, Direction::ClientToServer)
+    }
+
+    /// 网关一侧的连接，例如模拟网关，写出的帧记为发往券商端
+    pub fn server(inner: S, log: WireLogHandle, session_id: impl Into<String>) -> Self {
+        Self::new(inner, log, session_id, Direction::ServerToClient)
+    }
+
+    fn new(inner: S, log: WireLogHandle, session_id: impl Into<String>, outbound: Direction) -> Self {
+        Self { inner, log, session_id: session_id.into(), outbound, read_buffer: Vec::new(), write_buffer: Vec::new() }
+    }
+
+    /// 登录后改用登录消息中的会话标识
+    pub fn set_session_id(&mut self, session_id: impl Into<String>) {
+        self.session_id = session_id.into();
+    }
+
+    pub fn get_ref(&self) -> &S {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut S {
+        &mut self.inner
+    }
+
+    /// 取出连接，尚未凑成完整帧的字节不写入日志
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+
+    /// 追加读写的字节，把其中的完整帧交给后台线程
+    fn record(&mut self, outbound: bool, bytes: &[u8]) {
+        let (buffer, direction) = match outbound {
+            true => (&mut self.write_buffer, self.outbound),
+            false => (&mut self.read_buffer, self.outbound.opposite()),
+        };
+        buffer.extend_from_slice(bytes);
+
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+        let mut start = 0;
+        while buffer.len() - start >= MessageHeader::SIZE {
+            let body_length = BigEndian::read_u32(&buffer[start + 8..start + 12]) as usize;
+            let end = match body_length {
+                body_length if body_length > MAX_BODY_LENGTH => buffer.len(),
+                body_length => start + MessageHeader::SIZE + body_length + 4,
+            };
+            if buffer.len() < end {
+                break;
+            }
+            self.log.append(WireRecord::new(timestamp, direction, self.session_id.as_str(), buffer[start..end].to_vec()));
+            start = end;
+        }
+        buffer.drain(..start);
+    }
+}
+
+impl<S: AsyncRead + Unpin> AsyncRead for WireLogTap<S> {
+    fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        let filled = buf.filled().len();
+        let result = Pin::new(&mut self.inner).poll_read(cx, buf);
+        if let Poll::Ready(Ok(())) = result {
+            self.record(false, &buf.filled()[filled..]);
+        }
+        result
+    }
+}
+
+impl<S: AsyncWrite + Unpin> AsyncWrite for WireLogTap<S> {
+    fn poll_write(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        let result = Pin::new(&mut self.inner).poll_write(cx, buf);
+        if let Poll::Ready(Ok(written)) = result {
+            self.record(true, &buf[..written]);
+        }
+        result
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_shutdown(cx)
+    }
+}
@@ -0,0 +1,108 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use crate::util::{WireLogError, WireLogResult};
+use crate::wirelog::reader::WireLogReader;
+use crate::wirelog::{file_header, log_path, parse_file_header, WireRecord, FILE_HEADER_SIZE};
+
+/// 只追加的日志写入器，按交易日切换文件
+///
+/// 追加登录消息（无论方向）时记下其中的 `TradeDate`，之后的记录都归入这个交易日，直到下一条登录消息；
+/// 此前的记录按接收时间的北京时间日期归入交易日，也可以用 `set_trade_date` 直接指定。
+/// 打开已有的文件时检查文件头，并截掉末尾写了一半的记录后继续追加
+///
+/// ```ignore
+/// let mut writer = WireLogWriter::open("logs", "tdgw")?;
+/// writer.append(&WireRecord::new(timestamp, Direction::ServerToClient, "S1", frame))?;
+/// writer.flush()?;
+/// ```
+#[derive(Debug)]
+pub struct WireLogWriter {
+    dir: PathBuf,
+    prefix: String,
+    /// 最近一条登录消息中或指定的交易日，为 `None` 时按记录的接收时间计算
+    trade_date: Option<u32>,
+    /// 当前文件的交易日和写入器
+    current: Option<(u32, BufWriter<File>)>,
+}
+
+impl WireLogWriter {
+    /// 在 `dir` 中写入 `{prefix}-{TradeDate}.wlog`，目录不存在时创建
+    pub fn open<P: AsRef<Path>>(dir: P, prefix: impl Into<String>) -> WireLogResult<Self> {
+        fs::create_dir_all(&dir)?;
+        Ok(Self { dir: dir.as_ref().to_path_buf(), prefix: prefix.into(), trade_date: None, current: None })
+    }
+
+    /// 指定之后记录所属的交易日，为 `None` 时恢复按接收时间计算；下一条登录消息会覆盖这里的设置
+    pub fn set_trade_date(&mut self, trade_date: Option<u32>) {
+        self.trade_date = trade_date;
+    }
+
+    /// 当前写入的文件
+    pub fn current_path(&self) -> Option<PathBuf> {
+        self.current.as_ref().map(|(trade_date, _)| log_path(&self.dir, &self.prefix, *trade_date))
+    }
+
+    /// 追加一条记录，交易日变化时先写完当前文件再切换到新文件
+    pub fn append(&mut self, record: &WireRecord) -> WireLogResult<()> {
+        let bytes = record.encode()?;
+        if let Some(trade_date) = record.logon_trade_date() {
+            self.trade_date = Some(trade_date);
+        }
+        let trade_date = self.trade_date.unwrap_or_else(|| record.trade_date());
+        let writer = match &mut self.current {
+            Some((current, writer)) if *current == trade_date => writer,
+            current => {
+                if let Some((_, writer)) = current {
+                    writer.flush()?;
+                }
+                let file = open_log(&log_path(&self.dir, &self.prefix, trade_date), trade_date)?;
+                &mut current.insert((trade_date, BufWriter::new(file))).1
+            },
+        };
+        writer.write_all(&bytes)?;
+        Ok(())
+    }
+
+    /// 把缓冲的记录写入文件
+    pub fn flush(&mut self) -> WireLogResult<()> {
+        if let Some((_, writer)) = &mut self.current {
+            writer.flush()?;
+        }
+        Ok(())
+    }
+
+    /// 写入文件并等待落盘
+    pub fn sync(&mut self) -> WireLogResult<()> {
+        self.flush()?;
+        if let Some((_, writer)) = &self.current {
+            writer.get_ref().sync_data()?;
+        }
+        Ok(())
+    }
+}
+
+/// 打开或创建某个交易日的文件，定位到最后一条完整记录之后
+fn open_log(path: &Path, trade_date: u32) -> WireLogResult<File> {
+    let mut file = OpenOptions::new().read(true).write(true).create(true).truncate(false).open(path)?;
+    if file.metadata()?.len() < FILE_HEADER_SIZE as u64 {
+        file.set_len(0)?;
+        file.write_all(&file_header(trade_date))?;
+        return Ok(file);
+    }
+
+    let mut header = [0u8; FILE_HEADER_SIZE];
+    file.read_exact(&mut header)?;
+    let existing = parse_file_header(&header)?;
+    if existing != trade_date {
+        return Err(WireLogError::InvalidHeader(format!(
+            "{} belongs to trade date {}, not {}", path.display(), existing, trade_date,
+        )));
+    }
+    // 进程在写记录的过程中退出时，末尾会留下不完整的记录
+    let end = WireLogReader::new(&file)?.valid_len()?;
+    file.set_len(end)?;
+    file.seek(SeekFrom::Start(end))?;
+    Ok(file)
+}
@@ -0,0 +1,192 @@
+//! `#[derive(TdgwMessage)]`：将已有的结构体映射到 XML 中配置的 TDGW 消息
+//!
+//! ```ignore
+//! #[derive(TdgwMessage)]
+//! #[tdgw(msg_type = 58)]
+//! struct Order {
+//!     biz_id: u32,
+//!     #[tdgw(field = "ClOrdID")]
+//!     client_order_id: String,
+//!     #[tdgw(skip)]
+//!     local_note: String,
+//! }
+//! ```
+//!
+//! 未标注 `field` 的成员按 PascalCase 推导消息字段名，例如 `order_qty` -> `OrderQty`，
+//! 其中 `id` 按协议的写法转为 `ID`，例如 `cl_ord_id` -> `ClOrdID`。
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Fields, LitInt, LitStr};
+
+#[proc_macro_derive(TdgwMessage, attributes(tdgw))]
+pub fn derive_tdgw_message(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input).unwrap_or_else(syn::Error::into_compile_error).into()
+}
+
+/// 结构体成员与消息字段的映射
+struct MemberMapping {
+    ident: syn::Ident,
+    ty: syn::Type,
+    /// 映射的消息字段名，`None` 表示跳过
+    field: Option<LitStr>,
+}
+
+/// snake_case 成员名转换为 PascalCase 消息字段名，`id` 按协议的写法转为 `ID`
+fn to_pascal_case(name: &str) -> String {
+    name.trim_start_matches("r#")
+        .split('_')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            if part.eq_ignore_ascii_case("id") {
+                return "ID".to_string();
+            }
+            let mut chars = part.chars();
+            chars.next().map(|c| c.to_ascii_uppercase().to_string() + chars.as_str()).unwrap_or_default()
+        })
+        .collect()
+}
+
+fn parse_msg_type(input: &DeriveInput) -> syn::Result<LitInt> {
+    let mut msg_type = None;
+    for attr in input.attrs.iter().filter(|attr| attr.path().is_ident("tdgw")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("msg_type") {
+                msg_type = Some(meta.value()?.parse::<LitInt>()?);
+                Ok(())
+            } else {
+                Err(meta.error("expected `msg_type = <u32>`"))
+            }
+        })?;
+    }
+    msg_type.ok_or_else(|| syn::Error::new_spanned(&input.ident, "missing `#[tdgw(msg_type = ...)]`"))
+}
+
+fn parse_member(field: &syn::Field) -> syn::Result<MemberMapping> {
+    let ident = field.ident.clone().expect("named field");
+    let mut name = None;
+    let mut skip = false;
+    for attr in field.attrs.iter().filter(|attr| attr.path().is_ident("tdgw")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("field") {
+                name = Some(meta.value()?.parse::<LitStr>()?);
+                Ok(())
+            } else if meta.path.is_ident("skip") {
+                skip = true;
+                Ok(())
+            } else {
+                Err(meta.error("expected `field = \"...\"` or `skip`"))
+            }
+        })?;
+    }
+    if skip && name.is_some() {
+        return Err(syn::Error::new_spanned(field, "`skip` cannot be combined with `field`"));
+    }
+
+    let field_name = if skip {
+        None
+    } else {
+        Some(name.unwrap_or_else(|| LitStr::new(&to_pascal_case(&ident.to_string()), ident.span())))
+    };
+    Ok(MemberMapping { ident, ty: field.ty.clone(), field: field_name })
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let msg_type = parse_msg_type(&input)?;
+    let name = &input.ident;
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(&input.generics, "TdgwMessage cannot be derived for generic structs"));
+    }
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => return Err(syn::Error::new_spanned(name, "TdgwMessage requires a struct with named fields")),
+        },
+        _ => return Err(syn::Error::new_spanned(name, "TdgwMessage can only be derived for structs")),
+    };
+    let members = fields.iter().map(parse_member).collect::<syn::Result<Vec<_>>>()?;
+    let mapped: Vec<_> = members.iter().filter(|member| member.field.is_some()).collect();
+
+    let krate = quote!(::sse_tdgw_binary);
+    let mapping_entries = mapped.iter().map(|member| {
+        let (ident, ty, field) = (&member.ident, &member.ty, member.field.as_ref().unwrap());
+        quote! {
+            #krate::message::FieldMapping {
+                member: stringify!(#ident),
+                field: #field,
+                rust_type: stringify!(#ty),
+                field_types: <#ty as #krate::message::MappedField>::FIELD_TYPES,
+                length: <#ty as #krate::message::MappedField>::LENGTH,
+            }
+        }
+    });
+    let add_fields = mapped.iter().map(|member| {
+        let (ident, field) = (&member.ident, member.field.as_ref().unwrap());
+        quote! {
+            message.add_field(#field.to_string(), #krate::message::MappedField::to_field_value(&self.#ident));
+        }
+    });
+    let read_fields = members.iter().map(|member| {
+        let ident = &member.ident;
+        match &member.field {
+            Some(field) => quote! { #ident: #krate::message::mapped_field(message, #field)? },
+            None => quote! { #ident: ::core::default::Default::default() },
+        }
+    });
+
+    Ok(quote! {
+        impl #krate::message::TdgwMessage for #name {
+            const MSG_TYPE: u32 = #msg_type;
+
+            fn field_mappings() -> &'static [#krate::message::FieldMapping] {
+                const MAPPINGS: &[#krate::message::FieldMapping] = &[#(#mapping_entries),*];
+                MAPPINGS
+            }
+
+            fn to_message(&self, seq_num: u32) -> #krate::message::Message {
+                let mut message = #krate::message::Message::new(#msg_type, seq_num);
+                #(#add_fields)*
+                message
+            }
+
+            fn from_message(message: &#krate::message::Message) -> #krate::util::MessageResult<Self> {
+                if message.msg_type != #msg_type {
+                    return Err(#krate::util::MessageError::UnexpectedMessageType(#msg_type, message.msg_type));
+                }
+                Ok(Self { #(#read_fields),* })
+            }
+        }
+
+        impl ::core::convert::From<&#name> for #krate::message::Message {
+            fn from(value: &#name) -> Self {
+                #krate::message::TdgwMessage::to_message(value, 0)
+            }
+        }
+
+        impl ::core::convert::TryFrom<&#krate::message::Message> for #name {
+            type Error = #krate::util::MessageError;
+
+            fn try_from(message: &#krate::message::Message) -> ::core::result::Result<Self, Self::Error> {
+                <#name as #krate::message::TdgwMessage>::from_message(message)
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_pascal_case() {
+        assert_eq!(to_pascal_case("order_qty"), "OrderQty");
+        assert_eq!(to_pascal_case("price"), "Price");
+        assert_eq!(to_pascal_case("r#type"), "Type");
+        assert_eq!(to_pascal_case("cl_ord_id"), "ClOrdID");
+        assert_eq!(to_pascal_case("biz_id"), "BizID");
+        assert_eq!(to_pascal_case("sender_comp_id"), "SenderCompID");
+    }
+}
@@ -1,7 +1,8 @@
 use sse_tdgw_binary::codec::encoder::MessageEncoder;
 use sse_tdgw_binary::codec::decoder::MessageDecoder;
 use sse_tdgw_binary::config::manager::ConfigManager;
-use sse_tdgw_binary::message::{Message, FieldValue};
+use sse_tdgw_binary::message::{Message, FieldValue, Record};
+use sse_tdgw_binary::util::MessageError;
 
 /// 数组类型编解码测试
 /// 专门测试数组字段的编码和解码功能
@@ -48,35 +49,35 @@ mod array_codec_tests {
         
         // 简单数组数据
         let simple_array_data = vec![
-            vec![
-                FieldValue::U32(1001),
-                FieldValue::Str("ITEM001".to_string()),
-                FieldValue::Float(123.45000), // 123.45000
-            ],
-            vec![
-                FieldValue::U32(1002),
-                FieldValue::Str("ITEM002".to_string()),
-                FieldValue::Float(678.90000), // 678.90000
-            ],
-            vec![
-                FieldValue::U32(1003),
-                FieldValue::Str("ITEM003".to_string()),
-                FieldValue::Float(999.99000), // 999.99000
-            ],
+            Record::from([
+                ("item_id", FieldValue::U32(1001)),
+                ("item_name", FieldValue::Str("ITEM001".to_string())),
+                ("item_price", FieldValue::Float(123.45000)), // 123.45000
+            ]),
+            Record::from([
+                ("item_id", FieldValue::U32(1002)),
+                ("item_name", FieldValue::Str("ITEM002".to_string())),
+                ("item_price", FieldValue::Float(678.90000)), // 678.90000
+            ]),
+            Record::from([
+                ("item_id", FieldValue::U32(1003)),
+                ("item_name", FieldValue::Str("ITEM003".to_string())),
+                ("item_price", FieldValue::Float(999.99000)), // 999.99000
+            ]),
         ];
         
         // 嵌套数组数据
         let nested_array_data = vec![
-            vec![
-                FieldValue::U16(100),
-                FieldValue::U16(2), // sub_count
-                FieldValue::Float(1234.56000), // 1234.56000
-            ],
-            vec![
-                FieldValue::U16(200),
-                FieldValue::U16(3), // sub_count
-                FieldValue::Float(7890.12000), // 7890.12000
-            ],
+            Record::from([
+                ("group_id", FieldValue::U16(100)),
+                ("sub_count", FieldValue::U16(2)),
+                ("sub_items", FieldValue::Float(1234.56000)), // 1234.56000
+            ]),
+            Record::from([
+                ("group_id", FieldValue::U16(200)),
+                ("sub_count", FieldValue::U16(3)),
+                ("sub_items", FieldValue::Float(7890.12000)), // 7890.12000
+            ]),
         ];
         
         // 添加字段
@@ -124,13 +125,13 @@ mod array_codec_tests {
             assert_eq!(array_data.len(), 3, "Simple array length mismatch");
             
             // 验证第一个数组项
-            assert_eq!(array_data[0][0], FieldValue::U32(1001), "First item ID mismatch");
-            if let FieldValue::Str(name) = &array_data[0][1] {
+            assert_eq!(array_data[0]["item_id"], FieldValue::U32(1001), "First item ID mismatch");
+            if let FieldValue::Str(name) = &array_data[0]["item_name"] {
                 assert!(name.starts_with("ITEM001"), "First item name mismatch");
             } else {
                 panic!("Expected string field for item name");
             }
-            assert_eq!(array_data[0][2], FieldValue::Float(123.45000), "First item price mismatch");
+            assert_eq!(array_data[0]["item_price"], FieldValue::Float(123.45000), "First item price mismatch");
         } else {
             panic!("Expected array field for simple_array");
         }
@@ -190,11 +191,11 @@ mod array_codec_tests {
         // 创建大数组（100个元素）
         let mut large_array_data = Vec::new();
         for i in 0..100 {
-            large_array_data.push(vec![
-                FieldValue::U32(i + 1000),
-                FieldValue::Str(format!("ITEM{:03}", i)),
-                FieldValue::Float((i + 1) as f64), // 价格递增
-            ]);
+            large_array_data.push(Record::from([
+                ("item_id", FieldValue::U32(i + 1000)),
+                ("item_name", FieldValue::Str(format!("ITEM{:03}", i))),
+                ("item_price", FieldValue::Float((i + 1) as f64)), // 价格递增
+            ]));
         }
         
         message.add_field("item".to_string(), FieldValue::U16(100));
@@ -223,9 +224,9 @@ mod array_codec_tests {
             assert_eq!(array_data.len(), 100, "Large array length mismatch");
             
             // 验证几个关键元素
-            assert_eq!(array_data[0][0], FieldValue::U32(1000), "First large item ID mismatch");
-            assert_eq!(array_data[99][0], FieldValue::U32(1099), "Last large item ID mismatch");
-            assert_eq!(array_data[50][2], FieldValue::Float(51.0), "Middle large item price mismatch");
+            assert_eq!(array_data[0]["item_id"], FieldValue::U32(1000), "First large item ID mismatch");
+            assert_eq!(array_data[99]["item_id"], FieldValue::U32(1099), "Last large item ID mismatch");
+            assert_eq!(array_data[50]["item_price"], FieldValue::Float(51.0), "Middle large item price mismatch");
         } else {
             panic!("Expected array field for simple_array");
         }
@@ -241,16 +242,16 @@ mod array_codec_tests {
         
         // 创建包含各种数据类型的数组
         let mixed_array_data = vec![
-            vec![
-                FieldValue::U32(u32::MAX),
-                FieldValue::Str("MAX_VAL".to_string()),
-                FieldValue::Float(9999999.99999),
-            ],
-            vec![
-                FieldValue::U32(0),
-                FieldValue::Str("MIN_VAL".to_string()),
-                FieldValue::Float(-9999999.99999),
-            ],
+            Record::from([
+                ("item_id", FieldValue::U32(u32::MAX)),
+                ("item_name", FieldValue::Str("MAX_VAL".to_string())),
+                ("item_price", FieldValue::Float(9999999.99999)),
+            ]),
+            Record::from([
+                ("item_id", FieldValue::U32(0)),
+                ("item_name", FieldValue::Str("MIN_VAL".to_string())),
+                ("item_price", FieldValue::Float(-9999999.99999)),
+            ]),
         ];
         
         message.add_field("item".to_string(), FieldValue::U16(2));
@@ -270,14 +271,62 @@ mod array_codec_tests {
         // 验证极值
         let simple_array = decoded_message.get_field("simple_array").unwrap();
         if let FieldValue::Array(array_data) = simple_array {
-            assert_eq!(array_data[0][0], FieldValue::U32(u32::MAX), "Max U32 value mismatch");
-            assert_eq!(array_data[0][2], FieldValue::Float(9999999.99999), "Max I64 value mismatch");
-            assert_eq!(array_data[1][0], FieldValue::U32(0), "Min U32 value mismatch");
-            assert_eq!(array_data[1][2], FieldValue::Float(-9999999.99999), "Min I64 value mismatch");
+            assert_eq!(array_data[0]["item_id"], FieldValue::U32(u32::MAX), "Max U32 value mismatch");
+            assert_eq!(array_data[0]["item_price"], FieldValue::Float(9999999.99999), "Max I64 value mismatch");
+            assert_eq!(array_data[1]["item_id"], FieldValue::U32(0), "Min U32 value mismatch");
+            assert_eq!(array_data[1]["item_price"], FieldValue::Float(-9999999.99999), "Min I64 value mismatch");
         } else {
             panic!("Expected array field for simple_array");
         }
         
         println!("✓ Array field type consistency test passed");
     }
-}
\ No newline at end of file
+
+    /// 测试数组元素字段名校验
+    #[test]
+    fn test_array_element_name_validation() {
+        let config_manager = create_array_test_config_manager();
+        let mut encoder = MessageEncoder::new(&config_manager);
+
+        // 元素字段按名称编码，与插入顺序无关
+        let mut message = Message::new(2001, 1);
+        message.add_field("item".to_string(), FieldValue::U16(1));
+        message.add_field("simple_array".to_string(), FieldValue::Array(vec![
+            Record::new()
+                .with("item_price", 1.5)
+                .with("item_id", 7u32)
+                .with("item_name", "ITEM007"),
+        ]));
+        message.add_field("nested_array".to_string(), FieldValue::Array(vec![]));
+        let encoded_data = encoder.encode(&message).expect("Failed to encode reordered element");
+        let mut decoder = MessageDecoder::new(&config_manager, &encoded_data);
+        let decoded_message = decoder.decode().unwrap();
+        let element = &decoded_message.get_field("simple_array").unwrap().as_array().unwrap()[0];
+        assert_eq!(element.names().collect::<Vec<_>>(), vec!["item_id", "item_name", "item_price"], "解码后应按结构定义排序");
+        assert_eq!(element["item_id"], FieldValue::U32(7));
+
+        // 缺少字段时报错，不再填充默认值
+        message.add_field("simple_array".to_string(), FieldValue::Array(vec![
+            Record::from([("item_id", FieldValue::U32(7)), ("item_name", FieldValue::from("ITEM007"))]),
+        ]));
+        match encoder.encode(&message) {
+            Err(MessageError::MissingRequiredFields(fields)) => assert_eq!(fields, vec!["simple_array[0].item_price"]),
+            other => panic!("Expected MissingRequiredFields error, got: {:?}", other),
+        }
+
+        // 结构中未定义的字段名
+        message.add_field("simple_array".to_string(), FieldValue::Array(vec![
+            Record::new()
+                .with("item_id", 7u32)
+                .with("item_name", "ITEM007")
+                .with("item_price", 1.5)
+                .with("item_qty", 1u32),
+        ]));
+        match encoder.encode(&message) {
+            Err(MessageError::UnknownField(name)) => assert_eq!(name, "simple_array[0].item_qty"),
+            other => panic!("Expected UnknownField error, got: {:?}", other),
+        }
+
+        println!("✓ Array element name validation test passed");
+    }
+}
@@ -0,0 +1,121 @@
+use sse_tdgw_binary::codec::batch::{decode_frames_parallel, DecodeFrames, Frames};
+use sse_tdgw_binary::codec::encoder::MessageEncoder;
+use sse_tdgw_binary::config::manager::ConfigManager;
+use sse_tdgw_binary::message::{FieldValue, Message};
+use sse_tdgw_binary::util::MessageError;
+
+/// 批量编解码测试
+/// 测试连续缓冲区的批量编码、逐帧解码和并行解码
+#[cfg(test)]
+mod batch_codec_tests {
+    use super::*;
+
+    fn create_test_config_manager() -> ConfigManager {
+        let mut config_manager = ConfigManager::new();
+        let config_xml = r#"
+        <messages>
+            <message type="58" name="NewOrderSingle">
+                <field name="ClOrdID" type="char" length="10" desc="订单编号"/>
+                <field name="Price" type="price" desc="价格"/>
+                <field name="Signature" type="bytes" desc="变长签名">
+                    <length_field name="SignatureLen" type="u8" desc="签名长度"/>
+                </field>
+            </message>
+        </messages>
+        "#;
+        config_manager.load_from_str(config_xml).expect("Failed to load batch test config");
+        config_manager
+    }
+
+    fn create_order(seq_num: u32) -> Message {
+        let mut message = Message::new(58, seq_num);
+        message.add_field("ClOrdID".to_string(), FieldValue::from(format!("ORD{}", seq_num).as_str()));
+        message.add_field("Price".to_string(), FieldValue::Float(10.5));
+        message.add_field("Signature".to_string(), FieldValue::Bytes(vec![0xAB; seq_num as usize % 7]));
+        message
+    }
+
+    /// 测试批量编码及帧索引
+    #[test]
+    fn test_encode_batch() {
+        let config_manager = create_test_config_manager();
+        let mut encoder = MessageEncoder::new(&config_manager);
+
+        let mut invalid = create_order(2);
+        invalid.add_field("Price".to_string(), FieldValue::Float(1e12));
+        let messages = vec![create_order(1), invalid, Message::new(999, 3), create_order(4)];
+        let batch = encoder.encode_batch(&messages);
+
+        assert_eq!(batch.len(), 4);
+        assert!(matches!(batch.error(1), Some(MessageError::ValueExceedsRange(_))));
+        assert!(matches!(batch.error(2), Some(MessageError::UnknownMessageType(999))));
+        assert_eq!(batch.errors().count(), 2);
+        assert!(batch.frame(1).is_none(), "编码失败的消息没有帧");
+
+        // 失败的消息不在缓冲区中留下数据，成功的帧连续存放
+        let first = encoder.encode(&messages[0]).unwrap();
+        let last = encoder.encode(&messages[3]).unwrap();
+        assert_eq!(batch.as_bytes(), [first.as_slice(), last.as_slice()].concat());
+        assert_eq!(batch.frame(3).unwrap(), &last[..]);
+        assert_eq!(batch.frame_range(3), Some(first.len()..first.len() + last.len()));
+        assert_eq!(batch.frames().map(|(index, _)| index).collect::<Vec<_>>(), vec![0, 3]);
+
+        println!("✓ Encode batch test passed");
+    }
+
+    /// 测试逐帧解码时单个帧的错误不影响其他帧
+    #[test]
+    fn test_decode_frames_with_errors() {
+        let config_manager = create_test_config_manager();
+        let mut encoder = MessageEncoder::new(&config_manager);
+        let batch = encoder.encode_batch(&[create_order(1), create_order(2), create_order(3)]);
+
+        let mut buffer = batch.as_bytes().to_vec();
+        let second = batch.frame_range(1).unwrap();
+        buffer[second.start + 14] ^= 0xFF;
+        buffer.extend_from_slice(&batch.frame(0).unwrap()[..20]);
+
+        let offsets: Vec<_> = Frames::new(&buffer).map(|frame| frame.map(|(offset, _)| offset).ok()).collect();
+        assert_eq!(offsets, vec![Some(0), Some(second.start), Some(second.end), None]);
+
+        let decoded: Vec<_> = DecodeFrames::new(&config_manager, &buffer).collect();
+        assert_eq!(decoded.len(), 4);
+        assert_eq!(decoded[0].result.as_ref().unwrap().seq_num, 1);
+        assert!(matches!(decoded[1].result, Err(MessageError::ChecksumError)));
+        assert_eq!(decoded[2].result.as_ref().unwrap().seq_num, 3, "校验和错误之后的帧应继续解码");
+        assert!(matches!(decoded[3].result, Err(MessageError::BodyTooShort)), "末尾不完整的帧应报错");
+        assert_eq!(decoded[3].offset, second.end + batch.frame(2).unwrap().len());
+        assert_eq!(decoded.iter().map(|frame| frame.index).collect::<Vec<_>>(), vec![0, 1, 2, 3]);
+
+        println!("✓ Decode frames with errors test passed");
+    }
+
+    /// 测试并行解码与逐帧解码结果一致
+    #[test]
+    fn test_decode_frames_parallel() {
+        let config_manager = create_test_config_manager();
+        let mut encoder = MessageEncoder::new(&config_manager);
+        let messages: Vec<_> = (0..1000).map(create_order).collect();
+        let mut buffer = encoder.encode_batch(&messages).into_bytes();
+        buffer[12 + 2] ^= 0xFF;
+        buffer.extend_from_slice(&[0, 0, 0]);
+
+        let sequential: Vec<_> = DecodeFrames::new(&config_manager, &buffer).collect();
+        for threads in [1, 3, 8] {
+            let parallel = decode_frames_parallel(&config_manager, &buffer, threads);
+            assert_eq!(parallel.len(), sequential.len(), "{} 个线程时帧数不一致", threads);
+            for (left, right) in parallel.iter().zip(&sequential) {
+                assert_eq!((left.index, left.offset), (right.index, right.offset));
+                match (&left.result, &right.result) {
+                    (Ok(left), Ok(right)) => assert_eq!(left.fields, right.fields),
+                    (Err(left), Err(right)) => assert_eq!(left.to_string(), right.to_string()),
+                    _ => panic!("并行解码结果与逐帧解码不一致: index {}", left.index),
+                }
+            }
+        }
+        assert!(matches!(sequential[0].result, Err(MessageError::ChecksumError)));
+        assert!(matches!(sequential[1000].result, Err(MessageError::HeaderTooShort)));
+
+        println!("✓ Decode frames parallel test passed");
+    }
+}
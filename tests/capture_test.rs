@@ -0,0 +1,344 @@
+use std::io::Cursor;
+use std::time::Duration;
+
+use sse_tdgw_binary::capture::{format_timestamp, CaptureDecoder, CaptureReader, CaptureReport, Direction, LinkType, StreamIssue, TcpSegment};
+use sse_tdgw_binary::codec::encoder::MessageEncoder;
+use sse_tdgw_binary::config::manager::ConfigManager;
+use sse_tdgw_binary::message::{FieldValue, Message};
+use sse_tdgw_binary::util::CaptureError;
+
+mod common;
+use common::{heartbeat, load_config};
+
+/// 抓包解析测试
+/// 测试 pcap 和 pcapng 文件的读取、TCP 流重组、分帧、重传和缺口的识别以及 pcap 子命令
+#[cfg(test)]
+mod capture_tests {
+    use super::*;
+
+    const CLIENT_PORT: u16 = 50123;
+    const SERVER_PORT: u16 = 9126;
+    const SYN: u8 = 0x02;
+    const ACK: u8 = 0x10;
+    const PSH_ACK: u8 = 0x18;
+
+    fn logout(config_manager: &ConfigManager, seq_num: u32, text: &str) -> Vec<u8> {
+        let mut message = Message::new(41, seq_num);
+        message.add_field("SessionStatus".to_string(), FieldValue::U32(0));
+        message.add_field("Text".to_string(), FieldValue::from(text));
+        MessageEncoder::new(config_manager).encode(&message).unwrap()
+    }
+
+    /// 以太网 + IPv4 + TCP 数据包，`from_client` 决定源和目的端口
+    fn tcp_packet(from_client: bool, seq: u32, flags: u8, payload: &[u8]) -> Vec<u8> {
+        let (src, dst) = match from_client {
+            true => ([10, 0, 0, 1], CLIENT_PORT),
+            false => ([10, 0, 0, 2], SERVER_PORT),
+        };
+        let (dst_ip, dst_port) = match from_client {
+            true => ([10, 0, 0, 2], SERVER_PORT),
+            false => ([10, 0, 0, 1], CLIENT_PORT),
+        };
+
+        let mut packet = vec![0u8; 12];
+        packet.extend_from_slice(&0x0800u16.to_be_bytes());
+        packet.extend_from_slice(&[0x45, 0]);
+        packet.extend_from_slice(&((20 + 20 + payload.len()) as u16).to_be_bytes());
+        packet.extend_from_slice(&[0, 1, 0x40, 0, 64, 6, 0, 0]);
+        packet.extend_from_slice(&src);
+        packet.extend_from_slice(&dst_ip);
+        packet.extend_from_slice(&dst.to_be_bytes());
+        packet.extend_from_slice(&dst_port.to_be_bytes());
+        packet.extend_from_slice(&seq.to_be_bytes());
+        packet.extend_from_slice(&[0, 0, 0, 0, 0x50, flags, 0xff, 0xff, 0, 0, 0, 0]);
+        packet.extend_from_slice(payload);
+        packet
+    }
+
+    /// 小端、微秒精度的 pcap 文件
+    fn pcap_file(packets: &[(Duration, Vec<u8>)]) -> Vec<u8> {
+        let mut file = Vec::new();
+        file.extend_from_slice(&0xA1B2C3D4u32.to_le_bytes());
+        file.extend_from_slice(&[2, 0, 4, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+        file.extend_from_slice(&65535u32.to_le_bytes());
+        file.extend_from_slice(&1u32.to_le_bytes());
+        for (timestamp, data) in packets {
+            file.extend_from_slice(&(timestamp.as_secs() as u32).to_le_bytes());
+            file.extend_from_slice(&timestamp.subsec_micros().to_le_bytes());
+            file.extend_from_slice(&(data.len() as u32).to_le_bytes());
+            file.extend_from_slice(&(data.len() as u32).to_le_bytes());
+            file.extend_from_slice(data);
+        }
+        file
+    }
+
+    fn block(block_type: u32, body: &[u8]) -> Vec<u8> {
+        let padded = body.len().div_ceil(4) * 4;
+        let total = (12 + padded) as u32;
+        let mut block = Vec::new();
+        block.extend_from_slice(&block_type.to_be_bytes());
+        block.extend_from_slice(&total.to_be_bytes());
+        block.extend_from_slice(body);
+        block.resize(8 + padded, 0);
+        block.extend_from_slice(&total.to_be_bytes());
+        block
+    }
+
+    /// 大端、纳秒精度的 pcapng 文件，中间夹一个无关的块
+    fn pcapng_file(packets: &[(Duration, Vec<u8>)]) -> Vec<u8> {
+        let mut file = block(0x0A0D0D0A, &[0x1A, 0x2B, 0x3C, 0x4D, 0, 1, 0, 0, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]);
+        // 链路类型 1，if_tsresol = 9
+        file.extend(block(1, &[0, 1, 0, 0, 0, 0, 0xff, 0xff, 0, 9, 0, 1, 9, 0, 0, 0, 0, 0, 0, 0]));
+        file.extend(block(5, &[0; 12]));
+        for (timestamp, data) in packets {
+            let ticks = timestamp.as_nanos() as u64;
+            let mut body = vec![0, 0, 0, 0];
+            body.extend_from_slice(&((ticks >> 32) as u32).to_be_bytes());
+            body.extend_from_slice(&(ticks as u32).to_be_bytes());
+            body.extend_from_slice(&(data.len() as u32).to_be_bytes());
+            body.extend_from_slice(&(data.len() as u32).to_be_bytes());
+            body.extend_from_slice(data);
+            file.extend(block(6, &body));
+        }
+        file
+    }
+
+    fn decode(config_manager: &ConfigManager, file: Vec<u8>) -> CaptureReport {
+        let mut decoder = CaptureDecoder::new(config_manager);
+        for packet in CaptureReader::new(Cursor::new(file)).expect("Failed to read capture header") {
+            decoder.feed(&packet.expect("Failed to read packet"));
+        }
+        decoder.finish()
+    }
+
+    fn at(millis: u64) -> Duration {
+        Duration::from_secs(1_704_418_200) + Duration::from_millis(millis)
+    }
+
+    /// 测试跨报文段的帧、一个报文段中的多个帧以及客户端和网关的识别
+    #[test]
+    fn test_reassembly_across_segments() {
+        let config_manager = load_config();
+        let logout = logout(&config_manager, 1, "bye");
+        let mut two = heartbeat(&config_manager, 2);
+        two.extend(heartbeat(&config_manager, 3));
+
+        // 按握手判断客户端，登出消息拆成两个报文段，两个心跳在同一个报文段中
+        let packets = vec![
+            (at(0), tcp_packet(true, 1000, SYN, &[])),
+            (at(1), tcp_packet(false, 5000, SYN | ACK, &[])),
+            (at(2), tcp_packet(true, 1001, PSH_ACK, &logout[..20])),
+            (at(3), tcp_packet(true, 1021, PSH_ACK, &logout[20..])),
+            (at(4), tcp_packet(false, 5001, PSH_ACK, &two)),
+        ];
+        let report = decode(&config_manager, pcap_file(&packets));
+        assert_eq!(report.packets, 5);
+        assert_eq!(report.skipped, 0);
+        assert!(!report.has_problems(), "不应有问题: {:?}", report.connections[0].events);
+
+        assert_eq!(report.connections.len(), 1);
+        let connection = &report.connections[0];
+        assert_eq!(connection.client.port(), CLIENT_PORT);
+        assert_eq!(connection.server.port(), SERVER_PORT);
+
+        let messages: Vec<_> = connection.messages.iter()
+            .map(|captured| {
+                let message = captured.result.as_ref().unwrap();
+                (captured.timestamp, captured.direction, message.msg_type, message.seq_num)
+            })
+            .collect();
+        assert_eq!(messages, vec![
+            (at(3), Direction::ClientToServer, 41, 1),
+            (at(4), Direction::ServerToClient, 33, 2),
+            (at(4), Direction::ServerToClient, 33, 3),
+        ]);
+        let logout_message = connection.messages[0].result.as_ref().unwrap();
+        assert_eq!(logout_message.get_field("Text").and_then(FieldValue::as_str), Some("bye"));
+        assert_eq!(connection.messages_from(Direction::ServerToClient).count(), 2);
+        assert_eq!(format_timestamp(at(3)), "2024-01-05T01:30:00.003000Z");
+
+        println!("✓ Reassembly across segments test passed");
+    }
+
+    /// 测试重传、乱序和缺口
+    #[test]
+    fn test_retransmission_and_gap() {
+        let config_manager = load_config();
+        let frames: Vec<Vec<u8>> = (1..=4).map(|seq_num| heartbeat(&config_manager, seq_num)).collect();
+        let len = frames[0].len() as u32;
+
+        // 没有握手，端口较小的一方视为网关；第 2 帧重传，第 4 帧先于第 3 帧到达
+        let packets = vec![
+            (at(0), tcp_packet(true, 100, PSH_ACK, &frames[0])),
+            (at(1), tcp_packet(true, 100 + len, PSH_ACK, &frames[1])),
+            (at(2), tcp_packet(true, 100 + len, PSH_ACK, &frames[1])),
+            (at(3), tcp_packet(true, 100 + 3 * len, PSH_ACK, &frames[3])),
+            (at(4), tcp_packet(true, 100 + 2 * len, PSH_ACK, &frames[2])),
+        ];
+        let report = decode(&config_manager, pcap_file(&packets));
+        let connection = &report.connections[0];
+        assert_eq!(connection.server.port(), SERVER_PORT, "端口较小的一方应为网关");
+        let seq_nums: Vec<u32> = connection.messages.iter().map(|captured| captured.result.as_ref().unwrap().seq_num).collect();
+        assert_eq!(seq_nums, vec![1, 2, 3, 4], "重传的帧不应重复，乱序的帧应按序列号重组");
+        assert_eq!(connection.events.len(), 1);
+        assert_eq!(connection.events[0].issue, StreamIssue::Retransmission { bytes: len as usize });
+        assert_eq!(connection.events[0].timestamp, at(2));
+
+        // 第 2 帧只抓到前 5 个字节，其余部分没有抓到
+        let packets = vec![
+            (at(0), tcp_packet(true, 100, PSH_ACK, &frames[0])),
+            (at(1), tcp_packet(true, 100 + len, PSH_ACK, &frames[1][..5])),
+            (at(2), tcp_packet(true, 100 + 2 * len, PSH_ACK, &frames[2])),
+            (at(3), tcp_packet(true, 100 + 3 * len, PSH_ACK, &frames[3])),
+        ];
+        let report = decode(&config_manager, pcap_file(&packets));
+        assert!(report.has_problems());
+        let connection = &report.connections[0];
+        let seq_nums: Vec<u32> = connection.messages.iter().map(|captured| captured.result.as_ref().unwrap().seq_num).collect();
+        assert_eq!(seq_nums, vec![1, 3, 4], "缺口之后应从下一个报文段重新分帧");
+        assert_eq!(connection.events.len(), 1);
+        assert_eq!(connection.events[0].issue, StreamIssue::Gap {
+            expected_seq: 100 + len + 5,
+            seq: 100 + 2 * len,
+            missing: len as usize - 5,
+            discarded: 5,
+        });
+        assert_eq!(connection.events[0].timestamp, at(3), "缺口在抓包结束时报告");
+
+        println!("✓ Retransmission and gap test passed");
+    }
+
+    /// 测试解码失败、不完整的帧和错位的流
+    #[test]
+    fn test_decode_failures_and_truncation() {
+        let config_manager = load_config();
+        let mut corrupted = heartbeat(&config_manager, 1);
+        let last = corrupted.len() - 1;
+        corrupted[last] ^= 0xff;
+        let mut unknown = heartbeat(&config_manager, 2);
+        unknown[3] = 99;
+        let partial = heartbeat(&config_manager, 3);
+
+        let mut payload = corrupted.clone();
+        payload.extend(&unknown);
+        payload.extend(&partial[..10]);
+        let packets = vec![(at(0), tcp_packet(true, 1, PSH_ACK, &payload))];
+        let report = decode(&config_manager, pcap_file(&packets));
+        let connection = &report.connections[0];
+        assert_eq!(connection.failures().count(), 2);
+        let errors: Vec<String> = connection.failures().map(|captured| captured.result.as_ref().unwrap_err().to_string()).collect();
+        assert!(errors[0].contains("hecksum"), "应为校验和错误: {}", errors[0]);
+        assert!(errors[1].contains("99"), "应为未知消息类型: {}", errors[1]);
+        assert_eq!(connection.messages[1].frame, unknown, "应保留原始帧");
+        assert_eq!(connection.events.iter().map(|event| &event.issue).collect::<Vec<_>>(), vec![&StreamIssue::Truncated { bytes: 10 }]);
+
+        // 消息体长度明显不合理时丢弃缓冲区，下一个报文段重新分帧
+        let mut garbage = vec![0xffu8; 16];
+        garbage[..4].copy_from_slice(&[0, 0, 0, 33]);
+        let heartbeat = heartbeat(&config_manager, 4);
+        let packets = vec![
+            (at(0), tcp_packet(false, 1, PSH_ACK, &garbage)),
+            (at(1), tcp_packet(false, 17, PSH_ACK, &heartbeat)),
+        ];
+        let report = decode(&config_manager, pcap_file(&packets));
+        let connection = &report.connections[0];
+        assert_eq!(connection.messages.len(), 1);
+        assert_eq!(connection.messages[0].direction, Direction::ServerToClient);
+        assert!(matches!(&connection.events[0].issue, StreamIssue::Framing { bytes: 16, .. }), "{:?}", connection.events);
+
+        println!("✓ Decode failures and truncation test passed");
+    }
+
+    /// 测试 pcapng 格式、端口过滤以及损坏的文件
+    #[test]
+    fn test_pcapng_and_port_filter() {
+        let config_manager = load_config();
+        let frame = heartbeat(&config_manager, 7);
+        let timestamp = Duration::new(1_704_418_200, 123_456_789);
+        let packets = vec![(timestamp, tcp_packet(false, 1, PSH_ACK, &frame))];
+
+        let file = pcapng_file(&packets);
+        let read: Vec<_> = CaptureReader::new(Cursor::new(file.clone())).unwrap().collect::<Result<_, _>>().unwrap();
+        assert_eq!(read.len(), 1);
+        assert_eq!(read[0].timestamp, timestamp, "应按纳秒精度读取时间");
+        assert_eq!(read[0].link_type, LinkType::Ethernet);
+        assert_eq!(format_timestamp(read[0].timestamp), "2024-01-05T01:30:00.123456Z");
+
+        let report = decode(&config_manager, file.clone());
+        assert_eq!(report.connections[0].messages[0].result.as_ref().unwrap().seq_num, 7);
+
+        let mut decoder = CaptureDecoder::new(&config_manager).with_port(1234);
+        decoder.feed(&read[0]);
+        let report = decoder.finish();
+        assert!(report.connections.is_empty());
+        assert_eq!(report.skipped, 1, "其他端口的数据包应跳过");
+
+        let error = CaptureReader::new(Cursor::new(vec![1, 2, 3, 4, 5])).unwrap_err();
+        assert!(matches!(error, CaptureError::UnknownFormat(_)), "{:?}", error);
+        let mut truncated = pcap_file(&packets);
+        truncated.truncate(truncated.len() - 3);
+        let results: Vec<_> = CaptureReader::new(Cursor::new(truncated)).unwrap().collect();
+        assert!(matches!(results.as_slice(), [Err(CaptureError::Truncated(_))]), "{:?}", results);
+
+        // 没有原始长度的 simple packet block 报错而不是越界
+        let mut simple = pcapng_file(&[]);
+        simple.extend(block(3, &[]));
+        let results: Vec<_> = CaptureReader::new(Cursor::new(simple)).unwrap().collect();
+        assert!(matches!(results.as_slice(), [Err(CaptureError::InvalidBlock(_))]), "{:?}", results);
+
+        // 秒级精度、if_tsoffset 接近上限的接口，时间戳溢出时报错
+        let mut overflow = block(0x0A0D0D0A, &[0x1A, 0x2B, 0x3C, 0x4D, 0, 1, 0, 0, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]);
+        let mut interface = vec![0, 1, 0, 0, 0, 0, 0xff, 0xff, 0, 9, 0, 1, 0, 0, 0, 0, 0, 14, 0, 8];
+        interface.extend_from_slice(&i64::MAX.to_be_bytes());
+        interface.extend_from_slice(&[0; 4]);
+        overflow.extend(block(1, &interface));
+        let mut body = vec![0, 0, 0, 0, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff];
+        body.extend_from_slice(&[0; 8]);
+        overflow.extend(block(6, &body));
+        let results: Vec<_> = CaptureReader::new(Cursor::new(overflow)).unwrap().collect();
+        assert!(matches!(results.as_slice(), [Err(CaptureError::InvalidBlock(_))]), "{:?}", results);
+
+        // TCP 头部固定部分不完整的数据包无法解析
+        let packet = tcp_packet(true, 1, PSH_ACK, &[]);
+        assert!(TcpSegment::parse(LinkType::Ethernet, &packet).is_some());
+        assert!(TcpSegment::parse(LinkType::Ethernet, &packet[..14 + 20 + 13]).is_none());
+
+        println!("✓ Pcapng and port filter test passed");
+    }
+
+    /// 测试 pcap 子命令
+    #[cfg(feature = "cli")]
+    #[test]
+    fn test_pcap_command() {
+        use std::process::Command;
+
+        let config_manager = load_config();
+        let mut payload = heartbeat(&config_manager, 1);
+        payload.extend(&heartbeat(&config_manager, 2)[..8]);
+        let packets = vec![
+            (at(0), tcp_packet(true, 1, PSH_ACK, &logout(&config_manager, 1, "bye"))),
+            (at(5), tcp_packet(false, 1, PSH_ACK, &payload)),
+        ];
+        let path = std::env::temp_dir().join(format!("tdgw-capture-test-{}.pcap", std::process::id()));
+        std::fs::write(&path, pcap_file(&packets)).unwrap();
+
+        let output = Command::new(env!("CARGO_BIN_EXE_tdgw"))
+            .args(["pcap", "-c", common::CONFIG, path.to_str().unwrap()])
+            .output()
+            .expect("Failed to start tdgw");
+        std::fs::remove_file(&path).unwrap();
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        println!("{}{}", stdout, stderr);
+
+        assert_eq!(output.status.code(), Some(1), "有不完整的帧时退出码应为 1");
+        let lines: Vec<&str> = stdout.lines().collect();
+        assert_eq!(lines[0], format!("# 10.0.0.1:{} -> 10.0.0.2:{}", CLIENT_PORT, SERVER_PORT));
+        assert!(lines[1].starts_with("2024-01-05T01:30:00.000000Z -> MsgType=41|"), "{}", lines[1]);
+        assert!(lines[2].starts_with("2024-01-05T01:30:00.005000Z <- MsgType=33|"), "{}", lines[2]);
+        assert!(stderr.contains("capture ended inside a frame, 8 bytes left"), "{}", stderr);
+        assert!(stderr.contains("2 packets (0 skipped), 1 connections, 2 messages, 1 stream issues, 0 decode failures"), "{}", stderr);
+
+        println!("✓ Pcap command test passed");
+    }
+}
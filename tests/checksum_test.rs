@@ -0,0 +1,160 @@
+use std::sync::Arc;
+
+use sse_tdgw_binary::codec::checksum::{ChecksumAlgorithm, ChecksumMode, ChecksumState, NoChecksum, Sum8};
+use sse_tdgw_binary::codec::batch::DecodeFrames;
+use sse_tdgw_binary::codec::decoder::MessageDecoder;
+use sse_tdgw_binary::codec::encoder::MessageEncoder;
+use sse_tdgw_binary::codec::SharedDecoder;
+use sse_tdgw_binary::config::manager::ConfigManager;
+use sse_tdgw_binary::message::{FieldValue, Message};
+use sse_tdgw_binary::util::MessageError;
+
+/// 校验和测试
+/// 测试可替换的校验和算法、增量计算以及解码时的校验方式
+#[cfg(test)]
+mod checksum_tests {
+    use super::*;
+
+    /// 测试用的异或校验
+    #[derive(Debug)]
+    struct Xor8;
+
+    impl ChecksumAlgorithm for Xor8 {
+        fn update(&self, state: u32, data: &[u8]) -> u32 {
+            data.iter().fold(state as u8, |acc, byte| acc ^ byte) as u32
+        }
+    }
+
+    fn create_test_config_manager() -> ConfigManager {
+        let mut config_manager = ConfigManager::new();
+        let config_xml = r#"
+        <messages>
+            <message type="40" name="Logon">
+                <field name="SenderCompID" type="char" length="32" desc="发送方代码"/>
+                <field name="HeartBtInt" type="u16" desc="心跳间隔（秒）"/>
+            </message>
+        </messages>
+        "#;
+        config_manager.load_from_str(config_xml).expect("Failed to load checksum test config");
+        config_manager
+    }
+
+    fn create_logon() -> Message {
+        let mut message = Message::new(40, 1);
+        message.add_field("SenderCompID".to_string(), FieldValue::from("SENDER"));
+        message.add_field("HeartBtInt".to_string(), FieldValue::U16(30));
+        message
+    }
+
+    fn trailer(frame: &[u8]) -> u32 {
+        u32::from_be_bytes(frame[frame.len() - 4..].try_into().unwrap())
+    }
+
+    /// 测试默认算法与逐字节累加一致
+    #[test]
+    fn test_default_sum8() {
+        let config_manager = create_test_config_manager();
+        let frame = MessageEncoder::new(&config_manager).encode(&create_logon()).unwrap();
+        let body = &frame[..frame.len() - 4];
+
+        let expected = body.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte)) as u32;
+        assert_eq!(trailer(&frame), expected);
+        assert_eq!(config_manager.checksum().compute(body), expected);
+
+        // 分段累加与一次计算结果相同
+        let mut state = ChecksumState::new(&Sum8);
+        let (header, rest) = body.split_at(12);
+        state.update(header);
+        state.update(rest);
+        assert_eq!(state.finish(), expected);
+
+        println!("✓ Default Sum8 test passed");
+    }
+
+    /// 测试替换校验和算法
+    #[test]
+    fn test_custom_algorithm() {
+        let mut config_manager = create_test_config_manager();
+        config_manager.set_checksum(Xor8);
+        let frame = MessageEncoder::new(&config_manager).encode(&create_logon()).unwrap();
+
+        assert_eq!(trailer(&frame), Xor8.compute(&frame[..frame.len() - 4]), "编码应使用配置的算法");
+        let message = MessageDecoder::new(&config_manager, &frame).decode().expect("相同算法应能解码");
+        assert_eq!(message.get_u16("HeartBtInt").unwrap(), 30);
+
+        let default_manager = create_test_config_manager();
+        let result = MessageDecoder::new(&default_manager, &frame).decode();
+        assert!(matches!(result, Err(MessageError::ChecksumError)), "默认算法应拒绝异或校验的帧");
+
+        println!("✓ Custom algorithm test passed");
+    }
+
+    /// 测试关闭校验和
+    #[test]
+    fn test_no_checksum() {
+        let mut config_manager = create_test_config_manager();
+        config_manager.set_checksum(NoChecksum);
+        let mut frame = MessageEncoder::new(&config_manager).encode(&create_logon()).unwrap();
+        assert_eq!(trailer(&frame), 0, "关闭校验和时写入 0");
+
+        let len = frame.len();
+        frame[len - 1] = 0x5A;
+        let decoder = SharedDecoder::new(Arc::new(config_manager));
+        assert!(decoder.decode(&frame).is_ok(), "关闭校验和时不校验帧尾");
+        assert_eq!(decoder.view(&frame).unwrap().get_str("SenderCompID").unwrap(), "SENDER");
+
+        println!("✓ No checksum test passed");
+    }
+
+    /// 测试严格、告警和跳过三种校验方式
+    #[test]
+    fn test_checksum_modes() {
+        let config_manager = create_test_config_manager();
+        let mut frame = MessageEncoder::new(&config_manager).encode(&create_logon()).unwrap();
+        let len = frame.len();
+        frame[len - 1] ^= 0xFF;
+
+        let strict = MessageDecoder::new(&config_manager, &frame).decode();
+        assert!(matches!(strict, Err(MessageError::ChecksumError)), "默认严格校验");
+
+        let warned = MessageDecoder::new(&config_manager, &frame).with_checksum_mode(ChecksumMode::Warn).decode().unwrap();
+        assert!(warned.checksum_mismatch, "告警方式应标记校验和不一致");
+        assert_eq!(warned.get_str("SenderCompID").unwrap(), "SENDER");
+
+        let skipped = MessageDecoder::new(&config_manager, &frame).with_checksum_mode(ChecksumMode::Skip).decode().unwrap();
+        assert!(!skipped.checksum_mismatch, "跳过校验时不标记");
+
+        frame[len - 1] ^= 0xFF;
+        let valid = MessageDecoder::new(&config_manager, &frame).with_checksum_mode(ChecksumMode::Warn).decode().unwrap();
+        assert!(!valid.checksum_mismatch, "校验和正确时不标记");
+
+        println!("✓ Checksum modes test passed");
+    }
+
+    /// 测试批量解码和共享解码器使用告警方式
+    #[test]
+    fn test_checksum_modes_batch() {
+        let config_manager = create_test_config_manager();
+        let messages: Vec<_> = (1..=4).map(|seq_num| {
+            let mut message = create_logon();
+            message.seq_num = seq_num;
+            message
+        }).collect();
+        let batch = MessageEncoder::new(&config_manager).encode_batch(&messages);
+        let mut buffer = batch.as_bytes().to_vec();
+        let second = batch.frame_range(1).unwrap();
+        buffer[second.end - 1] ^= 0xFF;
+
+        let decoded: Vec<_> = DecodeFrames::new(&config_manager, &buffer).with_checksum_mode(ChecksumMode::Warn).collect();
+        let flags: Vec<_> = decoded.iter().map(|frame| frame.result.as_ref().unwrap().checksum_mismatch).collect();
+        assert_eq!(flags, vec![false, true, false, false]);
+
+        let decoder = SharedDecoder::new(Arc::new(config_manager)).with_checksum_mode(ChecksumMode::Warn);
+        let parallel = decoder.decode_batch_parallel(&buffer, 2);
+        assert_eq!(parallel.iter().map(|frame| frame.result.as_ref().unwrap().checksum_mismatch).collect::<Vec<_>>(), flags);
+        assert!(decoder.decode(&buffer[second.clone()]).unwrap().checksum_mismatch);
+        assert!(decoder.view(&buffer[second]).is_ok(), "告警方式下视图不校验校验和");
+
+        println!("✓ Checksum modes batch test passed");
+    }
+}
@@ -0,0 +1,182 @@
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+use sse_tdgw_binary::codec::encoder::MessageEncoder;
+use sse_tdgw_binary::config::manager::ConfigManager;
+use sse_tdgw_binary::message::{FieldDiff, FieldValue, Message, Record};
+use sse_tdgw_binary::util::ConfigError;
+
+mod common;
+use common::{load_config, CONFIG};
+
+/// 命令行工具测试
+/// 测试 tdgw 的 decode、encode、validate、list 和 diff 子命令，以及它们使用的配置检查和字段比较
+#[cfg(test)]
+mod cli_tests {
+    use super::*;
+
+    fn tdgw(args: &[&str], stdin: Option<&str>) -> Output {
+        let mut child = Command::new(env!("CARGO_BIN_EXE_tdgw"))
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .expect("Failed to start tdgw");
+        let input = stdin.unwrap_or_default().to_string();
+        let mut pipe = child.stdin.take().unwrap();
+        pipe.write_all(input.as_bytes()).unwrap();
+        drop(pipe);
+        child.wait_with_output().unwrap()
+    }
+
+    fn stdout(output: &Output) -> String {
+        String::from_utf8_lossy(&output.stdout).into_owned()
+    }
+
+    fn create_heartbeat_frame(seq_num: u32) -> String {
+        let config_manager = load_config();
+        hex::encode(MessageEncoder::new(&config_manager).encode(&Message::new(33, seq_num)).unwrap())
+    }
+
+    /// 测试 JSON 和 YAML 编码后再解码
+    #[test]
+    fn test_encode_decode() {
+        let json = r#"{"msg_type": 40, "seq_num": 1, "fields": {"SenderCompID": "S1", "TargetCompID": "T1", "HeartBtInt": 30, "PrtcVersion": "1.0", "TradeDate": 20240105, "QSize": 100}}"#;
+        let output = tdgw(&["encode", "-c", CONFIG], Some(json));
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        let frame = stdout(&output).trim().to_string();
+        assert!(frame.starts_with("00000028"), "消息类型 40: {}", frame);
+
+        let output = tdgw(&["decode", "-c", CONFIG, &frame, "-o", "line"], None);
+        assert_eq!(
+            stdout(&output).trim(),
+            "MsgType=40|MsgName=Logon|SeqNum=1|SenderCompID=S1|TargetCompID=T1|HeartBtInt=30|PrtcVersion=1.0|TradeDate=2024-01-05|QSize=100|"
+        );
+
+        let output = tdgw(&["decode", "-c", CONFIG, "-", "-o", "pretty"], Some(&frame));
+        assert!(stdout(&output).starts_with("Logon (40) seq=1\n"), "{}", stdout(&output));
+        let output = tdgw(&["decode", "-c", CONFIG, &frame, "-o", "json"], None);
+        let value: serde_json::Value = serde_json::from_str(&stdout(&output)).unwrap();
+        assert_eq!(value["fields"]["QSize"], 100);
+
+        // YAML 的多个文档依次编码，多个帧一起解码
+        let yaml = "msg_type: 33\nseq_num: 2\n---\n- msg_type: 33\n  seq_num: 3\n- msg_type: 33\n  seq_num: 4\n";
+        let output = tdgw(&["encode", "-c", CONFIG, "-i", "yaml"], Some(yaml));
+        let frames: Vec<_> = stdout(&output).lines().map(str::to_string).collect();
+        assert_eq!(frames, vec![create_heartbeat_frame(2), create_heartbeat_frame(3), create_heartbeat_frame(4)]);
+        let output = tdgw(&["decode", "-c", CONFIG, &frames.concat(), "-o", "line"], None);
+        assert_eq!(stdout(&output).lines().count(), 3);
+
+        let output = tdgw(&["decode", "-c", CONFIG, "-i", "base64", "AAAAIQAAAAIAAAAAAAAAIw=="], None);
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+        assert!(stdout(&output).starts_with("Heartbeat (33) seq=2"));
+
+        println!("✓ Encode decode test passed");
+    }
+
+    /// 测试错误帧的退出码和带标注的输出
+    #[test]
+    fn test_decode_errors() {
+        let mut frame = create_heartbeat_frame(5);
+        frame.replace_range(frame.len() - 2.., "00");
+
+        let output = tdgw(&["decode", "-c", CONFIG, &frame], None);
+        assert!(!output.status.success());
+        assert!(String::from_utf8_lossy(&output.stderr).contains("Checksum error"));
+
+        let output = tdgw(&["decode", "-c", CONFIG, &frame, "--checksum", "warn", "-o", "line"], None);
+        assert!(output.status.success());
+        assert!(String::from_utf8_lossy(&output.stderr).contains("checksum mismatch"));
+
+        let output = tdgw(&["decode", "-c", CONFIG, &frame, "-o", "annotated"], None);
+        assert!(!output.status.success(), "校验和错误时退出码不为 0");
+        assert!(stdout(&output).contains("Checksum"), "{}", stdout(&output));
+        assert!(stdout(&output).contains("^^ ^^ ^^ ^^"), "{}", stdout(&output));
+
+        let output = tdgw(&["decode", "-c", CONFIG, "zz"], None);
+        assert!(!output.status.success());
+        assert!(String::from_utf8_lossy(&output.stderr).contains("invalid hex input"));
+
+        println!("✓ Decode errors test passed");
+    }
+
+    /// 测试配置检查和消息列表
+    #[test]
+    fn test_validate_and_list() {
+        assert!(load_config().validate().is_empty(), "sse-message.xml 没有问题");
+        let output = tdgw(&["validate", "-c", CONFIG], None);
+        assert!(output.status.success());
+        assert!(stdout(&output).contains("0 problems"));
+
+        let mut config_manager = ConfigManager::new();
+        config_manager.load_from_str(r#"
+        <messages>
+            <message type="1" name="Broken">
+                <field name="Text" type="char"/>
+                <field name="Items" type="array">
+                    <length_field name="NoItems" type="i64"/>
+                    <struct>
+                        <field name="ItemID" type="u32"/>
+                    </struct>
+                </field>
+                <extension biz_id="100">
+                    <field name="Text" type="u8"/>
+                </extension>
+            </message>
+        </messages>
+        "#).unwrap();
+        let errors: Vec<_> = config_manager.validate().iter().map(ToString::to_string).collect();
+        assert_eq!(errors, vec![
+            "Invalid definition in message 1 (Broken): char field 'Text' has no length",
+            "Invalid definition in message 1 (Broken): length field 'NoItems' of 'Items' must be u8, u16 or u32, got I64",
+            "Invalid definition in message 1 (Broken): extensions are defined but the message has no BizID field",
+            "Invalid definition in message 1 (Broken): duplicate field 'Text (BizID 100)'",
+        ]);
+        assert!(matches!(config_manager.validate()[0], ConfigError::InvalidDefinition(..)));
+
+        let output = tdgw(&["list", "-c", CONFIG], None);
+        let text = stdout(&output);
+        assert!(text.lines().any(|line| line.trim_start().starts_with("58  NewOrderSingle")), "{}", text);
+        assert!(text.contains("BizID 300070: DividendSelect"), "{}", text);
+
+        let output = tdgw(&["validate", "-c", "missing.xml"], None);
+        assert!(!output.status.success());
+
+        println!("✓ Validate and list test passed");
+    }
+
+    /// 测试逐字段比较
+    #[test]
+    fn test_diff() {
+        let mut left = Message::new(58, 1);
+        left.add_field("ClOrdID".to_string(), FieldValue::from("ORD001"));
+        left.add_field("Parties".to_string(), FieldValue::Array(vec![
+            Record::new().with("PartyID", "P001"),
+            Record::new().with("PartyID", "P002"),
+        ]));
+        let mut right = left.clone();
+        right.seq_num = 2;
+        right.add_field("Parties".to_string(), FieldValue::Array(vec![Record::new().with("PartyID", "P009")]));
+        right.add_field("Text".to_string(), FieldValue::from("x"));
+
+        assert!(left.diff(&left.clone()).is_empty());
+        let diffs = left.diff(&right);
+        assert_eq!(diffs, vec![
+            FieldDiff { path: "Parties[0].PartyID".to_string(), left: Some("P001".into()), right: Some("P009".into()) },
+            FieldDiff { path: "Parties[1].PartyID".to_string(), left: Some("P002".into()), right: None },
+            FieldDiff { path: "Text".to_string(), left: None, right: Some("x".into()) },
+        ]);
+        assert_eq!(diffs[1].to_string(), "Parties[1].PartyID: P002 -> <missing>");
+
+        let output = tdgw(&["diff", "-c", CONFIG, &create_heartbeat_frame(1), &create_heartbeat_frame(1)], None);
+        assert!(output.status.success());
+        assert_eq!(stdout(&output).trim(), "frames are identical");
+
+        let output = tdgw(&["diff", "-c", CONFIG, &create_heartbeat_frame(1), &create_heartbeat_frame(2), "--seq-num"], None);
+        assert!(!output.status.success(), "有差异时退出码为 1");
+        assert_eq!(stdout(&output).trim(), "SeqNum: 1 -> 2");
+
+        println!("✓ Diff test passed");
+    }
+}
@@ -1,7 +1,8 @@
 use sse_tdgw_binary::codec::encoder::MessageEncoder;
 use sse_tdgw_binary::codec::decoder::MessageDecoder;
 use sse_tdgw_binary::config::manager::ConfigManager;
-use sse_tdgw_binary::message::{Message, FieldValue};
+use sse_tdgw_binary::message::{Message, FieldValue, Record};
+use sse_tdgw_binary::util::MessageError;
 
 /// 编解码集成测试
 /// 测试所有字段类型的编码和解码，确保往返一致性
@@ -87,7 +88,7 @@ mod codec_tests {
         // 验证每个字段
         for (field_name, original_value) in &original_message.fields {
             let decoded_value = decoded_message.get_field(field_name)
-                .expect(&format!("Field '{}' not found in decoded message", field_name));
+                .unwrap_or_else(|| panic!("Field '{}' not found in decoded message", field_name));
             
             assert_eq!(decoded_value, original_value, 
                 "Field '{}' value mismatch: expected {:?}, got {:?}", 
@@ -132,6 +133,7 @@ mod codec_tests {
 
     /// 测试最大值
     #[test]
+    #[allow(clippy::excessive_precision)]
     fn test_maximum_values() {
         let config_manager = create_test_config_manager();
         let mut message = Message::new(1001, u32::MAX);
@@ -238,6 +240,232 @@ mod codec_tests {
         println!("✓ Special string values test passed");
     }
 
+    /// 测试原始字节字段（定长与变长）
+    #[test]
+    fn test_bytes_field_roundtrip() {
+        let mut config_manager = ConfigManager::new();
+        let config_xml = r#"
+        <messages>
+            <message type="1002" name="BytesTestMessage">
+                <field name="field_reserved" type="bytes" length="6" desc="定长保留区"/>
+                <field name="field_signature" type="bytes" desc="变长签名">
+                    <length_field name="SignatureLen" type="u16" desc="签名长度"/>
+                </field>
+                <field name="field_u32" type="u32" desc="U32字段"/>
+            </message>
+        </messages>
+        "#;
+        config_manager.load_from_str(config_xml).expect("Failed to load bytes test config");
+
+        // 包含空格、0和非UTF-8字节，确保不会被裁剪或校验
+        let reserved = vec![0x20, 0x00, 0xFF, 0xFE, 0x00, 0x20];
+        let signature = vec![0xDE, 0xAD, 0xBE, 0xEF, 0x00, 0x80, 0x20];
+
+        let mut message = Message::new(1002, 42);
+        message.add_field("field_reserved".to_string(), FieldValue::Bytes(reserved.clone()));
+        message.add_field("field_signature".to_string(), FieldValue::Bytes(signature.clone()));
+        message.add_field("field_u32".to_string(), FieldValue::U32(7));
+
+        let mut encoder = MessageEncoder::new(&config_manager);
+        let encoded_data = encoder.encode(&message).expect("Failed to encode bytes message");
+
+        // 6字节定长 + 2字节长度 + 7字节签名 + 4字节U32
+        assert_eq!(encoded_data.len(), 12 + 6 + 2 + 7 + 4 + 4);
+
+        let mut decoder = MessageDecoder::new(&config_manager, &encoded_data);
+        let decoded_message = decoder.decode().expect("Failed to decode bytes message");
+
+        assert_eq!(decoded_message.get_field("field_reserved").unwrap().as_bytes().unwrap(), &reserved[..]);
+        assert_eq!(decoded_message.get_field("field_signature").unwrap().as_bytes().unwrap(), &signature[..]);
+        assert_eq!(decoded_message.get_field("field_u32").unwrap().as_u32().unwrap(), 7);
+
+        // 再次编码应得到完全相同的字节
+        let reencoded_data = encoder.encode(&decoded_message).expect("Failed to re-encode bytes message");
+        assert_eq!(reencoded_data, encoded_data);
+
+        // 超过定长的字节应当被拒绝而不是截断
+        let mut too_long = Message::new(1002, 43);
+        too_long.add_field("field_reserved".to_string(), FieldValue::Bytes(vec![1; 7]));
+        assert!(encoder.encode(&too_long).is_err(), "Bytes longer than fixed length should be rejected");
+
+        println!("✓ Bytes field roundtrip test passed");
+    }
+
+    /// 测试有符号整数字段及整数拓宽
+    #[test]
+    fn test_signed_integer_fields() {
+        let mut config_manager = ConfigManager::new();
+        let config_xml = r#"
+        <messages>
+            <message type="1003" name="SignedTestMessage">
+                <field name="field_i8" type="i8" desc="I8字段"/>
+                <field name="field_i16" type="i16" desc="I16字段"/>
+                <field name="field_i32" type="i32" desc="I32字段"/>
+                <field name="field_u32" type="u32" desc="U32字段"/>
+                <field name="field_i64" type="i64" desc="I64字段"/>
+            </message>
+        </messages>
+        "#;
+        config_manager.load_from_str(config_xml).expect("Failed to load signed test config");
+
+        let mut message = Message::new(1003, 1);
+        message.add_field("field_i8".to_string(), FieldValue::I8(i8::MIN));
+        message.add_field("field_i16".to_string(), FieldValue::I16(-12345));
+        message.add_field("field_i32".to_string(), FieldValue::I32(i32::MAX));
+        message.add_field("field_u32".to_string(), FieldValue::U8(200)); // U8 值拓宽到 U32 字段
+        message.add_field("field_i64".to_string(), FieldValue::U32(u32::MAX)); // U32 值拓宽到 I64 字段
+
+        let mut encoder = MessageEncoder::new(&config_manager);
+        let encoded_data = encoder.encode(&message).expect("Failed to encode signed message");
+        assert_eq!(encoded_data.len(), 12 + 1 + 2 + 4 + 4 + 8 + 4);
+
+        let mut decoder = MessageDecoder::new(&config_manager, &encoded_data);
+        let decoded_message = decoder.decode().expect("Failed to decode signed message");
+
+        assert_eq!(decoded_message.get_field("field_i8").unwrap().as_i8().unwrap(), i8::MIN);
+        assert_eq!(decoded_message.get_field("field_i16").unwrap().as_i16().unwrap(), -12345);
+        assert_eq!(decoded_message.get_field("field_i32").unwrap().as_i32().unwrap(), i32::MAX);
+        assert_eq!(decoded_message.get_field("field_u32").unwrap().as_u32().unwrap(), 200);
+        assert_eq!(decoded_message.get_field("field_i64").unwrap().as_i64().unwrap(), u32::MAX as i64);
+
+        // 收窄或改变符号的转换只在取值范围内才被接受
+        let mut narrowing = Message::new(1003, 2);
+        narrowing.add_field("field_i8".to_string(), FieldValue::I16(1));
+        assert!(encoder.encode(&narrowing).is_ok(), "In-range I16 value should be accepted for I8 field");
+        narrowing.add_field("field_i8".to_string(), FieldValue::I16(128));
+        assert!(matches!(encoder.encode(&narrowing), Err(MessageError::ValueExceedsRange(_))),
+            "Out-of-range I16 value should be rejected for I8 field");
+
+        let mut sign_change = Message::new(1003, 3);
+        sign_change.add_field("field_u32".to_string(), FieldValue::I8(-1));
+        assert!(matches!(encoder.encode(&sign_change), Err(MessageError::ValueExceedsRange(_))),
+            "Negative I8 value should be rejected for U32 field");
+
+        println!("✓ Signed integer fields test passed");
+    }
+
+    /// 测试数值类型自动转换
+    #[test]
+    fn test_numeric_coercion() {
+        let config_manager = create_test_config_manager();
+        let mut message = Message::new(1001, 1);
+
+        // 不关心线上宽度，直接使用常见的 Rust 类型或字符串
+        message.add_field("field_u8".to_string(), 7u32.into());
+        message.add_field("field_u16".to_string(), 30u32.into());
+        message.add_field("field_u32".to_string(), "1000".into());
+        message.add_field("field_u64".to_string(), 42i64.into());
+        message.add_field("field_i64".to_string(), (-5i32).into());
+        message.add_field("field_char".to_string(), "HELLO".into());
+        message.add_field("field_price".to_string(), "10.5".into());
+        message.add_field("field_quantity".to_string(), 100u32.into());
+        message.add_field("field_amount".to_string(), 1050.0.into());
+        message.add_field("field_date".to_string(), "20231201".into());
+        message.add_field("field_ntime".to_string(), 930000000000i64.into());
+
+        let mut encoder = MessageEncoder::new(&config_manager);
+        let encoded_data = encoder.encode(&message).expect("Failed to encode coerced message");
+
+        let mut decoder = MessageDecoder::new(&config_manager, &encoded_data);
+        let decoded_message = decoder.decode().expect("Failed to decode coerced message");
+
+        assert_eq!(decoded_message.get_field("field_u8").unwrap(), &FieldValue::U8(7));
+        assert_eq!(decoded_message.get_field("field_u16").unwrap(), &FieldValue::U16(30));
+        assert_eq!(decoded_message.get_field("field_u32").unwrap(), &FieldValue::U32(1000));
+        assert_eq!(decoded_message.get_field("field_u64").unwrap(), &FieldValue::U64(42));
+        assert_eq!(decoded_message.get_field("field_i64").unwrap(), &FieldValue::I64(-5));
+        assert_eq!(decoded_message.get_field("field_price").unwrap(), &FieldValue::Float(10.5));
+        assert_eq!(decoded_message.get_field("field_quantity").unwrap(), &FieldValue::Float(100.0));
+        assert_eq!(decoded_message.get_field("field_date").unwrap(), &FieldValue::U32(20231201));
+        assert_eq!(decoded_message.get_field("field_ntime").unwrap(), &FieldValue::U64(930000000000));
+
+        println!("✓ Numeric coercion test passed");
+    }
+
+    /// 测试编码到调用方提供的缓冲区
+    #[test]
+    fn test_encode_into_caller_buffers() {
+        let mut config_manager = create_test_config_manager();
+        config_manager.load_from_str(r#"
+        <messages>
+            <message type="1002" name="BytesTestMessage">
+                <field name="field_signature" type="bytes" desc="变长签名">
+                    <length_field name="SignatureLen" type="u16" desc="签名长度"/>
+                </field>
+                <field name="field_char" type="char" length="4" desc="Char字段"/>
+            </message>
+            <message type="1003" name="SmallArrayMessage">
+                <field name="field_items" type="array" desc="单字节计数的数组">
+                    <length_field name="NoItems" type="u8" desc="数组项个数"/>
+                    <struct>
+                        <field name="item_id" type="u16" desc="项目ID"/>
+                    </struct>
+                </field>
+            </message>
+        </messages>
+        "#).expect("Failed to load bytes test config");
+
+        let mut encoder = MessageEncoder::new(&config_manager);
+        let message = create_test_message();
+        let expected = encoder.encode(&message).expect("Failed to encode message");
+        assert_eq!(encoder.encoded_len(&message).unwrap(), expected.len(), "定长消息的编码长度应与实际一致");
+
+        // 追加到已有数据之后
+        let mut buffer = b"prefix".to_vec();
+        let written = encoder.encode_into(&message, &mut buffer).unwrap();
+        assert_eq!(written, expected.len());
+        assert_eq!(&buffer[6..], &expected[..]);
+
+        // 写入预分配的切片
+        let mut slice = [0u8; 256];
+        let written = encoder.encode_to_slice(&message, &mut slice).unwrap();
+        assert_eq!(&slice[..written], &expected[..]);
+
+        // 切片长度不足时报错
+        match encoder.encode_to_slice(&message, &mut slice[..expected.len() - 1]) {
+            Err(MessageError::BufferTooSmall(required, available)) => {
+                assert_eq!((required, available), (expected.len(), expected.len() - 1));
+            },
+            other => panic!("Expected BufferTooSmall error, got: {:?}", other),
+        }
+
+        // 靠后的字段取值无效时不向缓冲区写入任何数据
+        let mut invalid = create_test_message();
+        invalid.add_field("field_date".to_string(), FieldValue::U32(20231332));
+        let mut buffer = bytes::BytesMut::from(&b"prefix"[..]);
+        assert!(encoder.encode_into(&invalid, &mut buffer).is_err());
+        assert_eq!(&buffer[..], b"prefix", "校验失败时不应留下部分帧");
+        let mut slice = [0xEEu8; 256];
+        assert!(encoder.encode_to_slice(&invalid, &mut slice).is_err());
+        assert!(slice.iter().all(|&byte| byte == 0xEE), "校验失败时不应写入切片");
+
+        // 变长字段按实际值计算长度，超长的字符串按定长截断
+        let mut message = Message::new(1002, 1);
+        message.add_field("field_signature".to_string(), FieldValue::Bytes(vec![0xAB; 5]));
+        message.add_field("field_char".to_string(), FieldValue::Str("TOO_LONG".to_string()));
+        assert_eq!(encoder.encoded_len(&message).unwrap(), 12 + 2 + 5 + 4 + 4);
+        let mut buffer = bytes::BytesMut::new();
+        encoder.encode_into(&message, &mut buffer).unwrap();
+        assert_eq!(&buffer[..], &encoder.encode(&message).unwrap()[..]);
+        assert_eq!(&buffer[19..23], b"TOO_");
+
+        let decoded = MessageDecoder::new(&config_manager, &buffer).decode().expect("Failed to decode message");
+        assert_eq!(decoded.get_bytes("field_signature").unwrap(), &[0xAB; 5]);
+
+        // 数组元素个数超出计数字段的范围时报错，不写入截断后的计数
+        let mut message = Message::new(1003, 1);
+        message.add_field("field_items".to_string(), FieldValue::Array(vec![Record::new().with("item_id", 1u16); 256]));
+        assert!(matches!(encoder.encoded_len(&message), Err(MessageError::ValueExceedsRange(_))));
+        assert!(matches!(encoder.encode(&message), Err(MessageError::ValueExceedsRange(_))));
+        let mut buffer = bytes::BytesMut::from(&b"prefix"[..]);
+        assert!(matches!(encoder.encode_into(&message, &mut buffer), Err(MessageError::ValueExceedsRange(_))));
+        assert_eq!(&buffer[..], b"prefix", "计数超出范围时不应留下部分帧");
+        message.add_field("field_items".to_string(), FieldValue::Array(vec![Record::new().with("item_id", 1u16); 255]));
+        assert_eq!(encoder.encode(&message).unwrap().len(), 12 + 1 + 255 * 2 + 4);
+
+        println!("✓ Encode into caller buffers test passed");
+    }
+
     /// 性能测试：大量消息编解码
     #[test]
     fn test_performance() {
@@ -254,12 +482,12 @@ mod codec_tests {
             // 编码
             let mut encoder = MessageEncoder::new(&config_manager);
             let encoded_data = encoder.encode(&message)
-                .expect(&format!("Failed to encode message {}", i));
+                .unwrap_or_else(|_| panic!("Failed to encode message {}", i));
             
             // 解码
             let mut decoder = MessageDecoder::new(&config_manager, &encoded_data);
             let decoded_message = decoder.decode()
-                .expect(&format!("Failed to decode message {}", i));
+                .unwrap_or_else(|_| panic!("Failed to decode message {}", i));
             
             // 快速验证
             assert_eq!(decoded_message.msg_type, message.msg_type);
@@ -0,0 +1,30 @@
+//! 集成测试共用的辅助函数
+//!
+//! 每个测试文件只用到其中一部分，未使用的函数不告警
+#![allow(dead_code)]
+
+use sse_tdgw_binary::codec::encoder::MessageEncoder;
+use sse_tdgw_binary::config::manager::ConfigManager;
+use sse_tdgw_binary::message::Message;
+
+/// 协议完整的消息定义
+pub const CONFIG: &str = "config/sse-message.xml";
+
+/// 加载协议完整的消息定义
+pub fn load_config() -> ConfigManager {
+    let mut config_manager = ConfigManager::new();
+    config_manager.load_from_file(CONFIG).expect("Failed to load sse-message.xml");
+    config_manager
+}
+
+/// 编码一个心跳帧
+pub fn heartbeat(config_manager: &ConfigManager, seq_num: u32) -> Vec<u8> {
+    MessageEncoder::new(config_manager).encode(&Message::new(33, seq_num)).unwrap()
+}
+
+/// 重新计算校验和，便于构造字段内容错误但校验和正确的帧
+pub fn fix_checksum(frame: &mut [u8]) {
+    let end = frame.len() - 4;
+    let checksum = frame[..end].iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte));
+    frame[end..].copy_from_slice(&(checksum as u32).to_be_bytes());
+}
@@ -0,0 +1,190 @@
+use sse_tdgw_binary::codec::decoder::MessageDecoder;
+use sse_tdgw_binary::codec::encoder::MessageEncoder;
+use sse_tdgw_binary::codec::MessageView;
+use sse_tdgw_binary::config::manager::ConfigManager;
+use sse_tdgw_binary::message::{FieldValue, Message, Record};
+use sse_tdgw_binary::util::{ContextError, ErrorContext, MessageError};
+
+mod common;
+use common::fix_checksum;
+
+/// 错误上下文测试
+/// 测试编解码错误中记录的消息、字段路径、字节偏移以及期望值和实际值
+#[cfg(test)]
+mod error_context_tests {
+    use super::*;
+
+    fn create_test_config_manager() -> ConfigManager {
+        let mut config_manager = ConfigManager::new();
+        let config_xml = r#"
+        <messages>
+            <message type="103" name="ExecutionReport">
+                <field name="ClOrdID" type="char" length="10" desc="订单编号"/>
+                <field name="LastPx" type="price" desc="成交价格"/>
+                <field name="Parties" type="array" desc="参与方">
+                    <length_field name="NoParties" type="u8" desc="参与方个数"/>
+                    <struct>
+                        <field name="PartyID" type="char" length="4" desc="参与方代码"/>
+                        <field name="PartyRole" type="u16" desc="参与方角色"/>
+                    </struct>
+                </field>
+                <field name="TradeDate" type="date" desc="交易日期"/>
+            </message>
+        </messages>
+        "#;
+        config_manager.load_from_str(config_xml).expect("Failed to load error context test config");
+        config_manager
+    }
+
+    fn create_report(seq_num: u32) -> Message {
+        let mut message = Message::new(103, seq_num);
+        message.add_field("ClOrdID".to_string(), FieldValue::from("ORD001"));
+        message.add_field("LastPx".to_string(), FieldValue::Float(10.5));
+        message.add_field("Parties".to_string(), FieldValue::Array(vec![
+            Record::new().with("PartyID", "P001").with("PartyRole", 1u16),
+            Record::new().with("PartyID", "P002").with("PartyRole", 2u16),
+        ]));
+        message.add_field("TradeDate".to_string(), FieldValue::U32(20240105));
+        message
+    }
+
+    fn error_context(error: &ContextError) -> &ErrorContext {
+        assert_ne!(*error.context, ErrorContext::default(), "错误应带有上下文: {:?}", error);
+        &error.context
+    }
+
+    /// 测试数组元素字段的解码错误
+    #[test]
+    fn test_decode_array_element_error() {
+        let config_manager = create_test_config_manager();
+        let mut frame = MessageEncoder::new(&config_manager).encode(&create_report(7)).unwrap();
+
+        // 头部 12 + ClOrdID 10 + LastPx 8 + NoParties 1 + 第一个元素 6，第二个元素的 PartyID 从 37 开始
+        frame[38] = 0xFF;
+        fix_checksum(&mut frame);
+
+        let error = MessageDecoder::new(&config_manager, &frame).decode_with_context().unwrap_err();
+        assert!(matches!(&error.kind, MessageError::FieldDecodeError(_)));
+        let context = error_context(&error);
+        assert_eq!(context.msg_type, Some(103));
+        assert_eq!(context.msg_name.as_deref(), Some("ExecutionReport"));
+        assert_eq!(context.seq_num, Some(7));
+        assert_eq!(context.field.as_deref(), Some("Parties[1].PartyID"));
+        assert_eq!(context.offset, Some(38), "偏移应指向无效的字节");
+        assert_eq!(context.actual.as_deref(), Some("byte 0xff"));
+
+        let text = error.to_string();
+        assert!(text.starts_with("message 103 (ExecutionReport), seq 7, field 'Parties[1].PartyID', offset 38"), "{}", text);
+
+        // 不带上下文的解码仍然返回可以直接匹配的原始错误
+        let result = MessageDecoder::new(&config_manager, &frame).decode();
+        assert!(matches!(result, Err(MessageError::FieldDecodeError(_))), "Expected FieldDecodeError, got: {:?}", result);
+
+        println!("✓ Decode array element error test passed");
+    }
+
+    /// 测试消息体长度不足时的期望长度和实际长度
+    #[test]
+    fn test_decode_length_errors() {
+        let config_manager = create_test_config_manager();
+        let frame = MessageEncoder::new(&config_manager).encode(&create_report(1)).unwrap();
+
+        let error = MessageDecoder::new(&config_manager, &frame[..frame.len() - 1]).decode_with_context().unwrap_err();
+        assert!(matches!(&error.kind, MessageError::BodyTooShort));
+        let context = error_context(&error);
+        assert_eq!(context.msg_name.as_deref(), Some("ExecutionReport"));
+        assert_eq!(context.expected, Some(format!("{} bytes", frame.len())));
+        assert_eq!(context.actual, Some(format!("{} bytes", frame.len() - 1)));
+
+        // 消息体在第二个参与方的 PartyID 中间结束，PartyID 从 37 开始
+        let mut truncated = frame[..40].to_vec();
+        truncated[8..12].copy_from_slice(&28u32.to_be_bytes());
+        truncated.extend_from_slice(&[0; 4]);
+        fix_checksum(&mut truncated);
+        let error = MessageDecoder::new(&config_manager, &truncated).decode_with_context().unwrap_err();
+        assert!(matches!(&error.kind, MessageError::BodyTooShort));
+        let context = error_context(&error);
+        assert_eq!(context.field.as_deref(), Some("Parties[1].PartyID"));
+        assert_eq!(context.offset, Some(37));
+        assert_eq!((context.expected.as_deref(), context.actual.as_deref()), (Some("4 bytes"), Some("3 bytes")));
+
+        let error = MessageDecoder::new(&config_manager, &frame[..8]).decode_with_context().unwrap_err();
+        assert!(matches!(&error.kind, MessageError::HeaderTooShort));
+        let context = error_context(&error);
+        assert_eq!(context.msg_type, None, "头部不完整时没有消息类型");
+        assert_eq!((context.expected.as_deref(), context.actual.as_deref()), (Some("12 bytes"), Some("8 bytes")));
+
+        println!("✓ Decode length errors test passed");
+    }
+
+    /// 测试字段取值和校验和错误
+    #[test]
+    fn test_decode_value_errors() {
+        let config_manager = create_test_config_manager();
+        let mut frame = MessageEncoder::new(&config_manager).encode(&create_report(1)).unwrap();
+
+        let error = {
+            let mut corrupted = frame.clone();
+            let last = corrupted.len() - 1;
+            corrupted[last] ^= 0xFF;
+            MessageDecoder::new(&config_manager, &corrupted).decode_with_context().unwrap_err()
+        };
+        assert!(matches!(&error.kind, MessageError::ChecksumError));
+        let context = error_context(&error);
+        assert_eq!(context.offset, Some(frame.len() - 4), "偏移应指向帧尾");
+        assert_ne!(context.expected, context.actual);
+
+        // TradeDate 位于帧尾的校验和之前
+        let date_offset = frame.len() - 8;
+        frame[date_offset..date_offset + 4].copy_from_slice(&20241301u32.to_be_bytes());
+        fix_checksum(&mut frame);
+        let error = MessageDecoder::new(&config_manager, &frame).decode_with_context().unwrap_err();
+        assert!(matches!(&error.kind, MessageError::InvalidFieldValue(_)));
+        let context = error_context(&error);
+        assert_eq!(context.field.as_deref(), Some("TradeDate"));
+        assert_eq!(context.offset, Some(date_offset));
+        assert_eq!((context.expected.as_deref(), context.actual.as_deref()), (Some("YYYYMMDD"), Some("20241301")));
+
+        // 消息视图读取单个字段时同样带有上下文
+        let layout = config_manager.get_layout(103).unwrap();
+        let view = MessageView::new(layout, &frame).unwrap();
+        let error = view.get_with_context("TradeDate").unwrap_err();
+        assert_eq!(error_context(&error).field.as_deref(), Some("TradeDate"));
+        assert_eq!(error_context(&error).offset, Some(date_offset));
+
+        println!("✓ Decode value errors test passed");
+    }
+
+    /// 测试编码错误的字段路径和偏移
+    #[test]
+    fn test_encode_errors() {
+        let config_manager = create_test_config_manager();
+        let mut encoder = MessageEncoder::new(&config_manager);
+
+        let mut message = create_report(3);
+        message.add_field("Parties".to_string(), FieldValue::Array(vec![
+            Record::new().with("PartyID", "P001").with("PartyRole", 1u16),
+            Record::new().with("PartyID", "P002").with("PartyRole", 70000u32),
+        ]));
+        let error = encoder.encode_with_context(&message).unwrap_err();
+        assert!(matches!(&error.kind, MessageError::ValueExceedsRange(_)));
+        let context = error_context(&error);
+        assert_eq!(context.msg_name.as_deref(), Some("ExecutionReport"));
+        assert_eq!(context.seq_num, Some(3));
+        assert_eq!(context.field.as_deref(), Some("Parties[1].PartyRole"));
+        assert_eq!(context.offset, Some(41));
+        assert_eq!((context.expected.as_deref(), context.actual.as_deref()), (Some("U16"), Some("U32(70000)")));
+
+        let mut message = create_report(4);
+        message.add_field("LastPx".to_string(), FieldValue::Float(1e9));
+        let error = encoder.encode_with_context(&message).unwrap_err();
+        assert_eq!(error_context(&error).field.as_deref(), Some("LastPx"));
+        assert_eq!(error_context(&error).offset, Some(22));
+        assert_eq!(error_context(&error).actual.as_deref(), Some("1000000000"));
+
+        let error = encoder.encode_with_context(&Message::new(999, 5)).unwrap_err();
+        assert!(matches!(error.kind, MessageError::UnknownMessageType(999)));
+
+        println!("✓ Encode errors test passed");
+    }
+}
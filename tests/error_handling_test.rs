@@ -69,6 +69,18 @@ mod error_handling_tests {
         let mut encoder = MessageEncoder::new(&config_manager);
         let result = encoder.encode(&message);
         
+        // U8字段的U32值超出范围，无法转换
+        assert!(result.is_err(), "Should fail for out-of-range value");
+        if let Err(MessageError::ValueExceedsRange(_)) = result {
+            // 预期的错误类型
+        } else {
+            panic!("Expected ValueExceedsRange error, got: {:?}", result);
+        }
+
+        // 修正U8字段后，U16字段的非数字字符串仍然类型不匹配
+        message.add_field("field_u8".to_string(), FieldValue::U8(255));
+        let result = encoder.encode(&message);
+
         assert!(result.is_err(), "Should fail for field type mismatch");
         if let Err(MessageError::InvalidFieldValue(_)) = result {
             // 预期的错误类型
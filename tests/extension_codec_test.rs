@@ -484,7 +484,7 @@ mod extension_codec_tests {
         for (name, message) in test_cases {
             let mut encoder = MessageEncoder::new(&config_manager);
             let encoded_data = encoder.encode(&message)
-                .expect(&format!("Failed to encode {} message", name));
+                .unwrap_or_else(|_| panic!("Failed to encode {} message", name));
             
             let field_count = message.fields.len();
             let bytes_per_field = encoded_data.len() as f64 / field_count as f64;
@@ -0,0 +1,72 @@
+use sse_tdgw_binary::message::{FieldValue, Message, Record};
+use sse_tdgw_binary::util::{ConversionError, MessageError};
+
+/// 字段值转换测试
+/// 测试 `TryFrom<FieldValue>` 转换和 `Message` 的类型化读取
+#[cfg(test)]
+mod field_conversion_tests {
+    use super::*;
+
+    fn create_order() -> Message {
+        let mut message = Message::new(58, 1);
+        message.add_field("BizID".to_string(), FieldValue::U32(100010));
+        message.add_field("ClOrdID".to_string(), FieldValue::from("ORD001"));
+        message.add_field("Price".to_string(), FieldValue::Float(10.5));
+        message.add_field("Signature".to_string(), FieldValue::Bytes(vec![0xde, 0xad]));
+        message.add_field("Items".to_string(), FieldValue::Array(vec![Record::new().with("SetID", 1u32)]));
+        message
+    }
+
+    /// 测试 TryFrom 转换
+    #[test]
+    fn test_try_from_field_value() {
+        assert_eq!(u8::try_from(FieldValue::U8(7)), Ok(7));
+        assert_eq!(i64::try_from(&FieldValue::I64(-1)), Ok(-1));
+        assert_eq!(f64::try_from(FieldValue::Float(1.5)), Ok(1.5));
+        assert_eq!(String::try_from(FieldValue::from("ABC")), Ok("ABC".to_string()));
+        assert_eq!(<&str>::try_from(&FieldValue::from("ABC")), Ok("ABC"));
+        assert_eq!(Vec::<u8>::try_from(FieldValue::Bytes(vec![1, 2])), Ok(vec![1, 2]));
+
+        // 变体不符时返回错误而不是 panic，且不做隐式的宽度转换
+        let err = u32::try_from(FieldValue::U16(1)).unwrap_err();
+        assert_eq!(err, ConversionError { expected: "U32", actual: "U16" });
+        assert_eq!(err.to_string(), "expected FieldValue::U32, got FieldValue::U16");
+
+        let err = u8::try_from(&FieldValue::from("7")).unwrap_err();
+        assert_eq!(err.actual, "Str", "错误应包含实际的变体名");
+
+        println!("✓ TryFrom conversion test passed");
+    }
+
+    /// 测试 Message 类型化读取区分字段缺失和类型不符
+    #[test]
+    fn test_message_typed_getters() {
+        let message = create_order();
+
+        assert_eq!(message.get_u32("BizID").unwrap(), 100010);
+        assert_eq!(message.get_str("ClOrdID").unwrap(), "ORD001");
+        assert_eq!(message.get_f64("Price").unwrap(), 10.5);
+        assert_eq!(message.get_bytes("Signature").unwrap(), &[0xde, 0xad]);
+        assert_eq!(message.get_array("Items").unwrap()[0]["SetID"], FieldValue::U32(1));
+        assert_eq!(message.get_as::<u32>("BizID").unwrap(), 100010);
+
+        match message.get_u32("OrderQty") {
+            Err(MessageError::FieldNotFound(name)) => assert_eq!(name, "OrderQty"),
+            other => panic!("Expected FieldNotFound error, got: {:?}", other),
+        }
+
+        match message.get_u64("BizID") {
+            Err(MessageError::FieldConversionError(name, err)) => {
+                assert_eq!(name, "BizID");
+                assert_eq!(err, ConversionError { expected: "U64", actual: "U32" });
+            },
+            other => panic!("Expected FieldConversionError error, got: {:?}", other),
+        }
+        assert_eq!(
+            message.get_str("Price").unwrap_err().to_string(),
+            "Field 'Price' expected FieldValue::Str, got FieldValue::Float"
+        );
+
+        println!("✓ Message typed getters test passed");
+    }
+}
@@ -0,0 +1,164 @@
+use sse_tdgw_binary::codec::encoder::MessageEncoder;
+use sse_tdgw_binary::codec::HexDump;
+use sse_tdgw_binary::config::manager::ConfigManager;
+use sse_tdgw_binary::message::{FieldValue, Message, Record};
+use sse_tdgw_binary::util::MessageError;
+
+mod common;
+use common::fix_checksum;
+
+/// 十六进制转储测试
+/// 测试帧中每段字节与头部、字段、数组、扩展字段和校验和的对应关系以及错误标注
+#[cfg(test)]
+mod hex_dump_tests {
+    use super::*;
+
+    fn create_test_config_manager() -> ConfigManager {
+        let mut config_manager = ConfigManager::new();
+        let config_xml = r#"
+        <messages>
+            <message type="58" name="NewOrderSingle">
+                <field name="BizID" type="u32" desc="业务代码"/>
+                <field name="ClOrdID" type="char" length="6" desc="订单编号"/>
+                <field name="Side" type="char" length="1" desc="买卖方向：1=买, 2=卖"/>
+                <field name="Price" type="price" desc="申报价格"/>
+                <field name="Parties" type="array" desc="参与方">
+                    <length_field name="NoParties" type="u8" desc="参与方个数"/>
+                    <struct>
+                        <field name="PartyID" type="char" length="4" desc="参与方代码"/>
+                        <field name="PartyRole" type="u8" desc="参与方角色"/>
+                    </struct>
+                </field>
+                <field name="TradeDate" type="date" desc="交易日期"/>
+                <extension biz_id="300070">
+                    <field name="DividendSelect" type="char" length="1" desc="分红方式：U=红利转投, C=现金分红"/>
+                </extension>
+            </message>
+        </messages>
+        "#;
+        config_manager.load_from_str(config_xml).expect("Failed to load hex dump test config");
+        config_manager
+    }
+
+    fn create_frame(config_manager: &ConfigManager) -> Vec<u8> {
+        let mut message = Message::new(58, 9);
+        message.add_field("BizID".to_string(), FieldValue::U32(300070));
+        message.add_field("ClOrdID".to_string(), FieldValue::from("ORD001"));
+        message.add_field("Side".to_string(), FieldValue::from("2"));
+        message.add_field("Price".to_string(), FieldValue::Float(10.5));
+        message.add_field("Parties".to_string(), FieldValue::Array(vec![
+            Record::new().with("PartyID", "P001").with("PartyRole", 1u8),
+            Record::new().with("PartyID", "P002").with("PartyRole", 2u8),
+        ]));
+        message.add_field("TradeDate".to_string(), FieldValue::U32(20240105));
+        message.add_field("DividendSelect".to_string(), FieldValue::from("U"));
+        MessageEncoder::new(config_manager).encode(&message).unwrap()
+    }
+
+    /// 测试每行对应的字节范围和值
+    #[test]
+    fn test_byte_ranges() {
+        let config_manager = create_test_config_manager();
+        let frame = create_frame(&config_manager);
+        let dump = HexDump::new(&config_manager, &frame);
+        println!("{}", dump);
+
+        let lines: Vec<_> = dump.lines().iter()
+            .map(|line| (line.range.clone(), line.depth, line.name.as_str(), line.value.as_deref()))
+            .collect();
+        assert_eq!(lines, vec![
+            (0..4, 0, "MsgType", Some("58 (NewOrderSingle)")),
+            (4..8, 0, "SeqNum", Some("9")),
+            (8..12, 0, "BodyLength", Some("35")),
+            (12..16, 0, "BizID", Some("300070")),
+            (16..22, 0, "ClOrdID", Some("ORD001")),
+            (22..23, 0, "Side", Some("2 (卖)")),
+            (23..31, 0, "Price", Some("10.50000")),
+            (31..32, 0, "NoParties", Some("2")),
+            (32..37, 1, "Parties[0]", None),
+            (32..36, 2, "PartyID", Some("P001")),
+            (36..37, 2, "PartyRole", Some("1")),
+            (37..42, 1, "Parties[1]", None),
+            (37..41, 2, "PartyID", Some("P002")),
+            (41..42, 2, "PartyRole", Some("2")),
+            (42..46, 0, "TradeDate", Some("2024-01-05")),
+            (46..47, 0, "Extension (BizID 300070)", None),
+            (46..47, 1, "DividendSelect", Some("U (红利转投)")),
+            (47..51, 0, "Checksum", Some(format!("{:#010x}", frame[50]).as_str())),
+        ]);
+        assert!(!dump.has_errors());
+
+        let text = dump.to_string();
+        assert!(text.contains("0016..0022  4f 52 44 30 30 31"), "{}", text);
+        assert!(text.lines().any(|line| line.starts_with("0032..0037") && line.trim_end().ends_with("  Parties[0]")), "分组行不显示字节: {}", text);
+
+        println!("✓ Byte ranges test passed");
+    }
+
+    /// 测试无效取值和校验和错误的标注
+    #[test]
+    fn test_invalid_bytes_are_marked() {
+        let config_manager = create_test_config_manager();
+        let mut frame = create_frame(&config_manager);
+
+        // 第二个参与方的 PartyID 从 37 开始，日期从 42 开始
+        frame[38] = 0xFF;
+        frame[42..46].copy_from_slice(&20241399u32.to_be_bytes());
+        fix_checksum(&mut frame);
+        let dump = HexDump::new(&config_manager, &frame);
+        println!("{}", dump);
+
+        let errors: Vec<_> = dump.lines().iter().filter(|line| line.error.is_some()).collect();
+        assert_eq!(errors.len(), 2);
+        assert_eq!((errors[0].name.as_str(), errors[0].highlight.clone()), ("PartyID", 38..39), "只标出无效的字节");
+        assert_eq!((errors[1].name.as_str(), errors[1].highlight.clone()), ("TradeDate", 42..46));
+        assert_eq!(errors[1].value.as_deref(), Some("20241399"), "无效日期按原值显示");
+
+        let text = dump.to_string();
+        let marks: Vec<_> = text.lines().filter(|line| line.contains("^^")).collect();
+        assert_eq!(marks.len(), 2, "{}", text);
+        assert_eq!(marks[0].find("^^"), Some(15), "标记位于第二个字节之下");
+        assert!(marks[1].contains("^^ ^^ ^^ ^^") && marks[1].contains("expected YYYYMMDD, got 20241399"), "{}", text);
+
+        // 校验和不一致
+        let last = frame.len() - 1;
+        frame[last] ^= 0xFF;
+        let dump = HexDump::new(&config_manager, &frame);
+        let checksum = dump.lines().last().unwrap();
+        assert_eq!(checksum.name, "Checksum");
+        assert!(matches!(checksum.error.as_ref().map(|error| &error.kind), Some(MessageError::ChecksumError)));
+
+        println!("✓ Invalid bytes are marked test passed");
+    }
+
+    /// 测试截断的帧、未知消息类型和不完整的头部
+    #[test]
+    fn test_truncated_and_unknown_frames() {
+        let config_manager = create_test_config_manager();
+        let frame = create_frame(&config_manager);
+
+        // 帧在第二个参与方中间结束
+        let dump = HexDump::new(&config_manager, &frame[..39]);
+        println!("{}", dump);
+        let last = dump.lines().last().unwrap();
+        assert_eq!((last.name.as_str(), last.range.clone()), ("PartyID", 37..39));
+        assert_eq!(dump.lines()[dump.lines().len() - 2].range, 37..39, "数组元素包含截断处的字节");
+        assert!(matches!(last.error.as_ref().map(|error| &error.kind), Some(MessageError::BodyTooShort)));
+        let body_length = &dump.lines()[2];
+        assert!(body_length.error.is_some(), "帧长度不足时标出消息体长度");
+
+        let mut unknown = frame.clone();
+        unknown[..4].copy_from_slice(&999u32.to_be_bytes());
+        let dump = HexDump::new(&config_manager, &unknown);
+        let names: Vec<_> = dump.lines().iter().map(|line| line.name.as_str()).collect();
+        assert_eq!(names, vec!["MsgType", "SeqNum", "BodyLength", "<body>", "Checksum"]);
+        assert!(matches!(dump.lines()[0].error.as_ref().map(|error| &error.kind), Some(MessageError::UnknownMessageType(999))));
+
+        let dump = HexDump::new(&config_manager, &frame[..8]);
+        assert_eq!(dump.lines().len(), 1);
+        assert!(dump.has_errors());
+        assert!(dump.to_string().contains("expected 12 bytes, got 8 bytes"));
+
+        println!("✓ Truncated and unknown frames test passed");
+    }
+}
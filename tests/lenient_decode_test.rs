@@ -0,0 +1,152 @@
+use std::sync::Arc;
+
+use sse_tdgw_binary::codec::decoder::MessageDecoder;
+use sse_tdgw_binary::codec::encoder::MessageEncoder;
+use sse_tdgw_binary::codec::{ChecksumMode, SharedDecoder};
+use sse_tdgw_binary::config::manager::ConfigManager;
+use sse_tdgw_binary::message::{FieldValue, Message, Record};
+use sse_tdgw_binary::util::MessageError;
+
+mod common;
+use common::fix_checksum;
+
+/// 宽松解码测试
+/// 测试字段取值无效时保留原始值、记录诊断信息并继续解码其余字段
+#[cfg(test)]
+mod lenient_decode_tests {
+    use super::*;
+
+    fn create_test_config_manager() -> ConfigManager {
+        let mut config_manager = ConfigManager::new();
+        let config_xml = r#"
+        <messages>
+            <message type="58" name="NewOrderSingle">
+                <field name="ClOrdID" type="char" length="10" desc="订单编号"/>
+                <field name="Price" type="price" desc="申报价格"/>
+                <field name="TransactTime" type="ntime" desc="申报时间"/>
+                <field name="Legs" type="array" desc="腿">
+                    <length_field name="NoLegs" type="u8" desc="腿个数"/>
+                    <struct>
+                        <field name="LegDate" type="date" desc="腿日期"/>
+                        <field name="LegQty" type="quantity" desc="腿数量"/>
+                    </struct>
+                </field>
+                <field name="OrderQty" type="quantity" desc="申报数量"/>
+                <field name="TradeDate" type="date" desc="交易日期"/>
+            </message>
+        </messages>
+        "#;
+        config_manager.load_from_str(config_xml).expect("Failed to load lenient decode test config");
+        config_manager
+    }
+
+    fn create_order() -> Message {
+        let mut message = Message::new(58, 9);
+        message.add_field("ClOrdID".to_string(), FieldValue::from("ORD001"));
+        message.add_field("Price".to_string(), FieldValue::Float(10.5));
+        message.add_field("TransactTime".to_string(), FieldValue::U64(930001230000));
+        message.add_field("Legs".to_string(), FieldValue::Array(vec![
+            Record::new().with("LegDate", 20240105u32).with("LegQty", 100.0),
+            Record::new().with("LegDate", 20240106u32).with("LegQty", 200.0),
+        ]));
+        message.add_field("OrderQty".to_string(), FieldValue::Float(300.0));
+        message.add_field("TradeDate".to_string(), FieldValue::U32(20240105));
+        message
+    }
+
+    /// 构造 TransactTime、第二条腿的日期和 ClOrdID 都无效的帧
+    fn create_invalid_frame(config_manager: &ConfigManager) -> Vec<u8> {
+        let mut frame = MessageEncoder::new(config_manager).encode(&create_order()).unwrap();
+        // 头部 12 + ClOrdID 10 + Price 8，TransactTime 从 30 开始；NoLegs 在 38，第二条腿从 51 开始
+        frame[12] = 0xC3;
+        frame[30..38].copy_from_slice(&996001230000u64.to_be_bytes());
+        frame[51..55].copy_from_slice(&20241399u32.to_be_bytes());
+        fix_checksum(&mut frame);
+        frame
+    }
+
+    /// 测试无效取值以原始值保存并记录诊断信息
+    #[test]
+    fn test_invalid_values_are_kept() {
+        let config_manager = create_test_config_manager();
+        let frame = create_invalid_frame(&config_manager);
+
+        let result = MessageDecoder::new(&config_manager, &frame).decode();
+        assert!(matches!(result, Err(MessageError::FieldDecodeError(_))), "默认解码遇到第一个问题即报错");
+
+        let decoded = MessageDecoder::new(&config_manager, &frame).decode_lenient().unwrap();
+        assert!(!decoded.is_clean());
+        let message = &decoded.message;
+        assert_eq!(message.fields.len(), 6, "所有字段都应被解码");
+        assert!(matches!(message.get_field("ClOrdID"), Some(FieldValue::Bytes(raw)) if raw[0] == 0xC3));
+        assert_eq!(message.get_field("TransactTime"), Some(&FieldValue::U64(996001230000)));
+        assert_eq!(message.get_f64("OrderQty").unwrap(), 300.0, "无效字段之后的字段应正常解码");
+        assert_eq!(message.get_u32("TradeDate").unwrap(), 20240105);
+        match message.get_field("Legs") {
+            Some(FieldValue::Array(legs)) => {
+                assert_eq!(legs[1]["LegDate"], FieldValue::U32(20241399));
+                assert_eq!(legs[1]["LegQty"], FieldValue::Float(200.0));
+            },
+            other => panic!("Expected Legs array, got {:?}", other),
+        }
+
+        let paths: Vec<_> = decoded.diagnostics.iter()
+            .map(|e| e.context.field.clone().unwrap_or_default())
+            .collect();
+        assert_eq!(paths, vec!["ClOrdID", "TransactTime", "Legs[1].LegDate"]);
+        let context = &decoded.diagnostics[2].context;
+        assert_eq!(context.msg_name.as_deref(), Some("NewOrderSingle"));
+        assert_eq!(context.offset, Some(51));
+        assert_eq!(context.actual.as_deref(), Some("20241399"));
+
+        println!("✓ Invalid values are kept test passed");
+    }
+
+    /// 测试消息体被截断时保留截断之前的字段
+    #[test]
+    fn test_truncated_body() {
+        let config_manager = create_test_config_manager();
+        let frame = MessageEncoder::new(&config_manager).encode(&create_order()).unwrap();
+
+        // 消息体在 OrderQty 中间结束
+        let body_length = 66 - 12;
+        let mut truncated = frame[..66].to_vec();
+        truncated[8..12].copy_from_slice(&(body_length as u32).to_be_bytes());
+        truncated.extend_from_slice(&[0; 4]);
+        fix_checksum(&mut truncated);
+
+        let decoded = MessageDecoder::new(&config_manager, &truncated).decode_lenient().unwrap();
+        assert_eq!(decoded.message.fields.keys().collect::<Vec<_>>(), vec!["ClOrdID", "Price", "TransactTime", "Legs"]);
+        assert_eq!(decoded.diagnostics.len(), 1);
+        assert!(matches!(decoded.diagnostics[0].kind, MessageError::BodyTooShort));
+        assert_eq!(decoded.diagnostics[0].context.field.as_deref(), Some("OrderQty"));
+
+        // 头部不完整时无法解码
+        assert!(MessageDecoder::new(&config_manager, &frame[..8]).decode_lenient().is_err());
+
+        println!("✓ Truncated body test passed");
+    }
+
+    /// 测试宽松解码与校验方式组合使用
+    #[test]
+    fn test_lenient_with_checksum_mode() {
+        let config_manager = create_test_config_manager();
+        let mut frame = create_invalid_frame(&config_manager);
+        let last = frame.len() - 1;
+        frame[last] ^= 0xFF;
+
+        let decoder = SharedDecoder::new(Arc::new(config_manager));
+        let result = decoder.decode_lenient(&frame);
+        assert!(matches!(result.unwrap_err().kind, MessageError::ChecksumError), "严格校验时校验和错误仍然报错");
+
+        let decoder = decoder.with_checksum_mode(ChecksumMode::Warn);
+        let decoded = decoder.decode_lenient(&frame).unwrap();
+        assert!(decoded.message.checksum_mismatch);
+        assert_eq!(decoded.diagnostics.len(), 3);
+
+        let valid = MessageEncoder::new(decoder.config_manager()).encode(&create_order()).unwrap();
+        assert!(decoder.decode_lenient(&valid).unwrap().is_clean());
+
+        println!("✓ Lenient with checksum mode test passed");
+    }
+}
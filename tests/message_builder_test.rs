@@ -0,0 +1,211 @@
+use sse_tdgw_binary::codec::encoder::MessageEncoder;
+use sse_tdgw_binary::codec::decoder::MessageDecoder;
+use sse_tdgw_binary::config::manager::ConfigManager;
+use sse_tdgw_binary::message::{FieldValue, Record};
+use sse_tdgw_binary::util::{ConfigError, MessageError};
+
+/// 消息构建器测试
+/// 测试按消息定义校验字段名、字段类型、必填字段和扩展字段
+#[cfg(test)]
+mod message_builder_tests {
+    use super::*;
+
+    /// 创建测试用的配置管理器
+    fn create_test_config_manager() -> ConfigManager {
+        let mut config_manager = ConfigManager::new();
+
+        let config_xml = r#"
+        <messages>
+            <message type="40" name="Logon">
+                <field name="SenderCompID" type="char" length="32" desc="发送方代码"/>
+                <field name="TargetCompID" type="char" length="32" desc="接收方代码"/>
+                <field name="HeartBtInt" type="u16" desc="心跳间隔（秒）"/>
+                <field name="TradeDate" type="date" desc="交易日期（YYYYMMDD）"/>
+            </message>
+            <message type="58" name="NewOrderSingle">
+                <field name="BizID" type="u32" desc="业务代码"/>
+                <field name="ClOrdID" type="char" length="10" desc="会员内部订单编号"/>
+                <field name="Price" type="price" desc="申报价格"/>
+                <extension biz_id="300060">
+                    <field name="Custodian" type="char" length="3" desc="转托管的目标方代理人"/>
+                </extension>
+                <extension biz_id="300070">
+                    <field name="DividendSelect" type="char" length="1" desc="分红方式"/>
+                </extension>
+            </message>
+            <message type="206" name="ExecRptSync">
+                <field name="SyncRequests" type="array" desc="同步请求项数组">
+                    <length_field name="NoGroups" type="u16" desc="同步请求项个数"/>
+                    <struct>
+                        <field name="Pbu" type="char" length="8" desc="登录或订阅用PBU"/>
+                        <field name="SetID" type="u32" desc="平台内分区号"/>
+                    </struct>
+                </field>
+            </message>
+        </messages>
+        "#;
+
+        config_manager.load_from_str(config_xml).expect("Failed to load test config");
+        config_manager
+    }
+
+    /// 测试构建完整消息并编解码
+    #[test]
+    fn test_build_and_roundtrip() {
+        let config_manager = create_test_config_manager();
+
+        let message = config_manager.builder("Logon").unwrap()
+            .seq_num(7)
+            .set("TradeDate", 20231201u32).unwrap()
+            .set("SenderCompID", "SENDER").unwrap()
+            .set("TargetCompID", "TARGET").unwrap()
+            .set("HeartBtInt", 30u32).unwrap() // U32 值按 U16 字段转换
+            .build()
+            .expect("Failed to build Logon");
+
+        assert_eq!(message.msg_type, 40);
+        assert_eq!(message.seq_num, 7);
+        assert_eq!(message.get_field("HeartBtInt").unwrap(), &FieldValue::U16(30));
+
+        // 字段按消息定义顺序排列
+        let names: Vec<&str> = message.fields.keys().map(|s| s.as_str()).collect();
+        assert_eq!(names, vec!["SenderCompID", "TargetCompID", "HeartBtInt", "TradeDate"]);
+
+        let mut encoder = MessageEncoder::new(&config_manager);
+        let encoded_data = encoder.encode(&message).expect("Failed to encode built message");
+        let mut decoder = MessageDecoder::new(&config_manager, &encoded_data);
+        let decoded_message = decoder.decode().expect("Failed to decode built message");
+        assert_eq!(decoded_message.get_field("SenderCompID").unwrap().as_str().unwrap(), "SENDER");
+
+        println!("✓ Builder roundtrip test passed");
+    }
+
+    /// 测试未知消息名称和未知字段
+    #[test]
+    fn test_unknown_message_and_field() {
+        let config_manager = create_test_config_manager();
+
+        assert!(matches!(
+            config_manager.builder("Logn"),
+            Err(ConfigError::MessageNameNotFound(name)) if name == "Logn"
+        ));
+
+        let result = config_manager.builder("NewOrderSingle").unwrap().set("ClOrdId", "ORD1");
+        match result {
+            Err(MessageError::UnknownField(msg)) => assert!(msg.contains("ClOrdId"), "{}", msg),
+            Err(other) => panic!("Expected UnknownField error, got: {:?}", other),
+            Ok(_) => panic!("Expected UnknownField error"),
+        }
+
+        println!("✓ Unknown message and field test passed");
+    }
+
+    /// 测试字段类型校验
+    #[test]
+    fn test_field_type_validation() {
+        let config_manager = create_test_config_manager();
+
+        let result = config_manager.builder("Logon").unwrap().set("HeartBtInt", 70000u32);
+        assert!(matches!(result, Err(MessageError::ValueExceedsRange(_))));
+
+        let result = config_manager.builder("Logon").unwrap().set("HeartBtInt", "thirty");
+        assert!(matches!(result, Err(MessageError::InvalidFieldValue(_))));
+
+        // 数组元素按字段名逐个转换
+        let result = config_manager.builder("ExecRptSync").unwrap()
+            .set("SyncRequests", vec![
+                Record::new().with("SetID", 1u8).with("Pbu", "PBU001"),
+            ]).unwrap()
+            .build()
+            .unwrap();
+        let elements = result.get_field("SyncRequests").unwrap().as_array().unwrap();
+        assert_eq!(elements[0]["SetID"], FieldValue::U32(1));
+        assert_eq!(elements[0].names().collect::<Vec<_>>(), vec!["Pbu", "SetID"], "元素字段应按结构定义排序");
+
+        let result = config_manager.builder("ExecRptSync").unwrap()
+            .set("SyncRequests", vec![Record::new().with("Pbu", "PBU001")]);
+        assert!(matches!(result, Err(MessageError::MissingRequiredFields(missing)) if missing == vec!["SyncRequests[0].SetID"]));
+
+        let result = config_manager.builder("ExecRptSync").unwrap()
+            .set("SyncRequests", vec![Record::new().with("Pbu", "PBU001").with("SetId", 1u32)]);
+        assert!(matches!(result, Err(MessageError::UnknownField(name)) if name == "SyncRequests[0].SetId"));
+
+        println!("✓ Field type validation test passed");
+    }
+
+    /// 测试缺失必填字段
+    #[test]
+    fn test_missing_required_fields() {
+        let config_manager = create_test_config_manager();
+
+        let result = config_manager.builder("Logon").unwrap()
+            .set("SenderCompID", "SENDER").unwrap()
+            .build();
+
+        match result {
+            Err(MessageError::MissingRequiredFields(missing)) => {
+                assert_eq!(missing, vec!["TargetCompID", "HeartBtInt", "TradeDate"]);
+            },
+            other => panic!("Expected MissingRequiredFields error, got: {:?}", other),
+        }
+
+        println!("✓ Missing required fields test passed");
+    }
+
+    /// 测试按 BizID 处理扩展字段
+    #[test]
+    fn test_extension_fields() {
+        let config_manager = create_test_config_manager();
+
+        // 扩展字段可以在 BizID 之前设置，并排在基础字段之后
+        let message = config_manager.builder("NewOrderSingle").unwrap()
+            .set("Custodian", "001").unwrap()
+            .set("BizID", 300060u32).unwrap()
+            .set("ClOrdID", "ORD1").unwrap()
+            .set("Price", 10.5).unwrap()
+            .build()
+            .expect("Failed to build order with extension");
+        let names: Vec<&str> = message.fields.keys().map(|s| s.as_str()).collect();
+        assert_eq!(names, vec!["BizID", "ClOrdID", "Price", "Custodian"]);
+
+        // 缺少当前 BizID 的扩展字段
+        let result = config_manager.builder("NewOrderSingle").unwrap()
+            .set("BizID", 300070u32).unwrap()
+            .set("ClOrdID", "ORD1").unwrap()
+            .set("Price", 10.5).unwrap()
+            .build();
+        assert!(matches!(result, Err(MessageError::MissingRequiredFields(missing)) if missing == vec!["DividendSelect"]));
+
+        // 设置了其他 BizID 的扩展字段
+        let result = config_manager.builder("NewOrderSingle").unwrap()
+            .set("BizID", 300070u32).unwrap()
+            .set("ClOrdID", "ORD1").unwrap()
+            .set("Price", 10.5).unwrap()
+            .set("DividendSelect", "U").unwrap()
+            .set("Custodian", "001").unwrap()
+            .build();
+        assert!(matches!(result, Err(MessageError::UnknownField(msg)) if msg.contains("Custodian")));
+
+        // 没有扩展定义的 BizID 不需要扩展字段
+        let result = config_manager.builder("NewOrderSingle").unwrap()
+            .set("BizID", 100010u32).unwrap()
+            .set("ClOrdID", "ORD1").unwrap()
+            .set("Price", 10.5).unwrap()
+            .build();
+        assert!(result.is_ok());
+
+        // 多个多余的扩展字段时按设置顺序报告第一个，每次结果相同
+        for _ in 0..10 {
+            let result = config_manager.builder("NewOrderSingle").unwrap()
+                .set("BizID", 100010u32).unwrap()
+                .set("DividendSelect", "U").unwrap()
+                .set("ClOrdID", "ORD1").unwrap()
+                .set("Price", 10.5).unwrap()
+                .set("Custodian", "001").unwrap()
+                .build();
+            assert!(matches!(result, Err(MessageError::UnknownField(ref msg)) if msg.starts_with("DividendSelect")), "应报告最先设置的字段: {:?}", result);
+        }
+
+        println!("✓ Extension fields test passed");
+    }
+}
@@ -0,0 +1,91 @@
+use sse_tdgw_binary::message::{FieldValue, Message, Record};
+use sse_tdgw_binary::util::MessageError;
+
+/// 字段路径测试
+/// 测试按 `SyncResponses[1].EndReportIndex` 形式的路径读取和修改字段
+#[cfg(test)]
+mod message_path_tests {
+    use super::*;
+
+    /// 创建包含数组字段的同步响应消息
+    fn create_sync_response() -> Message {
+        let mut message = Message::new(207, 1);
+        message.add_field("PartitionNo".to_string(), FieldValue::U32(3));
+        message.add_field("SyncResponses".to_string(), FieldValue::Array(vec![
+            Record::new().with("Pbu", "PBU001").with("SetID", 1u32).with("EndReportIndex", 100u64),
+            Record::new().with("Pbu", "PBU002").with("SetID", 2u32).with("EndReportIndex", 200u64),
+        ]));
+        message
+    }
+
+    /// 断言路径错误出现在指定的路径段
+    fn assert_path_error<T: std::fmt::Debug>(result: Result<T, MessageError>, expected_segment: &str, expected_reason: &str) {
+        match result {
+            Err(MessageError::FieldPathError(_, segment, reason)) => {
+                assert_eq!(segment, expected_segment, "出错的路径段不一致");
+                assert!(reason.contains(expected_reason), "错误原因不符: {}", reason);
+            },
+            other => panic!("Expected FieldPathError, got: {:?}", other),
+        }
+    }
+
+    /// 测试按路径读取字段
+    #[test]
+    fn test_get_path() {
+        let message = create_sync_response();
+
+        assert_eq!(message.get_path("PartitionNo").unwrap(), &FieldValue::U32(3));
+        assert_eq!(message.get_path("SyncResponses[1].EndReportIndex").unwrap(), &FieldValue::U64(200));
+        assert_eq!(message.get_u64_path("SyncResponses[1].EndReportIndex").unwrap(), 200);
+        assert_eq!(message.get_u32_path("SyncResponses[0].SetID").unwrap(), 1);
+        assert_eq!(message.get_str_path("SyncResponses[0].Pbu").unwrap(), "PBU001");
+        assert_eq!(message.get_path("SyncResponses").unwrap().as_array().unwrap().len(), 2);
+
+        println!("✓ Get path test passed");
+    }
+
+    /// 测试错误信息指出出错的路径段
+    #[test]
+    fn test_path_errors() {
+        let message = create_sync_response();
+
+        assert_path_error(message.get_path("SyncResponse[1].EndReportIndex"), "SyncResponse", "field not found");
+        assert_path_error(message.get_path("SyncResponses[5].EndReportIndex"), "SyncResponses[5]", "out of bounds for array of length 2");
+        assert_path_error(message.get_path("SyncResponses[1].EndReportIdx"), "SyncResponses[1].EndReportIdx", "field not found");
+        assert_path_error(message.get_path("SyncResponses.EndReportIndex"), "SyncResponses.EndReportIndex", "array requires an index");
+        assert_path_error(message.get_path("PartitionNo[0]"), "PartitionNo[0]", "is not an array");
+        assert_path_error(message.get_path("SyncResponses[1]"), "SyncResponses[1]", "expected a field");
+        assert_path_error(message.get_path("SyncResponses[x].SetID"), "SyncResponses[x]", "invalid index 'x'");
+        assert_path_error(message.get_path("SyncResponses[1]..SetID"), "SyncResponses[1].", "empty field name");
+        assert_path_error(message.get_u32_path("SyncResponses[1].EndReportIndex"), "SyncResponses[1].EndReportIndex", "expected FieldValue::U32, got FieldValue::U64");
+
+        println!("✓ Path errors test passed");
+    }
+
+    /// 测试按路径修改字段
+    #[test]
+    fn test_set_path() {
+        let mut message = create_sync_response();
+
+        let old = message.set_path("SyncResponses[1].EndReportIndex", 250u64).unwrap();
+        assert_eq!(old, Some(FieldValue::U64(200)));
+        assert_eq!(message.get_u64_path("SyncResponses[1].EndReportIndex").unwrap(), 250);
+
+        // 不存在的字段会被添加
+        assert_eq!(message.set_path("SyncResponses[0].Text", "OK").unwrap(), None);
+        assert_eq!(message.get_str_path("SyncResponses[0].Text").unwrap(), "OK");
+        assert_eq!(message.set_path("TradeDate", 20231201u32).unwrap(), None);
+        assert_eq!(message.get_u32_path("TradeDate").unwrap(), 20231201);
+
+        if let FieldValue::U32(partition) = message.get_path_mut("PartitionNo").unwrap() {
+            *partition += 1;
+        }
+        assert_eq!(message.get_u32_path("PartitionNo").unwrap(), 4);
+
+        assert_path_error(message.set_path("SyncResponses[2].SetID", 3u32), "SyncResponses[2]", "out of bounds");
+        assert_path_error(message.set_path("SyncResponses[1]", 3u32), "SyncResponses[1]", "must end with a field name");
+        assert_path_error(message.set_path("PartitionNo.Value", 3u32), "PartitionNo.Value", "has no fields");
+
+        println!("✓ Set path test passed");
+    }
+}
@@ -0,0 +1,153 @@
+use serde::de::DeserializeSeed;
+use sse_tdgw_binary::codec::encoder::MessageEncoder;
+use sse_tdgw_binary::codec::decoder::MessageDecoder;
+use sse_tdgw_binary::config::manager::ConfigManager;
+use sse_tdgw_binary::message::{FieldValue, Message, MessageSeed, Record};
+
+/// 消息序列化测试
+/// 测试 Message 与 serde 格式之间的转换，包括字段顺序、数组字段名和按定义还原数值类型
+#[cfg(test)]
+mod message_serde_tests {
+    use super::*;
+
+    /// 创建测试用的配置管理器
+    fn create_test_config_manager() -> ConfigManager {
+        let mut config_manager = ConfigManager::new();
+
+        let config_xml = r#"
+        <messages>
+            <message type="40" name="Logon">
+                <field name="SenderCompID" type="char" length="32" desc="发送方代码"/>
+                <field name="HeartBtInt" type="u16" desc="心跳间隔（秒）"/>
+                <field name="TradeDate" type="date" desc="交易日期（YYYYMMDD）"/>
+                <field name="Price" type="price" desc="价格"/>
+                <field name="Signature" type="bytes" length="4" desc="签名"/>
+            </message>
+            <message type="58" name="NewOrderSingle">
+                <field name="BizID" type="u32" desc="业务代码"/>
+                <field name="ClOrdID" type="char" length="10" desc="会员内部订单编号"/>
+                <extension biz_id="300060">
+                    <field name="Custodian" type="char" length="3" desc="转托管的目标方代理人"/>
+                </extension>
+            </message>
+            <message type="207" name="ExecRptSyncRsp">
+                <field name="SyncResponses" type="array" desc="同步响应项数组">
+                    <length_field name="NoGroups" type="u16" desc="同步响应项个数"/>
+                    <struct>
+                        <field name="Pbu" type="char" length="8" desc="登录或订阅用 PBU"/>
+                        <field name="SetID" type="u32" desc="平台内分区号"/>
+                        <field name="EndReportIndex" type="u64" desc="分区最大回报序号"/>
+                    </struct>
+                </field>
+            </message>
+        </messages>
+        "#;
+
+        config_manager.load_from_str(config_xml).expect("Failed to load test config");
+        config_manager
+    }
+
+    fn from_json(config_manager: &ConfigManager, json: &str) -> serde_json::Result<Message> {
+        let mut deserializer = serde_json::Deserializer::from_str(json);
+        MessageSeed::new(config_manager).deserialize(&mut deserializer)
+    }
+
+    fn create_logon() -> Message {
+        let mut message = Message::new(40, 3);
+        message.add_field("SenderCompID".to_string(), FieldValue::from("SENDER"));
+        message.add_field("HeartBtInt".to_string(), FieldValue::U16(30));
+        message.add_field("TradeDate".to_string(), FieldValue::U32(20231201));
+        message.add_field("Price".to_string(), FieldValue::Float(10.5));
+        message.add_field("Signature".to_string(), FieldValue::Bytes(vec![0xde, 0xad, 0xbe, 0xef]));
+        message
+    }
+
+    /// 测试 JSON 往返后数值类型与解码结果一致
+    #[test]
+    fn test_json_roundtrip_restores_types() {
+        let config_manager = create_test_config_manager();
+        let message = create_logon();
+
+        let json = serde_json::to_string(&message).expect("Failed to serialize message");
+        assert_eq!(
+            json,
+            r#"{"msg_type":40,"seq_num":3,"fields":{"SenderCompID":"SENDER","HeartBtInt":30,"TradeDate":20231201,"Price":10.5,"Signature":"deadbeef"}}"#,
+            "字段应按插入顺序输出，字节输出为十六进制"
+        );
+
+        let restored = from_json(&config_manager, &json).expect("Failed to deserialize message");
+        assert_eq!(restored.msg_type, 40);
+        assert_eq!(restored.seq_num, 3);
+        assert_eq!(restored.fields, message.fields, "反序列化后的字段类型应与定义一致");
+
+        // 与编解码得到的消息一致
+        let mut encoder = MessageEncoder::new(&config_manager);
+        let encoded_data = encoder.encode(&restored).unwrap();
+        let mut decoder = MessageDecoder::new(&config_manager, &encoded_data);
+        assert_eq!(decoder.decode().unwrap().fields, message.fields);
+
+        println!("✓ JSON roundtrip test passed");
+    }
+
+    /// 测试数组元素输出为带字段名的对象
+    #[test]
+    fn test_array_as_named_objects() {
+        let config_manager = create_test_config_manager();
+
+        let mut message = Message::new(207, 1);
+        message.add_field("SyncResponses".to_string(), FieldValue::Array(vec![
+            Record::new().with("Pbu", "PBU001").with("SetID", 1u32).with("EndReportIndex", 100u64),
+            Record::new().with("Pbu", "PBU002").with("SetID", 2u32).with("EndReportIndex", 200u64),
+        ]));
+
+        let json = serde_json::to_string(&message).unwrap();
+        assert_eq!(
+            json,
+            r#"{"msg_type":207,"seq_num":1,"fields":{"SyncResponses":[{"Pbu":"PBU001","SetID":1,"EndReportIndex":100},{"Pbu":"PBU002","SetID":2,"EndReportIndex":200}]}}"#
+        );
+
+        let restored = from_json(&config_manager, &json).unwrap();
+        assert_eq!(restored.fields, message.fields);
+
+        // 按结构定义位置排列的列表同样可以还原
+        let positional = r#"{"msg_type":207,"seq_num":1,"fields":{"SyncResponses":[["PBU001",1,100],["PBU002",2,200]]}}"#;
+        assert_eq!(from_json(&config_manager, positional).unwrap().fields, message.fields);
+
+        println!("✓ Named array elements test passed");
+    }
+
+    /// 测试按定义转换和报错
+    #[test]
+    fn test_schema_guided_deserialization() {
+        let config_manager = create_test_config_manager();
+
+        // fields 出现在 msg_type 之前，扩展字段同样可以识别
+        let message = from_json(
+            &config_manager,
+            r#"{"fields":{"BizID":300060,"ClOrdID":"ORD1","Custodian":"001"},"seq_num":2,"msg_type":58}"#,
+        ).unwrap();
+        assert_eq!(message.get_field("BizID").unwrap(), &FieldValue::U32(300060));
+        assert_eq!(message.get_field("Custodian").unwrap(), &FieldValue::from("001"));
+
+        // 紧凑格式中消息以列表表示
+        let message = from_json(&config_manager, r#"[58, 2, {"BizID": "100010", "ClOrdID": "ORD1"}]"#).unwrap();
+        assert_eq!(message.get_field("BizID").unwrap(), &FieldValue::U32(100010));
+
+        let err = from_json(&config_manager, r#"{"msg_type":58,"seq_num":1,"fields":{"ClOrdId":"ORD1"}}"#).unwrap_err();
+        assert!(err.to_string().contains("ClOrdId"), "{}", err);
+
+        let err = from_json(&config_manager, r#"{"msg_type":40,"seq_num":1,"fields":{"HeartBtInt":70000}}"#).unwrap_err();
+        assert!(err.to_string().contains("out of range"), "{}", err);
+
+        let err = from_json(&config_manager, r#"{"msg_type":99,"seq_num":1,"fields":{}}"#).unwrap_err();
+        assert!(err.to_string().contains("Unknown message type: 99"), "{}", err);
+
+        let err = from_json(
+            &config_manager,
+            r#"{"msg_type":207,"seq_num":1,"fields":{"SyncResponses":[{"Pbu":"PBU001","SetID":1}]}}"#,
+        ).unwrap_err();
+        assert!(err.to_string().contains("SyncResponses[0].EndReportIndex"), "{}", err);
+
+        println!("✓ Schema guided deserialization test passed");
+    }
+}
@@ -0,0 +1,222 @@
+use sse_tdgw_binary::codec::decoder::MessageDecoder;
+use sse_tdgw_binary::codec::encoder::MessageEncoder;
+use sse_tdgw_binary::codec::{FieldSize, MessageLayout, MessageView};
+use sse_tdgw_binary::config::manager::ConfigManager;
+use sse_tdgw_binary::message::{FieldValue, Message, Record};
+use sse_tdgw_binary::util::{ConversionError, MessageError};
+
+/// 消息视图测试
+/// 测试 `MessageView` 按需解码的结果与 `MessageDecoder` 一致
+#[cfg(test)]
+mod message_view_tests {
+    use super::*;
+
+    /// 创建包含定长字段、变长字段和扩展字段的测试配置管理器
+    fn create_test_config_manager() -> ConfigManager {
+        let mut config_manager = ConfigManager::new();
+        let config_xml = r#"
+        <messages>
+            <message type="4001" name="ViewTestMessage">
+                <field name="BizID" type="u32" desc="业务编号"/>
+                <field name="ClOrdID" type="char" length="10" desc="订单编号"/>
+                <field name="Price" type="price" desc="价格"/>
+                <field name="Items" type="array" desc="明细">
+                    <length_field name="NoItems" type="u16" desc="明细个数"/>
+                    <struct>
+                        <field name="ItemID" type="u32" desc="明细编号"/>
+                        <field name="ItemQty" type="quantity" desc="明细数量"/>
+                    </struct>
+                </field>
+                <field name="Signature" type="bytes" desc="变长签名">
+                    <length_field name="SignatureLen" type="u16" desc="签名长度"/>
+                </field>
+                <field name="TradeDate" type="date" desc="交易日期"/>
+                <extension biz_id="300060">
+                    <field name="Custodian" type="char" length="3" desc="托管方代码"/>
+                    <field name="FundType" type="u8" desc="基金类型"/>
+                </extension>
+            </message>
+            <message type="4002" name="FixedTestMessage">
+                <field name="PartitionNo" type="u32" desc="分区号"/>
+                <field name="Reserved" type="bytes" length="4" desc="保留区"/>
+            </message>
+        </messages>
+        "#;
+        config_manager.load_from_str(config_xml).expect("Failed to load view test config");
+        config_manager
+    }
+
+    fn create_test_message() -> Message {
+        let mut message = Message::new(4001, 7);
+        message.add_field("BizID".to_string(), FieldValue::U32(300060));
+        message.add_field("ClOrdID".to_string(), FieldValue::from("ORD001"));
+        message.add_field("Price".to_string(), FieldValue::Float(10.5));
+        message.add_field("Items".to_string(), FieldValue::Array(vec![
+            Record::new().with("ItemID", 1u32).with("ItemQty", FieldValue::Float(100.0)),
+            Record::new().with("ItemID", 2u32).with("ItemQty", FieldValue::Float(200.0)),
+        ]));
+        message.add_field("Signature".to_string(), FieldValue::Bytes(vec![0xde, 0xad, 0xbe]));
+        message.add_field("TradeDate".to_string(), FieldValue::U32(20231201));
+        message.add_field("Custodian".to_string(), FieldValue::from("ABC"));
+        message.add_field("FundType".to_string(), FieldValue::U8(2));
+        message
+    }
+
+    fn encode(config_manager: &ConfigManager, message: &Message) -> Vec<u8> {
+        let mut encoder = MessageEncoder::new(config_manager);
+        encoder.encode(message).expect("Failed to encode message")
+    }
+
+    /// 测试布局中的偏移和大小
+    #[test]
+    fn test_message_layout() {
+        let config_manager = create_test_config_manager();
+
+        let layout = MessageLayout::new(config_manager.get_message_def(4001).unwrap());
+        let body = layout.body();
+        assert_eq!(body.index_of("Price"), Some(2));
+        assert_eq!(body.fields()[2].offset, Some(14), "Price 应位于 BizID 和 ClOrdID 之后");
+        assert_eq!(body.fields()[3].offset, Some(22), "第一个变长字段本身有固定偏移");
+        assert_eq!(body.fields()[3].size, FieldSize::Prefixed { prefix: 2, unit: 12 });
+        assert_eq!(body.fields()[5].offset, None, "变长字段之后的字段没有固定偏移");
+        assert_eq!(body.fixed_len(), None);
+        assert_eq!(layout.extension(300060).unwrap().fixed_len(), Some(4));
+
+        let layout = MessageLayout::new(config_manager.get_message_def(4002).unwrap());
+        assert_eq!(layout.body().fixed_len(), Some(8));
+        assert!(!layout.has_extensions());
+
+        println!("✓ Message layout test passed");
+    }
+
+    /// 测试配置管理器加载时预先计算的布局
+    #[test]
+    fn test_compiled_layouts() {
+        let mut config_manager = create_test_config_manager();
+
+        let layout = config_manager.get_layout_by_name("FixedTestMessage").expect("加载时应生成布局");
+        assert_eq!(layout.msg_type, 4002);
+        assert_eq!(layout.fixed_body_len(), Some(8));
+        assert_eq!(config_manager.get_layout(4001).unwrap().fixed_body_len(), None, "含变长字段的消息不是定长消息");
+
+        // 定长消息的消息体长度不足时直接报错
+        let mut frame = vec![0, 0, 0x0f, 0xa2, 0, 0, 0, 1, 0, 0, 0, 4, 0, 0, 0, 3];
+        let checksum = frame.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte));
+        frame.extend_from_slice(&[0, 0, 0, checksum]);
+        let result = MessageDecoder::new(&config_manager, &frame).decode();
+        assert!(matches!(result, Err(MessageError::BodyTooShort)), "Expected BodyTooShort error, got: {:?}", result);
+
+        // 长度足够时按布局偏移直接读取各字段
+        let mut full = vec![0, 0, 0x0f, 0xa2, 0, 0, 0, 1, 0, 0, 0, 8, 0, 0, 0, 3, 1, 2, 3, 4];
+        let checksum = full.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte));
+        full.extend_from_slice(&[0, 0, 0, checksum]);
+        let message = MessageDecoder::new(&config_manager, &full).decode().unwrap();
+        assert_eq!(message.get_u32("PartitionNo").unwrap(), 3);
+        assert_eq!(message.get_field("Reserved").and_then(FieldValue::as_bytes), Some(&[1u8, 2, 3, 4][..]));
+        let names: Vec<&str> = message.fields.keys().map(String::as_str).collect();
+        assert_eq!(names, vec!["PartitionNo", "Reserved"], "字段顺序应与定义一致");
+
+        // 重新加载同一消息类型时布局随之更新
+        config_manager.load_from_str(r#"
+        <messages>
+            <message type="4002" name="FixedTestMessage">
+                <field name="PartitionNo" type="u32" desc="分区号"/>
+            </message>
+        </messages>
+        "#).unwrap();
+        assert_eq!(config_manager.get_layout(4002).unwrap().fixed_body_len(), Some(4));
+        let message = MessageDecoder::new(&config_manager, &frame).decode().unwrap();
+        assert_eq!(message.get_u32("PartitionNo").unwrap(), 3);
+
+        println!("✓ Compiled layouts test passed");
+    }
+
+    /// 测试按需读取的字段与完整解码结果一致
+    #[test]
+    fn test_view_matches_decoder() {
+        let config_manager = create_test_config_manager();
+        let frame = encode(&config_manager, &create_test_message());
+        let decoded = MessageDecoder::new(&config_manager, &frame).decode().unwrap();
+
+        let layout = config_manager.get_layout(4001).unwrap();
+        let view = MessageView::new(layout, &frame).expect("Failed to create view");
+        assert_eq!(view.msg_type(), 4001);
+        assert_eq!(view.seq_num(), 7);
+
+        for (name, value) in &decoded.fields {
+            assert_eq!(&view.get(name).unwrap(), value, "字段 {} 与解码结果不一致", name);
+        }
+        assert_eq!(view.get_at(5).unwrap(), FieldValue::U32(20231201));
+        let message = view.to_message().unwrap();
+        assert_eq!((message.msg_type, message.seq_num), (decoded.msg_type, decoded.seq_num));
+        assert_eq!(message.fields, decoded.fields, "to_message 应与解码结果一致");
+
+        // 变长字段之后的字段和扩展字段也能直接读取
+        assert_eq!(view.get_u32("TradeDate").unwrap(), 20231201);
+        assert_eq!(view.get_u8("FundType").unwrap(), 2);
+        assert_eq!(view.get_f64("Price").unwrap(), 10.5);
+
+        println!("✓ View matches decoder test passed");
+    }
+
+    /// 测试字符串和字节字段借用帧中的数据
+    #[test]
+    fn test_zero_copy_access() {
+        let config_manager = create_test_config_manager();
+        let frame = encode(&config_manager, &create_test_message());
+        let layout = config_manager.get_layout(4001).unwrap();
+        let view = MessageView::new(layout, &frame).unwrap();
+
+        let cl_ord_id = view.get_str("ClOrdID").unwrap();
+        assert_eq!(cl_ord_id, "ORD001");
+        assert!(frame.as_ptr_range().contains(&cl_ord_id.as_ptr()), "字符串应借用帧中的数据");
+
+        let signature = view.get_bytes("Signature").unwrap();
+        assert_eq!(signature, &[0xde, 0xad, 0xbe], "变长字节不应包含长度前缀");
+        assert!(frame.as_ptr_range().contains(&signature.as_ptr()));
+
+        assert_eq!(view.raw("Signature").unwrap(), &[0x00, 0x03, 0xde, 0xad, 0xbe]);
+        assert_eq!(view.get_str("Custodian").unwrap(), "ABC");
+
+        println!("✓ Zero-copy access test passed");
+    }
+
+    /// 测试视图的错误处理
+    #[test]
+    fn test_view_errors() {
+        let config_manager = create_test_config_manager();
+        let frame = encode(&config_manager, &create_test_message());
+        let layout = config_manager.get_layout(4001).unwrap();
+
+        let fixed_layout = config_manager.get_layout(4002).unwrap();
+        match MessageView::new(fixed_layout, &frame) {
+            Err(MessageError::UnexpectedMessageType(expected, actual)) => {
+                assert_eq!((expected, actual), (4002, 4001));
+            },
+            other => panic!("Expected UnexpectedMessageType error, got: {:?}", other),
+        }
+
+        let mut corrupted = frame.clone();
+        corrupted[14] ^= 0xff;
+        assert!(matches!(MessageView::new(layout, &corrupted), Err(MessageError::ChecksumError)));
+        assert!(matches!(MessageView::new(layout, &frame[..frame.len() - 6]), Err(MessageError::BodyTooShort)));
+
+        let view = MessageView::new(layout, &frame).unwrap();
+        match view.get("OrderQty") {
+            Err(MessageError::FieldNotFound(name)) => assert!(name.starts_with("OrderQty"), "错误应包含字段名: {}", name),
+            other => panic!("Expected FieldNotFound error, got: {:?}", other),
+        }
+        assert!(matches!(view.get_at(10), Err(MessageError::FieldNotFound(_))));
+
+        match view.get_u64("BizID") {
+            Err(MessageError::FieldConversionError(name, err)) => {
+                assert_eq!(name, "BizID");
+                assert_eq!(err, ConversionError { expected: "U64", actual: "U32" });
+            },
+            other => panic!("Expected FieldConversionError error, got: {:?}", other),
+        }
+        assert!(matches!(view.get_str("Signature"), Err(MessageError::FieldConversionError(_, _))));
+
+        println!("✓ View errors test passed");
+    }
+}
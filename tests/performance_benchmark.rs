@@ -1,8 +1,10 @@
 use std::time::{Duration, Instant};
 use sse_tdgw_binary::codec::encoder::MessageEncoder;
 use sse_tdgw_binary::codec::decoder::MessageDecoder;
+use sse_tdgw_binary::codec::batch::DecodeFrames;
+use sse_tdgw_binary::codec::checksum::Sum8;
 use sse_tdgw_binary::config::manager::ConfigManager;
-use sse_tdgw_binary::message::{Message, FieldValue};
+use sse_tdgw_binary::message::{Message, FieldValue, Record};
 
 /// 性能基准测试
 /// 测试编解码器在各种场景下的性能表现
@@ -137,11 +139,11 @@ mod performance_tests {
         
         let mut array_data = Vec::new();
         for i in 0..array_size {
-            array_data.push(vec![
-                FieldValue::U32(seq * 1000 + i as u32),
-                FieldValue::Float(10000000.0 + (i as f64 % 1000000.0)),
-                FieldValue::Float(1000000.0 + (i as f64 % 100000.0)),
-            ]);
+            array_data.push(Record::from([
+                ("item_id", FieldValue::U32(seq * 1000 + i as u32)),
+                ("item_price", FieldValue::Float(10000000.0 + (i as f64 % 1000000.0))),
+                ("item_qty", FieldValue::Float(1000000.0 + (i as f64 % 100000.0))),
+            ]));
         }
         
         message.add_field("items".to_string(), FieldValue::Array(array_data));
@@ -151,7 +153,7 @@ mod performance_tests {
     /// 执行性能测试的辅助函数
     fn run_performance_test<F>(test_name: &str, iterations: usize, test_fn: F) -> (Duration, f64)
     where
-        F: Fn() -> (),
+        F: Fn(),
     {
         // 预热
         for _ in 0..100 {
@@ -333,24 +335,18 @@ mod performance_tests {
         
         let start_time = Instant::now();
         
-        // 批量编码
-        let mut encoded_messages = Vec::new();
-        for message in &messages {
-            let mut encoder = MessageEncoder::new(&config_manager);
-            let encoded_data = encoder.encode(message).expect("Encode failed");
-            encoded_messages.push(encoded_data);
-        }
+        // 批量编码到连续缓冲区
+        let mut encoder = MessageEncoder::new(&config_manager);
+        let batch = encoder.encode_batch(&messages);
+        assert_eq!(batch.errors().count(), 0, "Encode failed");
         
         let encode_time = start_time.elapsed();
         
         // 批量解码
         let decode_start = Instant::now();
-        let mut decoded_messages = Vec::new();
-        for encoded_data in &encoded_messages {
-            let mut decoder = MessageDecoder::new(&config_manager, encoded_data);
-            let decoded_message = decoder.decode().expect("Decode failed");
-            decoded_messages.push(decoded_message);
-        }
+        let decoded_messages: Vec<Message> = DecodeFrames::new(&config_manager, batch.as_bytes())
+            .map(|frame| frame.result.expect("Decode failed"))
+            .collect();
         
         let decode_time = decode_start.elapsed();
         let total_time = start_time.elapsed();
@@ -377,6 +373,31 @@ mod performance_tests {
         println!("✓ Batch message processing performance test passed");
     }
 
+    /// 测试校验和性能：分块累加与逐字节累加对比
+    #[test]
+    fn test_checksum_performance() {
+        let data: Vec<u8> = (0..64 * 1024u32).map(|i| (i * 31 + 7) as u8).collect();
+        let iterations = 200;
+
+        let byte_loop = |data: &[u8]| data.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte));
+        assert_eq!(Sum8::sum(&data), byte_loop(&data), "分块累加结果应与逐字节累加一致");
+
+        let (loop_time, _) = run_performance_test("Checksum byte loop", iterations, || {
+            std::hint::black_box(byte_loop(std::hint::black_box(&data)));
+        });
+        let (chunked_time, _) = run_performance_test("Checksum Sum8", iterations, || {
+            std::hint::black_box(Sum8::sum(std::hint::black_box(&data)));
+        });
+
+        let megabytes = (data.len() * iterations) as f64 / (1024.0 * 1024.0);
+        println!("[Checksum] byte loop: {:.2} MB/s, Sum8: {:.2} MB/s ({:.2}x)",
+                megabytes / loop_time.as_secs_f64(),
+                megabytes / chunked_time.as_secs_f64(),
+                loop_time.as_secs_f64() / chunked_time.as_secs_f64());
+
+        println!("✓ Checksum performance test passed");
+    }
+
     /// 测试内存使用效率
     #[test]
     fn test_memory_efficiency() {
@@ -426,7 +447,8 @@ mod performance_tests {
         println!("\n=== Comprehensive Performance Report ===");
         
         // 测试不同消息类型的性能
-        let test_cases: Vec<(&str, Box<dyn Fn() -> Message>, usize)> = vec![
+        type MessageFactory = Box<dyn Fn() -> Message>;
+        let test_cases: Vec<(&str, MessageFactory, usize)> = vec![
             ("Small", Box::new(|| create_small_message(12345)), 5000),
             ("Medium", Box::new(|| create_medium_message(12345)), 2000),
             ("Large", Box::new(|| create_large_message(12345)), 500),
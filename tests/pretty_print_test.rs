@@ -0,0 +1,130 @@
+use sse_tdgw_binary::codec::decoder::MessageDecoder;
+use sse_tdgw_binary::codec::encoder::MessageEncoder;
+use sse_tdgw_binary::config::manager::ConfigManager;
+use sse_tdgw_binary::message::{FieldValue, Message, MessagePrinter, PrintStyle, Record};
+
+/// 消息格式化测试
+/// 测试按消息定义输出字段描述、取值含义以及价格、日期和时间的格式
+#[cfg(test)]
+mod pretty_print_tests {
+    use super::*;
+
+    fn create_test_config_manager() -> ConfigManager {
+        let mut config_manager = ConfigManager::new();
+        let config_xml = r#"
+        <messages>
+            <message type="58" name="NewOrderSingle">
+                <field name="BizID" type="u32" desc="业务代码"/>
+                <field name="ClOrdID" type="char" length="10" desc="会员内部订单编号"/>
+                <field name="Side" type="char" length="1" desc="买卖方向：1=买, 2=卖"/>
+                <field name="Price" type="price" desc="申报价格"/>
+                <field name="OrderQty" type="quantity" desc="申报数量"/>
+                <field name="TradeDate" type="date" desc="交易日期"/>
+                <field name="TransactTime" type="ntime" desc="申报时间"/>
+                <field name="Parties" type="array" desc="参与方">
+                    <length_field name="NoParties" type="u8" desc="参与方个数"/>
+                    <struct>
+                        <field name="PartyID" type="char" length="4" desc="参与方代码"/>
+                        <field name="PartyRole" type="u8" desc="参与方角色：1=经纪商, 2=托管方"/>
+                    </struct>
+                </field>
+                <extension biz_id="300070">
+                    <field name="DividendSelect" type="char" length="1" desc="分红方式：U=红利转投, C=现金分红"/>
+                </extension>
+            </message>
+        </messages>
+        "#;
+        config_manager.load_from_str(config_xml).expect("Failed to load pretty print test config");
+        config_manager
+    }
+
+    fn create_order() -> Message {
+        let mut message = Message::new(58, 12);
+        message.add_field("BizID".to_string(), FieldValue::U32(300070));
+        message.add_field("ClOrdID".to_string(), FieldValue::from("ORD001"));
+        message.add_field("Side".to_string(), FieldValue::from("1"));
+        message.add_field("Price".to_string(), FieldValue::Float(10.5));
+        message.add_field("OrderQty".to_string(), FieldValue::Float(300.0));
+        message.add_field("TradeDate".to_string(), FieldValue::U32(20240105));
+        message.add_field("TransactTime".to_string(), FieldValue::U64(930001234567));
+        message.add_field("Parties".to_string(), FieldValue::Array(vec![
+            Record::new().with("PartyID", "P001").with("PartyRole", 1u8),
+            Record::new().with("PartyID", "P002").with("PartyRole", 2u8),
+        ]));
+        message.add_field("DividendSelect".to_string(), FieldValue::from("C"));
+        message
+    }
+
+    /// 测试多行格式
+    #[test]
+    fn test_multi_line() {
+        let config_manager = create_test_config_manager();
+        let frame = MessageEncoder::new(&config_manager).encode(&create_order()).unwrap();
+        let message = MessageDecoder::new(&config_manager, &frame).decode().unwrap();
+
+        let text = config_manager.printer(58).unwrap().format(&message);
+        let lines: Vec<_> = text.lines().collect();
+        assert_eq!(lines[0], "NewOrderSingle (58) seq=12");
+        assert!(lines.contains(&"  Side           = 1 (买)  # 买卖方向：1=买, 2=卖"), "{}", text);
+        assert!(lines.contains(&"  Price          = 10.50000  # 申报价格"), "{}", text);
+        assert!(lines.contains(&"  OrderQty       = 300.000  # 申报数量"), "{}", text);
+        assert!(lines.contains(&"  TradeDate      = 2024-01-05  # 交易日期"), "{}", text);
+        assert!(lines.contains(&"  TransactTime   = 09:30:00.1234567  # 申报时间"), "{}", text);
+        assert!(lines.contains(&"  Parties        = 2 item(s)  # 参与方"), "{}", text);
+        assert!(lines.contains(&"    [1]"), "{}", text);
+        assert!(lines.contains(&"      PartyRole = 2 (托管方)  # 参与方角色：1=经纪商, 2=托管方"), "{}", text);
+        assert_eq!(lines.last(), Some(&"  DividendSelect = C (现金分红)  # 分红方式：U=红利转投, C=现金分红"), "扩展字段在最后输出");
+
+        println!("✓ Multi line test passed");
+    }
+
+    /// 测试单行格式
+    #[test]
+    fn test_single_line() {
+        let config_manager = create_test_config_manager();
+        let def = config_manager.get_message_def(58).unwrap();
+
+        let text = MessagePrinter::new(def).single_line().format(&create_order());
+        assert_eq!(
+            text,
+            "MsgType=58|MsgName=NewOrderSingle|SeqNum=12|BizID=300070|ClOrdID=ORD001|Side=1|Price=10.50000|OrderQty=300.000|\
+             TradeDate=2024-01-05|TransactTime=09:30:00.1234567|NoParties=2|PartyID=P001|PartyRole=1|PartyID=P002|PartyRole=2|\
+             DividendSelect=C|"
+        );
+        assert!(!text.contains('\n'));
+
+        // 值中的分隔符转义后不会被误认为字段边界
+        let mut message = create_order();
+        message.add_field("ClOrdID".to_string(), FieldValue::from("A|B=C\\\n"));
+        message.add_field("Extra".to_string(), FieldValue::from("x=1|y"));
+        let text = MessagePrinter::new(def).single_line().format(&message);
+        assert!(text.contains("|ClOrdID=A\\|B\\=C\\\\\\n|Side=1|"), "{}", text);
+        assert!(text.ends_with("|Extra=x\\=1\\|y|"), "{}", text);
+        assert!(!text.contains('\n'));
+
+        println!("✓ Single line test passed");
+    }
+
+    /// 测试无效取值和消息定义之外的字段
+    #[test]
+    fn test_raw_and_unknown_values() {
+        let config_manager = create_test_config_manager();
+        let def = config_manager.get_message_def(58).unwrap();
+
+        let mut message = Message::new(58, 1);
+        message.add_field("TradeDate".to_string(), FieldValue::U32(20241399));
+        message.add_field("Price".to_string(), FieldValue::I64(99_999_999_999_999));
+        message.add_field("Side".to_string(), FieldValue::from("9"));
+        message.add_field("Extra".to_string(), FieldValue::Bytes(vec![0xAB, 0xCD]));
+
+        let text = MessagePrinter::new(def).with_style(PrintStyle::SingleLine).format(&message);
+        assert_eq!(text, "MsgType=58|MsgName=NewOrderSingle|SeqNum=1|Side=9|Price=99999999999999|TradeDate=20241399|Extra=abcd|");
+
+        let text = MessagePrinter::new(def).format(&message);
+        assert!(text.contains("  Side      = 9  # 买卖方向"), "没有对应含义时只输出值: {}", text);
+        assert!(text.ends_with("  Extra     = abcd\n"), "{}", text);
+        assert!(config_manager.printer(999).is_err());
+
+        println!("✓ Raw and unknown values test passed");
+    }
+}
@@ -0,0 +1,311 @@
+use std::time::{Duration, Instant};
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+use sse_tdgw_binary::capture::Direction;
+use sse_tdgw_binary::codec::decoder::MessageDecoder;
+use sse_tdgw_binary::codec::encoder::MessageEncoder;
+use sse_tdgw_binary::codec::MessageHeader;
+use sse_tdgw_binary::config::manager::ConfigManager;
+use sse_tdgw_binary::message::{FieldValue, Message};
+use sse_tdgw_binary::replay::{ntime_of, Mismatch, Pace, Recording, Replayer, ResponseMismatch};
+use sse_tdgw_binary::util::ReplayError;
+use sse_tdgw_binary::wirelog::WireRecord;
+
+mod common;
+use common::heartbeat;
+
+/// 会话回放测试
+/// 测试回放节奏、序列号和 TransactTime 的重写、应答的逐字段比较以及 replay 子命令
+#[cfg(test)]
+mod replay_tests {
+    use super::*;
+
+    const CONFIG_XML: &str = r#"
+    <messages>
+        <message type="33" name="Heartbeat">
+        </message>
+        <message type="58" name="NewOrderSingle">
+            <field name="ClOrdID" type="char" length="10" desc="会员内部订单编号"/>
+            <field name="Price" type="price" desc="申报价格"/>
+            <field name="TransactTime" type="ntime" desc="申报时间"/>
+        </message>
+        <message type="32" name="ExecutionReport">
+            <field name="ClOrdID" type="char" length="10" desc="会员内部订单编号"/>
+            <field name="OrdStatus" type="char" length="1" desc="订单状态：0=新订单, 4=已撤销, 8=已拒绝"/>
+            <field name="TransactTime" type="ntime" desc="回报时间"/>
+        </message>
+    </messages>
+    "#;
+
+    fn create_test_config_manager() -> ConfigManager {
+        let mut config_manager = ConfigManager::new();
+        config_manager.load_from_str(CONFIG_XML).expect("Failed to load replay test config");
+        config_manager
+    }
+
+    fn order(config_manager: &ConfigManager, seq_num: u32, cl_ord_id: &str) -> Vec<u8> {
+        let mut message = Message::new(58, seq_num);
+        message.add_field("ClOrdID".to_string(), FieldValue::from(cl_ord_id));
+        message.add_field("Price".to_string(), FieldValue::Float(10.5));
+        message.add_field("TransactTime".to_string(), FieldValue::U64(930000000000));
+        MessageEncoder::new(config_manager).encode(&message).unwrap()
+    }
+
+    fn report(config_manager: &ConfigManager, seq_num: u32, cl_ord_id: &str, ord_status: &str) -> Vec<u8> {
+        report_at(config_manager, seq_num, cl_ord_id, ord_status, 930000100000)
+    }
+
+    fn report_at(config_manager: &ConfigManager, seq_num: u32, cl_ord_id: &str, ord_status: &str, transact_time: u64) -> Vec<u8> {
+        let mut message = Message::new(32, seq_num);
+        message.add_field("ClOrdID".to_string(), FieldValue::from(cl_ord_id));
+        message.add_field("OrdStatus".to_string(), FieldValue::from(ord_status));
+        message.add_field("TransactTime".to_string(), FieldValue::U64(transact_time));
+        MessageEncoder::new(config_manager).encode(&message).unwrap()
+    }
+
+    fn at(millis: u64) -> Duration {
+        Duration::from_secs(1_704_418_200) + Duration::from_millis(millis)
+    }
+
+    /// 两笔订单及其回报，中间夹一个网关的心跳
+    fn create_recording(config_manager: &ConfigManager) -> Recording {
+        Recording::new(vec![
+            WireRecord::new(at(0), Direction::ClientToServer, "S1", order(config_manager, 11, "ORD001")),
+            WireRecord::new(at(5), Direction::ServerToClient, "S1", report(config_manager, 21, "ORD001", "0")),
+            WireRecord::new(at(60), Direction::ServerToClient, "S1", heartbeat(config_manager, 22)),
+            WireRecord::new(at(100), Direction::ClientToServer, "S1", order(config_manager, 12, "ORD002")),
+            WireRecord::new(at(105), Direction::ServerToClient, "S1", report(config_manager, 23, "ORD002", "0")),
+        ])
+    }
+
+    fn decode(config_manager: &ConfigManager, frame: &[u8]) -> Message {
+        MessageDecoder::new(config_manager, frame).decode().expect("重写后的帧应能严格解码")
+    }
+
+    /// 测试回放节奏以及序列号和 TransactTime 的重写
+    #[test]
+    fn test_schedule_and_rewrite() {
+        let config_manager = create_test_config_manager();
+        let recording = create_recording(&config_manager);
+
+        let offsets = |pace| -> Vec<Duration> {
+            Replayer::new(&config_manager).with_pace(pace).schedule(&recording).iter().map(|(offset, _)| *offset).collect()
+        };
+        assert_eq!(offsets(Pace::Original), vec![Duration::ZERO, Duration::from_millis(100)]);
+        assert_eq!(offsets(Pace::Speed(4.0)), vec![Duration::ZERO, Duration::from_millis(25)]);
+        assert_eq!(offsets(Pace::AsFastAsPossible), vec![Duration::ZERO, Duration::ZERO]);
+        assert_eq!(offsets(Pace::Speed(1e-30)), vec![Duration::ZERO, Duration::MAX], "间隔超出范围时取最大值");
+        let server = Replayer::new(&config_manager).with_direction(Direction::ServerToClient).schedule(&recording);
+        assert_eq!(server.len(), 3, "回放网关一侧时发出网关的帧");
+        assert_eq!(server[2].0, Duration::from_millis(100));
+
+        // 只改序列号时保留原始字节，只更新头部和校验和
+        let original = &recording.records[3].frame;
+        let replayer = Replayer::new(&config_manager).rewrite_seq_num(100);
+        let rewritten = replayer.rewrite(1, original, at(0)).unwrap();
+        assert_eq!(MessageHeader::parse(&rewritten).unwrap().seq_num, 101);
+        assert_eq!(rewritten[12..rewritten.len() - 4], original[12..original.len() - 4]);
+        assert_eq!(decode(&config_manager, &rewritten).get_field("ClOrdID").and_then(FieldValue::as_str), Some("ORD002"));
+
+        // 2024-01-05 01:30:00.1234567 UTC 为北京时间 09:30:00.1234567
+        let now = Duration::new(1_704_418_200, 123_456_789);
+        assert_eq!(ntime_of(now), 930001234567);
+        let replayer = Replayer::new(&config_manager).rewrite_seq_num(1).rewrite_transact_time();
+        let message = decode(&config_manager, &replayer.rewrite(0, original, now).unwrap());
+        assert_eq!(message.seq_num, 1);
+        assert_eq!(message.get_field("TransactTime"), Some(&FieldValue::U64(930001234567)));
+        assert_eq!(message.get_field("Price"), Some(&FieldValue::Float(10.5)));
+
+        let rewritten = Replayer::new(&config_manager).rewrite_transact_time().rewrite(0, &heartbeat(&config_manager, 5), now).unwrap();
+        assert_eq!(rewritten, heartbeat(&config_manager, 5), "没有 TransactTime 的消息不变");
+        let error = Replayer::new(&config_manager).rewrite_transact_time().rewrite(3, &original[..10], now).unwrap_err();
+        assert!(error.to_string().starts_with("Failed to rewrite frame #3"), "{}", error);
+
+        // 只改序列号时同样先确认帧完整，截断的帧返回错误
+        let replayer = Replayer::new(&config_manager).rewrite_seq_num(1);
+        for truncated in [&original[..10], &original[..original.len() - 1]] {
+            let error = replayer.rewrite(2, truncated, now).unwrap_err();
+            assert!(matches!(error, ReplayError::Rewrite { index: 2, .. }), "{:?}", error);
+        }
+
+        println!("✓ Schedule and rewrite test passed");
+    }
+
+    /// 测试应答的比较
+    #[test]
+    fn test_compare() {
+        let config_manager = create_test_config_manager();
+        let expected = [
+            report(&config_manager, 1, "ORD001", "0"),
+            heartbeat(&config_manager, 2),
+            report(&config_manager, 3, "ORD002", "0"),
+            report(&config_manager, 4, "ORD003", "0"),
+        ];
+        let actual = [
+            report_at(&config_manager, 9, "ORD001", "0", 930009900000),
+            report(&config_manager, 10, "ORD002", "8"),
+            order(&config_manager, 11, "ORD003"),
+            report(&config_manager, 12, "ORD004", "0"),
+            vec![0, 0, 0, 99, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0],
+        ];
+        let expected: Vec<&[u8]> = expected.iter().map(Vec::as_slice).collect();
+        let actual: Vec<&[u8]> = actual.iter().map(Vec::as_slice).collect();
+
+        let replayer = Replayer::new(&config_manager).ignore_msg_type(33).ignore_field("TransactTime");
+        let mismatches = replayer.compare(&expected, &actual);
+        for mismatch in &mismatches {
+            println!("{}", mismatch);
+        }
+        assert_eq!(mismatches.len(), 4, "序列号和忽略的字段不参与比较");
+        assert_eq!((mismatches[0].expected, mismatches[0].actual), (Some(1), Some(1)));
+        assert!(
+            matches!(&mismatches[0].kind, Mismatch::Fields { msg_type: 32, diffs } if diffs.len() == 1 && diffs[0].path == "OrdStatus"),
+            "{:?}", mismatches[0],
+        );
+        assert_eq!(mismatches[0].to_string(), "response #1: message 32\n    OrdStatus: 0 -> 8");
+        assert!(matches!(&mismatches[1].kind, Mismatch::Unexpected(message) if message.msg_type == 58), "多出的订单不打乱之后的对齐");
+        assert_eq!(mismatches[1].to_string(), "received #2: unexpected 58 (seq 11)");
+        assert_eq!((mismatches[2].expected, mismatches[2].actual), (Some(2), Some(3)));
+        assert!(matches!(&mismatches[2].kind, Mismatch::Fields { diffs, .. } if diffs[0].path == "ClOrdID"));
+        assert!(matches!(&mismatches[3].kind, Mismatch::Undecodable { recorded: false, .. }));
+
+        let mismatches = Replayer::new(&config_manager).compare(&expected, &actual[..1]);
+        assert_eq!(mismatches.len(), 4);
+        assert!(matches!(&mismatches[0].kind, Mismatch::Fields { diffs, .. } if diffs[0].path == "TransactTime"));
+        assert!(matches!(&mismatches[3].kind, Mismatch::Missing(message) if message.seq_num == 4));
+
+        // 中间少收一个应答时只报告一处缺少
+        let dropped = [&actual[..1], &expected[3..]].concat();
+        let mismatches = replayer.compare(&expected, &dropped);
+        assert_eq!(mismatches.len(), 1, "{:?}", mismatches);
+        assert!(matches!(&mismatches[0].kind, Mismatch::Missing(message) if message.seq_num == 3));
+        assert_eq!(mismatches[0].to_string(), "response #1: missing 32 (seq 3)");
+
+        // 类型不同的应答出现在同一位置时报告为类型不符
+        let mismatches = replayer.compare(&expected[..1], &actual[2..3]);
+        assert!(matches!(&mismatches[..], [ResponseMismatch { kind: Mismatch::MsgType { .. }, .. }]), "{:?}", mismatches);
+
+        println!("✓ Compare test passed");
+    }
+
+    /// 测试向被测端回放并比较应答
+    #[tokio::test]
+    async fn test_replay() {
+        let config_manager = create_test_config_manager();
+        let recording = create_recording(&config_manager);
+        let (client, mut endpoint) = tokio::io::duplex(4096);
+
+        // 被测端对每笔订单回一个回报，第二笔订单的状态与录制不同
+        let responses = [report(&config_manager, 1, "ORD001", "0"), report(&config_manager, 2, "ORD002", "4")];
+        let frame_len = recording.records[0].frame.len();
+        let server = tokio::spawn(async move {
+            let mut received = Vec::new();
+            for response in responses {
+                let mut frame = vec![0u8; frame_len];
+                endpoint.read_exact(&mut frame).await.unwrap();
+                received.push((Instant::now(), frame));
+                endpoint.write_all(&response).await.unwrap();
+            }
+            received
+        });
+
+        let replayer = Replayer::new(&config_manager)
+            .rewrite_seq_num(1)
+            .ignore_msg_type(33)
+            .with_response_timeout(Duration::from_millis(50));
+        let report = replayer.replay(&recording, client).await.unwrap();
+        let received = server.await.unwrap();
+
+        assert_eq!(report.sent, 2);
+        assert_eq!(report.received.len(), 2);
+        let seq_nums: Vec<u32> = received.iter().map(|(_, frame)| MessageHeader::parse(frame).unwrap().seq_num).collect();
+        assert_eq!(seq_nums, vec![1, 2], "发出的帧应重新编号");
+        let gap = received[1].0 - received[0].0;
+        assert!(gap >= Duration::from_millis(90), "应保持录制时约 100ms 的间隔: {:?}", gap);
+        assert!(!report.is_match());
+        assert_eq!(report.mismatches.len(), 1);
+        assert_eq!(report.mismatches[0].to_string(), "response #1: message 32\n    OrdStatus: 0 -> 4");
+
+        // 第二笔订单无法解码时重写失败，被测端不关闭连接也应立即结束
+        let mut recording = create_recording(&config_manager);
+        let last = recording.records[3].frame.len() - 1;
+        recording.records[3].frame[last] ^= 0xFF;
+        let (client, _endpoint) = tokio::io::duplex(4096);
+        let replayer = Replayer::new(&config_manager)
+            .with_pace(Pace::AsFastAsPossible)
+            .rewrite_transact_time()
+            .with_response_timeout(Duration::from_millis(50));
+        let result = tokio::time::timeout(Duration::from_secs(5), replayer.replay(&recording, client))
+            .await
+            .expect("发送失败后回放应结束");
+        assert!(matches!(result, Err(ReplayError::Rewrite { index: 1, .. })), "{:?}", result.map(|report| report.sent));
+
+        println!("✓ Replay test passed");
+    }
+
+    /// 测试 replay 子命令回放报文日志
+    #[cfg(feature = "cli")]
+    #[test]
+    fn test_replay_command() {
+        use std::io::{Read, Write};
+        use std::net::TcpListener;
+        use std::process::Command;
+
+        use sse_tdgw_binary::wirelog::WireLogWriter;
+
+        let config_manager = create_test_config_manager();
+        let dir = std::env::temp_dir().join(format!("tdgw-replay-test-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        let mut writer = WireLogWriter::open(&dir, "tdgw").unwrap();
+        for record in &create_recording(&config_manager).records {
+            writer.append(record).unwrap();
+        }
+        let log = writer.current_path().unwrap();
+        drop(writer);
+        let config = dir.join("messages.xml");
+        std::fs::write(&config, CONFIG_XML).unwrap();
+
+        // 被测端按录制回报，但不发心跳
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap().to_string();
+        let responses = [report(&config_manager, 1, "ORD001", "0"), report(&config_manager, 2, "ORD002", "0")];
+        let frame_len = order(&config_manager, 1, "ORD001").len();
+        let server = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            for response in responses {
+                let mut frame = vec![0u8; frame_len];
+                stream.read_exact(&mut frame).unwrap();
+                stream.write_all(&response).unwrap();
+            }
+        });
+
+        let output = Command::new(env!("CARGO_BIN_EXE_tdgw"))
+            .args(["replay", "-c", config.to_str().unwrap(), log.to_str().unwrap(), "--to", &address, "--asap", "--timeout", "100"])
+            .args(["--ignore-field", "TransactTime"])
+            .output()
+            .expect("Failed to start tdgw");
+        server.join().unwrap();
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        println!("{}{}", stdout, String::from_utf8_lossy(&output.stderr));
+        assert_eq!(output.status.code(), Some(1), "缺少心跳时退出码应为 1");
+        assert!(stdout.contains("response #1: missing 33 (seq 22)"), "{}", stdout);
+        assert!(stdout.contains("sent 2 frames, received 2 frames, 1 mismatches"), "{}", stdout);
+
+        let output = Command::new(env!("CARGO_BIN_EXE_tdgw"))
+            .args(["replay", "-c", config.to_str().unwrap(), log.to_str().unwrap(), "--to", "127.0.0.1:1"])
+            .output()
+            .expect("Failed to start tdgw");
+        assert!(!output.status.success(), "无法连接时应失败");
+
+        let output = Command::new(env!("CARGO_BIN_EXE_tdgw"))
+            .args(["replay", "-c", config.to_str().unwrap(), log.to_str().unwrap(), "--to", &address, "--speed", "0"])
+            .output()
+            .expect("Failed to start tdgw");
+        assert!(!output.status.success(), "倍速不大于 0 时应报错");
+        assert!(String::from_utf8_lossy(&output.stderr).contains("speed must be a positive number"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+        println!("✓ Replay command test passed");
+    }
+}
@@ -0,0 +1,117 @@
+use std::sync::Arc;
+use std::thread;
+
+use sse_tdgw_binary::codec::{SharedDecoder, SharedEncoder};
+use sse_tdgw_binary::config::manager::ConfigManager;
+use sse_tdgw_binary::message::{FieldValue, Message};
+use sse_tdgw_binary::util::MessageError;
+
+/// 共享编解码器测试
+/// 测试持有 `Arc<ConfigManager>` 的编解码器在多线程和异步任务中使用
+#[cfg(test)]
+mod shared_codec_tests {
+    use super::*;
+
+    fn create_test_config_manager() -> Arc<ConfigManager> {
+        let mut config_manager = ConfigManager::new();
+        let config_xml = r#"
+        <messages>
+            <message type="33" name="Heartbeat">
+            </message>
+            <message type="58" name="NewOrderSingle">
+                <field name="BizID" type="u32" desc="业务代码"/>
+                <field name="ClOrdID" type="char" length="10" desc="订单编号"/>
+                <field name="Price" type="price" desc="价格"/>
+                <field name="OrderQty" type="quantity" desc="数量"/>
+            </message>
+        </messages>
+        "#;
+        config_manager.load_from_str(config_xml).expect("Failed to load shared codec test config");
+        Arc::new(config_manager)
+    }
+
+    fn create_order(seq_num: u32) -> Message {
+        let mut message = Message::new(58, seq_num);
+        message.add_field("BizID".to_string(), FieldValue::U32(300060));
+        message.add_field("ClOrdID".to_string(), FieldValue::from(format!("ORD{:03}", seq_num).as_str()));
+        message.add_field("Price".to_string(), FieldValue::Float(10.5));
+        message.add_field("OrderQty".to_string(), FieldValue::Float(100.0));
+        message
+    }
+
+    fn assert_send_sync<T: Send + Sync + 'static>() {}
+
+    /// 测试同一个实例编解码多个帧
+    #[test]
+    fn test_reuse_single_instance() {
+        assert_send_sync::<SharedEncoder>();
+        assert_send_sync::<SharedDecoder>();
+
+        let config_manager = create_test_config_manager();
+        let mut encoder = SharedEncoder::new(config_manager.clone());
+        let decoder = SharedDecoder::new(config_manager);
+
+        let frames: Vec<Vec<u8>> = (1..=3)
+            .map(|seq_num| encoder.encode(&create_order(seq_num)).unwrap().to_vec())
+            .collect();
+        for (index, frame) in frames.iter().enumerate() {
+            let message = decoder.decode(frame).expect("Failed to decode frame");
+            assert_eq!(message.seq_num, index as u32 + 1);
+            assert_eq!(message.get_str("ClOrdID").unwrap(), format!("ORD{:03}", index + 1));
+
+            let view = decoder.view(frame).expect("Failed to create view");
+            assert_eq!(view.get_f64("Price").unwrap(), 10.5);
+        }
+
+        // 不同长度的消息复用同一个缓冲区
+        let heartbeat = encoder.encode(&Message::new(33, 9)).unwrap();
+        assert_eq!(heartbeat.len(), 12 + 4, "复用缓冲区时不应残留上一帧的数据");
+
+        assert!(matches!(decoder.decode(&[0; 4]), Err(MessageError::HeaderTooShort)));
+        assert!(matches!(encoder.encode(&Message::new(999, 1)), Err(MessageError::UnknownMessageType(999))));
+
+        println!("✓ Reuse single instance test passed");
+    }
+
+    /// 测试在多个线程中共享解码器
+    #[test]
+    fn test_decode_across_threads() {
+        let config_manager = create_test_config_manager();
+        let decoder = Arc::new(SharedDecoder::new(config_manager.clone()));
+
+        let handles: Vec<_> = (0..4u32).map(|worker| {
+            let decoder = decoder.clone();
+            let mut encoder = SharedEncoder::new(config_manager.clone());
+            thread::spawn(move || {
+                for seq_num in worker * 100..worker * 100 + 100 {
+                    let frame = encoder.encode(&create_order(seq_num)).unwrap().to_vec();
+                    let message = decoder.decode(&frame).unwrap();
+                    assert_eq!(message.seq_num, seq_num);
+                }
+            })
+        }).collect();
+        for handle in handles {
+            handle.join().expect("Worker thread panicked");
+        }
+
+        println!("✓ Decode across threads test passed");
+    }
+
+    /// 测试在 tokio 任务中持有编解码器
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_use_in_tokio_task() {
+        let config_manager = create_test_config_manager();
+        let mut encoder = SharedEncoder::new(config_manager.clone());
+        let decoder = SharedDecoder::new(config_manager);
+
+        let task = tokio::spawn(async move {
+            let frame = encoder.encode(&create_order(7)).unwrap().to_vec();
+            tokio::task::yield_now().await;
+            decoder.decode(&frame).unwrap()
+        });
+        let message = task.await.expect("Task panicked");
+        assert_eq!(message.get_str("ClOrdID").unwrap(), "ORD007");
+
+        println!("✓ Use in tokio task test passed");
+    }
+}
@@ -0,0 +1,193 @@
+use sse_tdgw_binary::codec::encoder::MessageEncoder;
+use sse_tdgw_binary::codec::decoder::MessageDecoder;
+use sse_tdgw_binary::config::manager::ConfigManager;
+use sse_tdgw_binary::message::{FieldValue, Message, TdgwMessage};
+use sse_tdgw_binary::typed::{FixedStr, Price};
+use sse_tdgw_binary::util::MessageError;
+
+/// 派生宏测试
+/// 测试 `#[derive(TdgwMessage)]` 生成的转换和启动时的映射校验
+#[cfg(test)]
+mod tdgw_derive_tests {
+    use super::*;
+
+    /// 创建测试用的配置管理器
+    fn create_test_config_manager() -> ConfigManager {
+        let mut config_manager = ConfigManager::new();
+
+        let config_xml = r#"
+        <messages>
+            <message type="58" name="NewOrderSingle">
+                <field name="BizID" type="u32" desc="业务代码"/>
+                <field name="ClOrdID" type="char" length="10" desc="会员内部订单编号"/>
+                <field name="SecurityID" type="char" length="12" desc="证券代码"/>
+                <field name="Price" type="price" desc="申报价格"/>
+                <field name="OrderQty" type="quantity" desc="申报数量"/>
+                <field name="TransactTime" type="ntime" desc="申报时间"/>
+                <extension biz_id="300060">
+                    <field name="Custodian" type="char" length="3" desc="转托管的目标方代理人"/>
+                </extension>
+            </message>
+        </messages>
+        "#;
+
+        config_manager.load_from_str(config_xml).expect("Failed to load test config");
+        config_manager
+    }
+
+    /// 服务中已有的订单结构体
+    #[derive(Debug, Clone, PartialEq, TdgwMessage)]
+    #[tdgw(msg_type = 58)]
+    struct Order {
+        #[tdgw(field = "BizID")]
+        biz_id: u32,
+        #[tdgw(field = "ClOrdID")]
+        client_order_id: FixedStr<10>,
+        #[tdgw(field = "SecurityID")]
+        symbol: String,
+        price: Price,
+        order_qty: f64,
+        #[tdgw(field = "TransactTime")]
+        transact_time: u64,
+        #[tdgw(skip)]
+        strategy: String,
+    }
+
+    /// 带扩展字段的订单，字段名全部按成员名推导
+    #[derive(Debug, Clone, PartialEq, TdgwMessage)]
+    #[tdgw(msg_type = 58)]
+    struct TransferOrder {
+        biz_id: u32,
+        cl_ord_id: String,
+        security_id: String,
+        price: f64,
+        order_qty: f64,
+        transact_time: u64,
+        custodian: String,
+    }
+
+    /// 与配置不一致的映射
+    #[derive(Debug, TdgwMessage)]
+    #[tdgw(msg_type = 58)]
+    struct BrokenOrder {
+        #[tdgw(field = "ClOrdId")]
+        cl_ord_id: String,
+        #[tdgw(field = "Price")]
+        price: u32,
+        #[tdgw(field = "SecurityID")]
+        security_id: FixedStr<6>,
+    }
+
+    fn sample_order() -> Order {
+        Order {
+            biz_id: 100010,
+            client_order_id: "ORD001".into(),
+            symbol: "600000".to_string(),
+            price: Price::from_f64(10.5),
+            order_qty: 1000.0,
+            transact_time: 930000000000,
+            strategy: "twap".to_string(),
+        }
+    }
+
+    /// 测试结构体与消息相互转换并编解码
+    #[test]
+    fn test_struct_message_roundtrip() {
+        let config_manager = create_test_config_manager();
+        Order::validate(&config_manager).expect("Order mapping should be valid");
+
+        let order = sample_order();
+        let message = order.to_message(5);
+        assert_eq!(message.msg_type, 58);
+        assert_eq!(message.seq_num, 5);
+        assert_eq!(message.get_field("ClOrdID").unwrap(), &FieldValue::from("ORD001"));
+        assert_eq!(message.get_field("Price").unwrap(), &FieldValue::Float(10.5));
+        assert!(!message.has_field("Strategy"), "skip 的成员不应写入消息");
+
+        let mut encoder = MessageEncoder::new(&config_manager);
+        let encoded_data = encoder.encode(&message).expect("Failed to encode mapped message");
+        let mut decoder = MessageDecoder::new(&config_manager, &encoded_data);
+        let decoded_message = decoder.decode().expect("Failed to decode mapped message");
+
+        let decoded = Order::try_from(&decoded_message).expect("Failed to convert decoded message");
+        assert_eq!(decoded, Order { strategy: String::new(), ..order.clone() });
+
+        // From<&T> 使用序列号 0
+        assert_eq!(Message::from(&order).seq_num, 0);
+
+        println!("✓ Derive roundtrip test passed");
+    }
+
+    /// 测试扩展字段的映射
+    #[test]
+    fn test_extension_field_mapping() {
+        let config_manager = create_test_config_manager();
+        TransferOrder::validate(&config_manager).expect("Extension fields and inferred *ID fields should be accepted");
+
+        let order = TransferOrder {
+            biz_id: 300060,
+            cl_ord_id: "ORD002".to_string(),
+            security_id: "519001".to_string(),
+            price: 1.0,
+            order_qty: 100.0,
+            transact_time: 930000000000,
+            custodian: "001".to_string(),
+        };
+
+        let mut encoder = MessageEncoder::new(&config_manager);
+        let encoded_data = encoder.encode(&order.to_message(1)).unwrap();
+        let mut decoder = MessageDecoder::new(&config_manager, &encoded_data);
+        let decoded = TransferOrder::from_message(&decoder.decode().unwrap()).unwrap();
+        assert_eq!(decoded, order);
+
+        println!("✓ Extension field mapping test passed");
+    }
+
+    /// 测试启动时的映射校验
+    #[test]
+    fn test_validate_reports_all_problems() {
+        let config_manager = create_test_config_manager();
+
+        match BrokenOrder::validate(&config_manager) {
+            Err(MessageError::InvalidFieldMapping(problems)) => {
+                assert_eq!(problems.len(), 3, "{:?}", problems);
+                assert!(problems[0].contains("ClOrdId") && problems[0].contains("not defined"), "{}", problems[0]);
+                assert!(problems[1].contains("u32") && problems[1].contains("Price"), "{}", problems[1]);
+                assert!(problems[2].contains("length 6"), "{}", problems[2]);
+            },
+            other => panic!("Expected InvalidFieldMapping error, got: {:?}", other),
+        }
+
+        // 消息类型未加载
+        let empty = ConfigManager::new();
+        assert!(matches!(Order::validate(&empty), Err(MessageError::ConfigError(_))));
+
+        println!("✓ Mapping validation test passed");
+    }
+
+    /// 测试从消息转换时的错误
+    #[test]
+    fn test_from_message_errors() {
+        let message = sample_order().to_message(1);
+
+        let mut wrong_type = message.clone();
+        wrong_type.msg_type = 61;
+        assert!(matches!(Order::from_message(&wrong_type), Err(MessageError::UnexpectedMessageType(58, 61))));
+
+        let mut missing = Message::new(58, 1);
+        missing.add_field("BizID".to_string(), FieldValue::U32(100010));
+        assert!(matches!(
+            Order::from_message(&missing),
+            Err(MessageError::MissingRequiredFields(fields)) if fields == vec!["ClOrdID"]
+        ));
+
+        let mut mistyped = message;
+        mistyped.add_field("TransactTime".to_string(), FieldValue::from("093000"));
+        match Order::from_message(&mistyped) {
+            Err(MessageError::InvalidFieldValue(msg)) => assert!(msg.contains("TransactTime"), "{}", msg),
+            other => panic!("Expected InvalidFieldValue error, got: {:?}", other),
+        }
+
+        println!("✓ From message errors test passed");
+    }
+}
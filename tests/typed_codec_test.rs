@@ -0,0 +1,248 @@
+use sse_tdgw_binary::codec::encoder::MessageEncoder;
+use sse_tdgw_binary::codec::decoder::MessageDecoder;
+use sse_tdgw_binary::config::manager::ConfigManager;
+use sse_tdgw_binary::message::{Message, FieldValue, Record};
+use sse_tdgw_binary::typed::*;
+use sse_tdgw_binary::util::MessageError;
+
+/// 强类型消息测试
+/// 测试 build.rs 生成的消息结构体与动态编解码器逐字节一致
+#[cfg(test)]
+mod typed_codec_tests {
+    use super::*;
+
+    /// 加载生成强类型消息所用的同一份配置
+    fn create_test_config_manager() -> ConfigManager {
+        let mut config_manager = ConfigManager::new();
+        config_manager
+            .load_from_file(concat!(env!("CARGO_MANIFEST_DIR"), "/config/sse-message.xml"))
+            .expect("Failed to load config/sse-message.xml");
+        config_manager
+    }
+
+    fn encode_dynamic(config_manager: &ConfigManager, message: &Message) -> Vec<u8> {
+        let mut encoder = MessageEncoder::new(config_manager);
+        encoder.encode(message).expect("Failed to encode dynamic message")
+    }
+
+    fn encode_typed<T: TypedMessage>(message: &T, seq_num: u32) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        message.encode_into(seq_num, &mut buffer).expect("Failed to encode typed message");
+        assert_eq!(buffer.len(), message.encoded_len(), "encoded_len 与实际长度不一致");
+        buffer
+    }
+
+    /// 测试登录消息与动态编码器逐字节一致
+    #[test]
+    fn test_logon_matches_dynamic_codec() {
+        let config_manager = create_test_config_manager();
+
+        let logon = Logon {
+            sender_comp_id: "SENDER".into(),
+            target_comp_id: "TARGET".into(),
+            heart_bt_int: 30,
+            prtc_version: "1.0".into(),
+            trade_date: Date(20231201),
+            q_size: 1000,
+        };
+
+        let mut message = Message::new(40, 1);
+        message.add_field("SenderCompID".to_string(), FieldValue::from("SENDER"));
+        message.add_field("TargetCompID".to_string(), FieldValue::from("TARGET"));
+        message.add_field("HeartBtInt".to_string(), FieldValue::U16(30));
+        message.add_field("PrtcVersion".to_string(), FieldValue::from("1.0"));
+        message.add_field("TradeDate".to_string(), FieldValue::U32(20231201));
+        message.add_field("QSize".to_string(), FieldValue::U32(1000));
+
+        let typed_bytes = encode_typed(&logon, 1);
+        assert_eq!(typed_bytes, encode_dynamic(&config_manager, &message), "强类型编码结果应与动态编码一致");
+
+        let (header, decoded) = Logon::decode_from(&typed_bytes).expect("Failed to decode typed Logon");
+        assert_eq!(header.msg_type, Logon::MSG_TYPE);
+        assert_eq!(header.seq_num, 1);
+        assert_eq!(decoded, logon);
+        assert_eq!(decoded.sender_comp_id.as_str(), "SENDER");
+
+        // 超长的多字节字符串两边都在字符边界处截断
+        let long_name = "上海证券交易所".repeat(2);
+        let logon = Logon { sender_comp_id: long_name.as_str().into(), ..logon };
+        message.add_field("SenderCompID".to_string(), FieldValue::from(long_name.as_str()));
+        let typed_bytes = encode_typed(&logon, 1);
+        assert_eq!(typed_bytes, encode_dynamic(&config_manager, &message), "超长字符串的截断方式应一致");
+        let decoded = MessageDecoder::new(&config_manager, &typed_bytes).decode().expect("截断后应仍是合法的 UTF-8");
+        assert_eq!(decoded.get_field("SenderCompID").and_then(FieldValue::as_str), Some("上海证券交易所上海证"));
+
+        println!("✓ Typed Logon test passed");
+    }
+
+    /// 测试带扩展字段的订单与动态编码器一致
+    #[test]
+    fn test_extension_matches_dynamic_codec() {
+        let config_manager = create_test_config_manager();
+
+        let order = NewOrderSingle {
+            biz_id: 300060,
+            biz_pbu: "PBU01".into(),
+            cl_ord_id: "ORD001".into(),
+            security_id: "600000".into(),
+            account: "A123456789".into(),
+            owner_type: 1,
+            side: "1".into(),
+            price: Price::from_f64(10.5),
+            order_qty: Quantity::from_f64(1000.0),
+            ord_type: "2".into(),
+            time_in_force: "0".into(),
+            transact_time: NTime(930000000000),
+            credit_tag: "XY".into(),
+            clearing_firm: "CF001".into(),
+            branch_id: "BR001".into(),
+            user_info: "USER".into(),
+            extension: NewOrderSingleExtension::Biz300060 { custodian: "001".into() },
+        };
+
+        let typed_bytes = encode_typed(&order, 7);
+
+        // 动态解码强类型编码的结果
+        let mut decoder = MessageDecoder::new(&config_manager, &typed_bytes);
+        let message = decoder.decode().expect("Failed to decode typed bytes dynamically");
+        assert_eq!(message.get_field("Custodian").unwrap().as_str().unwrap(), "001");
+        assert_eq!(message.get_field("Price").unwrap().as_f64().unwrap(), 10.5);
+        assert_eq!(encode_dynamic(&config_manager, &message), typed_bytes, "动态重新编码应得到相同字节");
+
+        let (_, decoded) = NewOrderSingle::decode_from(&typed_bytes).unwrap();
+        assert_eq!(decoded, order);
+        assert_eq!(decoded.extension.biz_id(), Some(300060));
+
+        // 扩展变体与 BizID 不一致时报错，且不修改缓冲区
+        let mut buffer = vec![0xAA];
+        let mismatched = NewOrderSingle { biz_id: 300070, ..order.clone() };
+        assert!(matches!(mismatched.encode_into(1, &mut buffer), Err(MessageError::InvalidFieldValue(_))));
+        let missing = NewOrderSingle { extension: NewOrderSingleExtension::None, ..order };
+        assert!(matches!(missing.encode_into(1, &mut buffer), Err(MessageError::UnknownBizExtension(300060))));
+        assert_eq!(buffer, vec![0xAA]);
+
+        println!("✓ Typed extension test passed");
+    }
+
+    /// 测试数组消息与动态编码器一致
+    #[test]
+    fn test_array_matches_dynamic_codec() {
+        let config_manager = create_test_config_manager();
+
+        let response = ExecRptSyncRsp {
+            sync_responses: vec![
+                ExecRptSyncRspSyncResponsesEntry {
+                    pbu: "PBU001".into(),
+                    set_id: 1,
+                    begin_report_index: 1,
+                    end_report_index: 100,
+                    rej_reason: 0,
+                    text: "OK".into(),
+                },
+                ExecRptSyncRspSyncResponsesEntry {
+                    pbu: "PBU002".into(),
+                    set_id: 2,
+                    begin_report_index: 5,
+                    end_report_index: 5,
+                    rej_reason: 1001,
+                    text: "Rejected".into(),
+                },
+            ],
+        };
+
+        let mut message = Message::new(207, 3);
+        message.add_field("SyncResponses".to_string(), FieldValue::Array(vec![
+            Record::new()
+                .with("Pbu", "PBU001").with("SetID", 1u32).with("BeginReportIndex", 1u64)
+                .with("EndReportIndex", 100u64).with("RejReason", 0u32).with("Text", "OK"),
+            Record::new()
+                .with("Pbu", "PBU002").with("SetID", 2u32).with("BeginReportIndex", 5u64)
+                .with("EndReportIndex", 5u64).with("RejReason", 1001u32).with("Text", "Rejected"),
+        ]));
+
+        let typed_bytes = encode_typed(&response, 3);
+        assert_eq!(typed_bytes, encode_dynamic(&config_manager, &message));
+
+        let (_, decoded) = ExecRptSyncRsp::decode_from(&typed_bytes).unwrap();
+        assert_eq!(decoded, response);
+
+        // 空数组
+        let empty = ExecRptSyncRsp::default();
+        let mut empty_message = Message::new(207, 3);
+        empty_message.add_field("SyncResponses".to_string(), FieldValue::Array(Vec::new()));
+        assert_eq!(encode_typed(&empty, 3), encode_dynamic(&config_manager, &empty_message));
+
+        println!("✓ Typed array test passed");
+    }
+
+    /// 测试解码动态编码的执行报告
+    #[test]
+    fn test_decode_dynamic_execution_report() {
+        let config_manager = create_test_config_manager();
+
+        let message = config_manager.builder("ExecutionReport").unwrap()
+            .seq_num(9)
+            .set("Pbu", "PBU001").unwrap()
+            .set("SetID", 1u32).unwrap()
+            .set("ReportIndex", 42u64).unwrap()
+            .set("BizID", 300070u32).unwrap()
+            .set("ExecType", "0").unwrap()
+            .set("BizPbu", "PBU01").unwrap()
+            .set("ClOrdID", "ORD001").unwrap()
+            .set("SecurityID", "600000").unwrap()
+            .set("Account", "A123456789").unwrap()
+            .set("OwnerType", 1u8).unwrap()
+            .set("Side", "1").unwrap()
+            .set("Price", 12.345).unwrap()
+            .set("OrderQty", 1000.0).unwrap()
+            .set("LeavesQty", 1000.0).unwrap()
+            .set("CxlQty", 0.0).unwrap()
+            .set("OrdType", "2").unwrap()
+            .set("TimeInForce", "0").unwrap()
+            .set("OrdStatus", "0").unwrap()
+            .set("CreditTag", "XY").unwrap()
+            .set("OrigClOrdID", "").unwrap()
+            .set("ClearingFirm", "CF001").unwrap()
+            .set("BranchID", "BR001").unwrap()
+            .set("OrdRejReason", 0u32).unwrap()
+            .set("OrdCnfmID", "CNF0001").unwrap()
+            .set("OrigOrdCnfmID", "").unwrap()
+            .set("TradeDate", 20231201u32).unwrap()
+            .set("TransactTime", 930000000000u64).unwrap()
+            .set("UserInfo", "USER").unwrap()
+            .set("DividendSelect", "U").unwrap()
+            .build()
+            .unwrap();
+        let dynamic_bytes = encode_dynamic(&config_manager, &message);
+
+        let (header, report) = ExecutionReport::decode_from(&dynamic_bytes).expect("Failed to decode ExecutionReport");
+        assert_eq!(header.seq_num, 9);
+        assert_eq!(report.report_index, 42);
+        assert_eq!(report.price.to_f64(), 12.345);
+        assert_eq!(report.ord_cnfm_id.as_str(), "CNF0001");
+        assert_eq!(report.extension, ExecutionReportExtension::Biz300070 { dividend_select: "U".into() });
+        assert_eq!(encode_typed(&report, 9), dynamic_bytes);
+
+        println!("✓ Typed ExecutionReport decode test passed");
+    }
+
+    /// 测试消息类型、校验和与字段校验错误
+    #[test]
+    fn test_typed_decode_errors() {
+        let mut bytes = encode_typed(&Heartbeat {}, 1);
+        assert_eq!(bytes.len(), 16);
+
+        assert!(matches!(Logout::decode_from(&bytes), Err(MessageError::UnexpectedMessageType(41, 33))));
+        assert!(matches!(Heartbeat::decode_from(&bytes[..8]), Err(MessageError::HeaderTooShort)));
+
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0xFF;
+        assert!(matches!(Heartbeat::decode_from(&bytes), Err(MessageError::ChecksumError)));
+
+        // 日期格式与动态编码器使用相同规则校验
+        let invalid_date = Logon { trade_date: Date(20231301), ..Default::default() };
+        assert!(matches!(invalid_date.encode_into(1, &mut Vec::new()), Err(MessageError::InvalidFieldValue(_))));
+
+        println!("✓ Typed decode errors test passed");
+    }
+}
@@ -0,0 +1,291 @@
+use std::fs::{self, OpenOptions};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+use sse_tdgw_binary::capture::Direction;
+use sse_tdgw_binary::codec::encoder::MessageEncoder;
+use sse_tdgw_binary::config::manager::ConfigManager;
+use sse_tdgw_binary::message::{FieldValue, Message};
+use sse_tdgw_binary::util::WireLogError;
+use sse_tdgw_binary::wirelog::{log_files, log_path, trade_date_of, WireLogReader, WireLogTap, WireLogWriter, WireRecord};
+
+mod common;
+use common::{heartbeat, load_config};
+
+/// 报文日志测试
+/// 测试 WireLog 的写入、按交易日切换文件、遍历、按时间和序列号定位、损坏记录的处理以及挂在连接上记录收发的帧
+#[cfg(test)]
+mod wirelog_tests {
+    use super::*;
+
+    /// 2024-01-05 15:00:00 UTC，即北京时间 2024-01-05 23:00:00
+    const EVENING: u64 = 1_704_466_800;
+
+    fn logout(config_manager: &ConfigManager, seq_num: u32) -> Vec<u8> {
+        let mut message = Message::new(41, seq_num);
+        message.add_field("SessionStatus".to_string(), FieldValue::U32(0));
+        message.add_field("Text".to_string(), FieldValue::from("bye"));
+        MessageEncoder::new(config_manager).encode(&message).unwrap()
+    }
+
+    fn logon(config_manager: &ConfigManager, seq_num: u32, trade_date: u32) -> Vec<u8> {
+        let mut message = Message::new(40, seq_num);
+        message.add_field("SenderCompID".to_string(), FieldValue::from("S1"));
+        message.add_field("TargetCompID".to_string(), FieldValue::from("SSE"));
+        message.add_field("HeartBtInt".to_string(), FieldValue::U16(30));
+        message.add_field("PrtcVersion".to_string(), FieldValue::from("1.0"));
+        message.add_field("TradeDate".to_string(), FieldValue::U32(trade_date));
+        message.add_field("QSize".to_string(), FieldValue::U32(1000));
+        MessageEncoder::new(config_manager).encode(&message).unwrap()
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("tdgw-wirelog-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    fn at(seconds: u64) -> Duration {
+        Duration::from_secs(EVENING + seconds)
+    }
+
+    /// 两个会话在一个交易日内交替收发，跨过北京时间零点后的记录写入下一个交易日的文件
+    fn write_sample(config_manager: &ConfigManager, dir: &PathBuf) -> Vec<WireRecord> {
+        let records = vec![
+            WireRecord::new(at(0), Direction::ClientToServer, "S1", heartbeat(config_manager, 1)),
+            WireRecord::new(at(1), Direction::ServerToClient, "S1", heartbeat(config_manager, 1)),
+            WireRecord::new(at(2), Direction::ClientToServer, "S2", heartbeat(config_manager, 1)),
+            WireRecord::new(at(3), Direction::ClientToServer, "S1", heartbeat(config_manager, 2)),
+            WireRecord::new(at(4), Direction::ServerToClient, "S1", logout(config_manager, 2)),
+            WireRecord::new(at(3600), Direction::ClientToServer, "S1", heartbeat(config_manager, 1)),
+        ];
+        let mut writer = WireLogWriter::open(dir, "tdgw").unwrap();
+        for record in &records {
+            writer.append(record).unwrap();
+        }
+        writer.flush().unwrap();
+        records
+    }
+
+    /// 测试写入、按交易日切换文件和遍历
+    #[test]
+    fn test_write_and_read() {
+        let config_manager = load_config();
+        let dir = temp_dir("read");
+        let records = write_sample(&config_manager, &dir);
+        assert_eq!(trade_date_of(at(0)), 20240105);
+        assert_eq!(trade_date_of(at(3600)), 20240106, "北京时间零点之后属于下一个交易日");
+
+        let files = log_files(&dir, "tdgw").unwrap();
+        assert_eq!(files, vec![
+            (20240105, log_path(&dir, "tdgw", 20240105)),
+            (20240106, log_path(&dir, "tdgw", 20240106)),
+        ]);
+
+        let reader = WireLogReader::open(&files[0].1).unwrap();
+        assert_eq!(reader.trade_date(), 20240105);
+        let read: Vec<WireRecord> = reader.collect::<Result<_, _>>().unwrap();
+        assert_eq!(read, records[..5]);
+        assert_eq!(read[4].seq_num, 2, "序列号应取自帧头部");
+        assert_eq!(read[4].msg_type(), Some(41));
+        let message = read[4].decode(&config_manager).unwrap();
+        assert_eq!(message.get_field("Text").and_then(FieldValue::as_str), Some("bye"));
+
+        let read: Vec<WireRecord> = WireLogReader::open(&files[1].1).unwrap().collect::<Result<_, _>>().unwrap();
+        assert_eq!(read, records[5..]);
+
+        fs::remove_dir_all(&dir).unwrap();
+        println!("✓ Write and read test passed");
+    }
+
+    /// 测试按时间和序列号定位
+    #[test]
+    fn test_seek() {
+        let config_manager = load_config();
+        let dir = temp_dir("seek");
+        let records = write_sample(&config_manager, &dir);
+        let mut reader = WireLogReader::open(log_path(&dir, "tdgw", 20240105)).unwrap();
+
+        reader.seek_time(at(2)).unwrap();
+        assert_eq!(reader.next().unwrap().unwrap(), records[2]);
+        assert_eq!(reader.next().unwrap().unwrap(), records[3], "定位之后应继续顺序读取");
+
+        assert!(reader.seek_seq_num(None, Direction::ServerToClient, 2).unwrap());
+        assert_eq!(reader.next().unwrap().unwrap(), records[4]);
+        assert!(reader.seek_seq_num(Some("S2"), Direction::ClientToServer, 1).unwrap());
+        assert_eq!(reader.next().unwrap().unwrap(), records[2]);
+        assert!(reader.seek_seq_num(Some("S1"), Direction::ClientToServer, 2).unwrap());
+        assert_eq!(reader.next().unwrap().unwrap(), records[3]);
+
+        assert!(!reader.seek_seq_num(Some("S2"), Direction::ClientToServer, 2).unwrap(), "找不到时应返回 false");
+        assert!(reader.next().is_none(), "找不到时应定位到末尾");
+        reader.seek_time(at(100)).unwrap();
+        assert!(reader.next().is_none());
+
+        reader.rewind().unwrap();
+        assert_eq!(reader.count(), 5);
+
+        fs::remove_dir_all(&dir).unwrap();
+        println!("✓ Seek test passed");
+    }
+
+    /// 测试写了一半的记录、损坏的记录以及固定交易日
+    #[test]
+    fn test_truncated_and_corrupted() {
+        let config_manager = load_config();
+        let dir = temp_dir("corrupt");
+        let records = write_sample(&config_manager, &dir);
+        let path = log_path(&dir, "tdgw", 20240105);
+
+        // 模拟写记录时进程退出
+        let len = fs::metadata(&path).unwrap().len();
+        OpenOptions::new().write(true).open(&path).unwrap().set_len(len - 3).unwrap();
+        let read: Vec<_> = WireLogReader::open(&path).unwrap().collect();
+        assert_eq!(read.len(), 5);
+        assert!(matches!(read[4], Err(WireLogError::Truncated(_))), "{:?}", read[4]);
+        let mut reader = WireLogReader::open(&path).unwrap();
+        assert!(!reader.seek_seq_num(None, Direction::ServerToClient, 2).unwrap(), "不完整的记录不参与定位");
+
+        // 重新打开时截掉不完整的记录后继续追加
+        let mut writer = WireLogWriter::open(&dir, "tdgw").unwrap();
+        writer.append(&records[4]).unwrap();
+        drop(writer);
+        let read: Vec<WireRecord> = WireLogReader::open(&path).unwrap().collect::<Result<_, _>>().unwrap();
+        assert_eq!(read, records[..5]);
+
+        // 固定交易日之后不再按时间切换文件
+        let mut writer = WireLogWriter::open(&dir, "tdgw").unwrap();
+        writer.set_trade_date(Some(20240105));
+        writer.append(&records[5]).unwrap();
+        assert_eq!(writer.current_path(), Some(path.clone()));
+        drop(writer);
+        assert_eq!(WireLogReader::open(&path).unwrap().count(), 6);
+
+        // 断电后末尾补零的部分同样截掉
+        let mut bytes = fs::read(&path).unwrap();
+        bytes.extend_from_slice(&[0; 64]);
+        fs::write(&path, &bytes).unwrap();
+        let mut writer = WireLogWriter::open(&dir, "tdgw").unwrap();
+        writer.append(&records[0]).unwrap();
+        drop(writer);
+        let read: Vec<WireRecord> = WireLogReader::open(&path).unwrap().collect::<Result<_, _>>().unwrap();
+        assert_eq!(read.len(), 7);
+        assert_eq!(read[6], records[0]);
+
+        let mut bytes = fs::read(&path).unwrap();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0xff;
+        fs::write(&path, &bytes).unwrap();
+        let read: Vec<_> = WireLogReader::open(&path).unwrap().collect();
+        assert!(matches!(read.last(), Some(Err(WireLogError::Corrupted { .. }))), "末尾长度不一致应报告损坏: {:?}", read.last());
+        let mut reader = WireLogReader::open(&path).unwrap();
+        assert_eq!(reader.next().unwrap().unwrap(), records[0]);
+        let result = reader.seek_seq_num(None, Direction::ServerToClient, 2);
+        assert!(matches!(result, Err(WireLogError::Corrupted { .. })), "{:?}", result);
+        assert_eq!(reader.next().unwrap().unwrap(), records[1], "定位失败后应从原来的位置继续读取");
+
+        bytes[0] = b'X';
+        fs::write(&path, &bytes).unwrap();
+        assert!(matches!(WireLogReader::open(&path), Err(WireLogError::InvalidHeader(_))));
+        assert!(WireLogWriter::open(&dir, "tdgw").unwrap().append(&records[0]).is_err(), "文件头无效时不应追加");
+
+        fs::remove_dir_all(&dir).unwrap();
+        println!("✓ Truncated and corrupted test passed");
+    }
+
+    /// 测试登录消息中的 TradeDate 决定之后记录所属的交易日
+    #[test]
+    fn test_logon_trade_date() {
+        let config_manager = load_config();
+        let dir = temp_dir("logon");
+        let logon = WireRecord::new(at(3601), Direction::ServerToClient, "S1", logon(&config_manager, 1, 20240108));
+        assert_eq!(logon.logon_trade_date(), Some(20240108));
+        assert_eq!(WireRecord::new(at(0), Direction::ClientToServer, "S1", heartbeat(&config_manager, 1)).logon_trade_date(), None);
+
+        // 周五夜间登录下一个交易日，零点前后的记录都归入登录消息中的交易日
+        let mut writer = WireLogWriter::open(&dir, "tdgw").unwrap();
+        writer.append(&WireRecord::new(at(3600), Direction::ClientToServer, "S1", heartbeat(&config_manager, 1))).unwrap();
+        writer.append(&logon).unwrap();
+        writer.append(&WireRecord::new(at(3602), Direction::ClientToServer, "S1", heartbeat(&config_manager, 2))).unwrap();
+        assert_eq!(writer.current_path(), Some(log_path(&dir, "tdgw", 20240108)));
+        drop(writer);
+
+        let files: Vec<u32> = log_files(&dir, "tdgw").unwrap().into_iter().map(|(trade_date, _)| trade_date).collect();
+        assert_eq!(files, vec![20240106, 20240108], "登录之前按北京时间日期，登录之后按 TradeDate");
+        assert_eq!(WireLogReader::open(log_path(&dir, "tdgw", 20240108)).unwrap().count(), 2);
+
+        fs::remove_dir_all(&dir).unwrap();
+        println!("✓ Logon trade date test passed");
+    }
+
+    /// 测试挂在连接上记录收发的帧，以及 wirelog 子命令
+    #[tokio::test]
+    async fn test_tap() {
+        let config_manager = load_config();
+        let dir = temp_dir("tap");
+        let log = WireLogWriter::open(&dir, "tdgw").unwrap().spawn().unwrap();
+        let (client, mut server) = tokio::io::duplex(1024);
+        let mut client = WireLogTap::client(client, log.clone(), "S1");
+
+        // 一帧分两次写出，另一帧分两次读入
+        let logon = logon(&config_manager, 1, 20240105);
+        let frame = heartbeat(&config_manager, 7);
+        client.write_all(&logon).await.unwrap();
+        client.write_all(&frame[..5]).await.unwrap();
+        client.write_all(&frame[5..]).await.unwrap();
+        let reply = logout(&config_manager, 8);
+        server.write_all(&reply).await.unwrap();
+        let mut received = vec![0u8; reply.len()];
+        client.read_exact(&mut received[..10]).await.unwrap();
+        client.read_exact(&mut received[10..]).await.unwrap();
+        assert_eq!(received, reply, "应透明地转发读写");
+        client.flush().await.unwrap();
+        let mut sent = vec![0u8; logon.len() + frame.len()];
+        server.read_exact(&mut sent).await.unwrap();
+        assert_eq!(sent, [logon.clone(), frame.clone()].concat());
+
+        let writer = log.close().unwrap();
+        assert!(log.take_error().is_none());
+        let path = writer.current_path().unwrap();
+        assert_eq!(path, log_path(&dir, "tdgw", 20240105), "应按登录消息中的 TradeDate 选择文件");
+        let read: Vec<WireRecord> = WireLogReader::open(&path).unwrap().collect::<Result<_, _>>().unwrap();
+        let summary: Vec<_> = read.iter().map(|record| (record.direction, record.session_id.as_str(), record.seq_num, record.frame.clone())).collect();
+        assert_eq!(summary, vec![
+            (Direction::ClientToServer, "S1", 1, logon.clone()),
+            (Direction::ClientToServer, "S1", 7, frame),
+            (Direction::ServerToClient, "S1", 8, reply),
+        ]);
+
+        #[cfg(feature = "cli")]
+        {
+            use std::process::Command;
+
+            let output = Command::new(env!("CARGO_BIN_EXE_tdgw"))
+                .args(["wirelog", "-c", common::CONFIG, path.to_str().unwrap(), "--direction", "server", "--seq-num", "8"])
+                .output()
+                .expect("Failed to start tdgw");
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+            assert_eq!(stdout.lines().count(), 1, "{}", stdout);
+            assert!(stdout.contains(" S1 <- MsgType=41|MsgName=Logout|SeqNum=8|"), "{}", stdout);
+        }
+
+        // 写日志失败不影响连接，错误留给句柄取出
+        fs::write(&path, b"not a wire log file").unwrap();
+        let log = WireLogWriter::open(&dir, "tdgw").unwrap().spawn().unwrap();
+        let (client, mut server) = tokio::io::duplex(1024);
+        let mut client = WireLogTap::client(client, log.clone(), "S1");
+        client.write_all(&logon).await.unwrap();
+        let mut sent = vec![0u8; logon.len()];
+        server.read_exact(&mut sent).await.unwrap();
+        assert_eq!(sent, logon);
+        log.flush().unwrap();
+        assert!(matches!(log.take_error(), Some(WireLogError::InvalidHeader(_))));
+        assert!(log.take_error().is_none(), "错误只取出一次");
+
+        fs::remove_dir_all(&dir).unwrap();
+        println!("✓ Tap test passed");
+    }
+}